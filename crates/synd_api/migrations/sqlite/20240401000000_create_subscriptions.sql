@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS subscriptions (
+    user_id    TEXT    NOT NULL,
+    url        TEXT    NOT NULL,
+    title      TEXT,
+    folder     TEXT,
+    -- JSON array of tags
+    tags       TEXT    NOT NULL DEFAULT '[]',
+    created_at TEXT,
+    paused     INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (user_id, url)
+);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS archived_entries (
+    feed_url    TEXT NOT NULL,
+    entry_id    TEXT NOT NULL,
+    title       TEXT,
+    summary     TEXT,
+    website_url TEXT,
+    published   TEXT,
+    updated     TEXT,
+    archived_at TEXT NOT NULL,
+    PRIMARY KEY (feed_url, entry_id)
+);
@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS disabled_users (
+    user_id     TEXT NOT NULL PRIMARY KEY,
+    disabled_at TEXT NOT NULL
+);
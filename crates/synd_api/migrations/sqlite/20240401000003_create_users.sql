@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS users (
+    id         TEXT NOT NULL PRIMARY KEY,
+    email      TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS users_email ON users (email);
+
+CREATE TABLE IF NOT EXISTS identities (
+    provider TEXT NOT NULL,
+    subject  TEXT NOT NULL,
+    user_id  TEXT NOT NULL,
+    PRIMARY KEY (provider, subject)
+);
+CREATE INDEX IF NOT EXISTS identities_user_id ON identities (user_id);
@@ -0,0 +1 @@
+ALTER TABLE archived_entries ADD COLUMN content TEXT;
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS deletion_tokens (
+    user_id    TEXT NOT NULL PRIMARY KEY,
+    hash       TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
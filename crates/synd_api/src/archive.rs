@@ -0,0 +1,81 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use synd_feed::{
+    feed::parser::{FetchFeed, FetchFeedResult},
+    types::{Feed, Time},
+};
+
+use crate::repository::{types::ArchivedEntry, EntryArchiveRepository};
+
+/// Return the time before which entries are purged from the archive
+pub fn retain_since(retention: Duration, now: Time) -> Time {
+    chrono::Duration::from_std(retention)
+        .ok()
+        .and_then(|retention| now.checked_sub_signed(retention))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+/// `FetchFeed` which merges the entries of fetched feeds into the archive.
+/// Archive is updated only when feeds are actually fetched, so reading entries does not write
+#[derive(Clone)]
+pub struct ArchiveFeedService<S> {
+    service: S,
+    repository: Arc<dyn EntryArchiveRepository>,
+    retention: Duration,
+}
+
+impl<S> ArchiveFeedService<S> {
+    pub fn new(
+        service: S,
+        repository: Arc<dyn EntryArchiveRepository>,
+        retention: Duration,
+    ) -> Self {
+        Self {
+            service,
+            repository,
+            retention,
+        }
+    }
+
+    /// Archive entries of the feed requested with the url.
+    /// Failure is only logged so that it does not affect fetching the feed
+    async fn archive(&self, url: &str, feed: &Feed) {
+        let now = Utc::now();
+        let meta = feed.meta();
+        let entries = feed
+            .entries()
+            .map(|entry| ArchivedEntry::new(url, meta, entry, now))
+            .collect();
+
+        if let Err(err) = self
+            .repository
+            .archive_entries(url, entries, retain_since(self.retention, now))
+            .await
+        {
+            tracing::warn!(feed_url = url, "Failed to archive entries {err:?}");
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FetchFeed for ArchiveFeedService<S>
+where
+    S: FetchFeed,
+{
+    async fn fetch_feed(&self, url: String) -> FetchFeedResult<Feed> {
+        let feed = self.service.fetch_feed(url.clone()).await?;
+        self.archive(&url, &feed).await;
+        Ok(feed)
+    }
+
+    async fn fetch_feeds_parallel(&self, urls: &[String]) -> FetchFeedResult<Vec<Feed>> {
+        let feeds = self.service.fetch_feeds_parallel(urls).await?;
+        // Feeds are returned in the order of urls
+        for (url, feed) in urls.iter().zip(&feeds) {
+            self.archive(url, feed).await;
+        }
+        Ok(feeds)
+    }
+}
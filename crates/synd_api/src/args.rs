@@ -1,17 +1,53 @@
-use std::{net::IpAddr, path::PathBuf, str::FromStr, time::Duration};
+use std::{
+    collections::HashSet,
+    ffi::OsString,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
 
-use clap::{ArgAction, Parser};
+use anyhow::Context as _;
+use clap::{
+    error::ErrorKind, ArgAction, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum,
+};
+use synd_auth::jwt::oidc::{ClaimMapping, OidcConfig};
 
 use crate::{
     config::{self, env::env_key},
-    serve,
+    config_file::ConfigFile,
+    repository::{
+        kvsd::{KvsdConfig, PoolConfig},
+        types::Identity,
+        RepositoryConfig,
+    },
+    serve::{
+        self,
+        auth::Admin,
+        layer::{
+            client_ip::{IpNetwork, TrustedProxies},
+            rate_limit::RateLimit,
+        },
+    },
+    usecase::authorize::{ProUser, Quota, QuotaPolicy},
 };
 
 #[derive(Parser, Debug)]
-#[command(version, propagate_version = true, disable_help_subcommand = true)]
+#[command(
+    version,
+    propagate_version = true,
+    disable_help_subcommand = true,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Config file path. Settings in the file are overridden by environment variables and flags
+    #[arg(long, env = env_key!("CONFIG"), value_name = "PATH")]
+    pub config: Option<PathBuf>,
     #[command(flatten)]
-    pub kvsd: KvsdOptions,
+    pub repository: RepositoryOptions,
     #[command(flatten)]
     pub bind: BindOptions,
     #[command(flatten)]
@@ -19,29 +55,157 @@ pub struct Args {
     #[command(flatten)]
     pub tls: TlsOptions,
     #[command(flatten)]
+    pub feed: FeedOptions,
+    #[command(flatten)]
+    pub search: SearchOptions,
+    #[command(flatten)]
+    pub archive: ArchiveOptions,
+    #[command(flatten)]
+    pub realtime: RealtimeOptions,
+    #[command(flatten)]
+    pub quota: QuotaOptions,
+    #[command(flatten)]
+    pub admin: AdminOptions,
+    #[command(flatten)]
+    pub oidc: OidcOptions,
+    #[command(flatten)]
+    pub persisted_query: PersistedQueryOptions,
+    #[command(flatten)]
     pub o11y: ObservabilityOptions,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Upgrade data stored in kvsd to the current version.
+    /// Sqlite database is migrated on startup
+    Migrate(MigrateOptions),
+    /// Manage the config file
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Validate the config file merged with environment variables
+    Check(ConfigCheckOptions),
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ConfigCheckOptions {
+    /// Config file path
+    #[arg(long, env = env_key!("CONFIG"), value_name = "PATH")]
+    pub config: PathBuf,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct MigrateOptions {
+    /// Config file path. Kvsd options in the file are used
+    #[arg(long, env = env_key!("CONFIG"), value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    #[command(flatten)]
+    pub kvsd: KvsdOptions,
+    /// Report values to be upgraded without writing them
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+    /// Additionally migrate the user's data which is not registered in the users index
+    #[arg(long = "user-id", value_name = "USER_ID")]
+    pub user_ids: Vec<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RepositoryKind {
+    /// Store data in kvsd server
+    Kvsd,
+    /// Store data in a sqlite database file
+    Sqlite,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "Repository options")]
+pub struct RepositoryOptions {
+    /// Backend of the repository
+    #[arg(
+        long = "repository",
+        value_enum,
+        default_value_t = RepositoryKind::Kvsd,
+        env = env_key!("REPOSITORY"),
+    )]
+    pub kind: RepositoryKind,
+    #[command(flatten)]
+    pub kvsd: KvsdOptions,
+    #[command(flatten)]
+    pub sqlite: SqliteOptions,
+}
+
+/// Required if the repository is kvsd
 #[derive(clap::Args, Debug)]
 #[command(next_help_heading = "Kvsd options")]
 pub struct KvsdOptions {
     #[arg(long = "kvsd-host", env = env_key!("KVSD_HOST"))]
-    pub kvsd_host: String,
+    pub kvsd_host: Option<String>,
     #[arg(long = "kvsd-port", env = env_key!("KVSD_PORT"))]
-    pub kvsd_port: u16,
+    pub kvsd_port: Option<u16>,
     #[arg(long = "kvsd-username", alias = "kvsd-user", env = env_key!("KVSD_USER"))]
-    pub kvsd_username: String,
+    pub kvsd_username: Option<String>,
     #[arg(long = "kvsd-password", alias = "kvsd-pass", env = env_key!("KVSD_PASS"))]
-    pub kvsd_password: String,
+    pub kvsd_password: Option<String>,
+    /// Maximum number of kvsd connections
+    #[arg(
+        long = "kvsd-pool-size",
+        default_value_t = config::kvsd::DEFAULT_POOL_SIZE,
+        env = env_key!("KVSD_POOL_SIZE"),
+    )]
+    pub kvsd_pool_size: usize,
+    /// Timeout of establishing a kvsd connection including retries
+    #[arg(
+        long = "kvsd-connect-timeout",
+        value_parser = parse_duration::parse,
+        default_value = config::kvsd::DEFAULT_CONNECT_TIMEOUT,
+        env = env_key!("KVSD_CONNECT_TIMEOUT"),
+    )]
+    pub kvsd_connect_timeout: Duration,
+    /// Timeout of waiting for an available kvsd connection
+    #[arg(
+        long = "kvsd-acquire-timeout",
+        value_parser = parse_duration::parse,
+        default_value = config::kvsd::DEFAULT_ACQUIRE_TIMEOUT,
+        env = env_key!("KVSD_ACQUIRE_TIMEOUT"),
+    )]
+    pub kvsd_acquire_timeout: Duration,
+}
+
+/// Required if the repository is sqlite
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "Sqlite options")]
+pub struct SqliteOptions {
+    /// Sqlite database file path. If the file does not exist, it is created
+    #[arg(long = "sqlite-db", env = env_key!("SQLITE_DB"), value_name = "PATH")]
+    pub sqlite_db: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListenMode {
+    /// Serve https on tcp. requires tls options
+    Tls,
+    /// Serve plain http on tcp. intended to be used behind a reverse proxy
+    Http,
+    /// Serve plain http on unix domain socket
+    Unix,
 }
 
 #[derive(clap::Args, Debug)]
 #[command(next_help_heading = "Bind options")]
 pub struct BindOptions {
+    /// How to accept connections
+    #[arg(long, value_enum, default_value_t = ListenMode::Tls, env = env_key!("LISTEN"))]
+    pub listen: ListenMode,
     #[arg(long, value_parser = IpAddr::from_str, default_value = config::serve::DEFAULT_ADDR, env = env_key!("BIND_ADDR"))]
     pub addr: IpAddr,
     #[arg(long, default_value_t = config::serve::DEFAULT_PORT, env = env_key!("BIND_PORT"))]
     pub port: u16,
+    /// Unix domain socket path. Required if listen mode is unix
+    #[arg(long, env = env_key!("UNIX_SOCKET"), value_name = "PATH")]
+    pub unix_socket: Option<PathBuf>,
 }
 
 #[derive(clap::Args, Debug)]
@@ -55,17 +219,358 @@ pub struct ServeOptions {
     pub body_limit_bytes: usize,
     #[arg(long, default_value_t = config::serve::DEFAULT_REQUEST_CONCURRENCY_LIMIT)]
     pub concurrency_limit: usize,
+    #[command(flatten)]
+    pub rate_limit: RateLimitOptions,
+    /// Comma separated addresses or CIDR ranges of reverse proxies.
+    /// The client address is taken from `Forwarded` or `X-Forwarded-For` header set by them
+    #[arg(
+        long,
+        value_name = "CIDR",
+        value_delimiter = ',',
+        env = env_key!("TRUSTED_PROXIES"),
+    )]
+    pub trusted_proxies: Vec<IpNetwork>,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "Rate limit options")]
+pub struct RateLimitOptions {
+    /// Number of requests per minute allowed for each client ip. 0 disables the limit
+    #[arg(
+        long,
+        default_value_t = config::serve::DEFAULT_CLIENT_IP_RATE_LIMIT_PER_MINUTE,
+        env = env_key!("CLIENT_IP_RATE_LIMIT_PER_MINUTE"),
+    )]
+    pub client_ip_rate_limit_per_minute: u32,
+    /// Number of requests each client ip can send in a burst. At least 1
+    #[arg(
+        long,
+        default_value_t = config::serve::DEFAULT_CLIENT_IP_RATE_LIMIT_BURST,
+        value_parser = clap::value_parser!(u32).range(1..),
+        env = env_key!("CLIENT_IP_RATE_LIMIT_BURST"),
+    )]
+    pub client_ip_rate_limit_burst: u32,
+    /// Number of requests per minute allowed for each authenticated user. 0 disables the limit
+    #[arg(
+        long,
+        default_value_t = config::serve::DEFAULT_PRINCIPAL_RATE_LIMIT_PER_MINUTE,
+        env = env_key!("PRINCIPAL_RATE_LIMIT_PER_MINUTE"),
+    )]
+    pub principal_rate_limit_per_minute: u32,
+    /// Number of requests each authenticated user can send in a burst. At least 1
+    #[arg(
+        long,
+        default_value_t = config::serve::DEFAULT_PRINCIPAL_RATE_LIMIT_BURST,
+        value_parser = clap::value_parser!(u32).range(1..),
+        env = env_key!("PRINCIPAL_RATE_LIMIT_BURST"),
+    )]
+    pub principal_rate_limit_burst: u32,
 }
 
 #[derive(clap::Args, Debug)]
 #[command(next_help_heading = "Tls options")]
 pub struct TlsOptions {
-    /// Tls certificate file path
+    /// Tls certificate file path. Required if listen mode is tls
     #[arg(long = "tls-cert", env = env_key!("TLS_CERT"), value_name = "CERT_PATH")]
-    pub certificate: PathBuf,
-    /// Tls private key file path
+    pub certificate: Option<PathBuf>,
+    /// Tls private key file path. Required if listen mode is tls
     #[arg(long = "tls-key", env = env_key!("TLS_KEY"), value_name = "KEY_PATH")]
-    pub private_key: PathBuf,
+    pub private_key: Option<PathBuf>,
+    /// Interval of checking the modification of the certificate and key files.
+    /// `0s` disables the check. The files are also reloaded on SIGHUP
+    #[arg(
+        long = "tls-reload-interval",
+        value_parser = parse_duration::parse,
+        default_value = config::tls::DEFAULT_RELOAD_INTERVAL,
+        env = env_key!("TLS_RELOAD_INTERVAL"),
+    )]
+    pub reload_interval: Duration,
+}
+
+impl TlsOptions {
+    /// Return the certificate and private key paths which are required to serve https
+    pub fn pem_files(&self) -> anyhow::Result<(&Path, &Path)> {
+        match (&self.certificate, &self.private_key) {
+            (Some(certificate), Some(private_key)) => Ok((certificate, private_key)),
+            _ => anyhow::bail!("--tls-cert and --tls-key are required if listen mode is tls"),
+        }
+    }
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "Feed options")]
+pub struct FeedOptions {
+    /// Maximum size in bytes of the cached feeds
+    #[arg(
+        long = "feed-cache-size",
+        default_value_t = config::feed::DEFAULT_CACHE_SIZE_BYTES,
+        env = env_key!("FEED_CACHE_SIZE"),
+    )]
+    pub feed_cache_size_bytes: u64,
+    /// Time to live of the cached feeds
+    #[arg(
+        long = "feed-cache-ttl",
+        value_parser = parse_duration::parse,
+        default_value = config::feed::DEFAULT_CACHE_TTL,
+        env = env_key!("FEED_CACHE_TTL"),
+    )]
+    pub feed_cache_ttl: Duration,
+    /// Maximum size in bytes of the fetched feed body
+    #[arg(
+        long = "feed-body-limit",
+        default_value_t = config::feed::DEFAULT_BODY_LIMIT_BYTES,
+        env = env_key!("FEED_BODY_LIMIT"),
+    )]
+    pub feed_body_limit_bytes: usize,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "Search options")]
+pub struct SearchOptions {
+    /// Directory of the full-text search index of entries
+    #[arg(
+        long = "search-index-dir",
+        env = env_key!("SEARCH_INDEX_DIR"),
+        default_value = config::search::DEFAULT_INDEX_DIR,
+        value_name = "DIR",
+    )]
+    pub index_dir: PathBuf,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "Archive options")]
+pub struct ArchiveOptions {
+    /// Retention period of archived entries
+    #[arg(
+        long = "entry-retention",
+        value_parser = parse_duration::parse,
+        default_value = config::archive::DEFAULT_ENTRY_RETENTION,
+        env = env_key!("ENTRY_RETENTION"),
+    )]
+    pub entry_retention: Duration,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "Realtime options")]
+pub struct RealtimeOptions {
+    /// Interval of refreshing feeds watched by `newEntries` subscriptions
+    #[arg(
+        long = "refresh-interval",
+        value_parser = parse_duration::parse,
+        default_value = config::realtime::DEFAULT_REFRESH_INTERVAL,
+        env = env_key!("REFRESH_INTERVAL"),
+    )]
+    pub refresh_interval: Duration,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "Quota options")]
+pub struct QuotaOptions {
+    /// Maximum number of subscriptions of free tier users
+    #[arg(
+        long,
+        default_value_t = config::quota::DEFAULT_FREE_MAX_SUBSCRIPTIONS,
+        env = env_key!("QUOTA_FREE_MAX_SUBSCRIPTIONS"),
+    )]
+    pub free_max_subscriptions: usize,
+
+    /// Maximum number of mutations per minute of free tier users
+    #[arg(
+        long,
+        default_value_t = config::quota::DEFAULT_FREE_MUTATIONS_PER_MINUTE,
+        env = env_key!("QUOTA_FREE_MUTATIONS_PER_MINUTE"),
+    )]
+    pub free_mutations_per_minute: usize,
+
+    /// Maximum number of entries in a page of free tier users
+    #[arg(
+        long,
+        default_value_t = config::quota::DEFAULT_FREE_MAX_ENTRIES_PAGE_SIZE,
+        env = env_key!("QUOTA_FREE_MAX_ENTRIES_PAGE_SIZE"),
+    )]
+    pub free_max_entries_page_size: usize,
+
+    /// Maximum number of subscriptions of pro tier users
+    #[arg(
+        long,
+        default_value_t = config::quota::DEFAULT_PRO_MAX_SUBSCRIPTIONS,
+        env = env_key!("QUOTA_PRO_MAX_SUBSCRIPTIONS"),
+    )]
+    pub pro_max_subscriptions: usize,
+
+    /// Maximum number of mutations per minute of pro tier users
+    #[arg(
+        long,
+        default_value_t = config::quota::DEFAULT_PRO_MUTATIONS_PER_MINUTE,
+        env = env_key!("QUOTA_PRO_MUTATIONS_PER_MINUTE"),
+    )]
+    pub pro_mutations_per_minute: usize,
+
+    /// Maximum number of entries in a page of pro tier users
+    #[arg(
+        long,
+        default_value_t = config::quota::DEFAULT_PRO_MAX_ENTRIES_PAGE_SIZE,
+        env = env_key!("QUOTA_PRO_MAX_ENTRIES_PAGE_SIZE"),
+    )]
+    pub pro_max_entries_page_size: usize,
+
+    /// Comma separated users in pro tier.
+    /// Each user is `<provider>:<subject>` of the identity or `user:<user_id>` as `--admins`
+    #[arg(
+        long,
+        value_name = "PROVIDER:SUBJECT|user:USER_ID",
+        value_parser = parse_pro_user,
+        value_delimiter = ',',
+        env = env_key!("QUOTA_PRO_USERS"),
+    )]
+    pub pro_users: Vec<ProUser>,
+}
+
+fn parse_pro_user(s: &str) -> Result<ProUser, String> {
+    parse_admin(s).map(|user| match user {
+        Admin::Identity(identity) => ProUser::Identity(identity),
+        Admin::User(user_id) => ProUser::User(user_id),
+    })
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "Admin options")]
+pub struct AdminOptions {
+    /// Comma separated users who are authorized to administrative operations.
+    /// Each user is `<provider>:<subject>` of the identity such as `github:12345`
+    /// or `user:<user_id>`. Emails are not accepted since they are controlled by the providers
+    #[arg(
+        long = "admins",
+        value_name = "PROVIDER:SUBJECT|user:USER_ID",
+        value_parser = parse_admin,
+        value_delimiter = ',',
+        env = env_key!("ADMINS"),
+    )]
+    pub admins: Vec<Admin>,
+}
+
+fn parse_admin(s: &str) -> Result<Admin, String> {
+    let (provider, subject) = s
+        .trim()
+        .split_once(':')
+        .filter(|(provider, subject)| !provider.is_empty() && !subject.is_empty())
+        .ok_or_else(|| format!("`{s}` is not `<provider>:<subject>` or `user:<user_id>`"))?;
+
+    Ok(match provider {
+        "user" => Admin::User(subject.to_owned()),
+        provider => Admin::Identity(Identity::new(provider, subject)),
+    })
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "OpenID Connect options")]
+pub struct OidcOptions {
+    /// OpenID Connect provider whose id tokens are accepted with `<name> <id_token>` scheme.
+    /// Format: `name=<name>,issuer=<url>,audience=<client_id>[,jwks_url=<url>]
+    /// [,subject_claim=<claim>][,email_claim=<claim>][,email_verified_claim=<claim>]`.
+    /// Id tokens whose email is not verified are rejected
+    #[arg(
+        long = "oidc-provider",
+        value_name = "SPEC",
+        value_parser = parse_oidc_provider,
+        value_delimiter = ';',
+        env = env_key!("OIDC_PROVIDERS"),
+    )]
+    pub providers: Vec<OidcProvider>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OidcProvider {
+    pub name: String,
+    pub config: OidcConfig,
+}
+
+const RESERVED_OIDC_PROVIDER_NAMES: &[&str] = &["github", "google", "token", "user"];
+
+fn parse_oidc_provider(spec: &str) -> Result<OidcProvider, String> {
+    let mut name = None;
+    let mut issuer = None;
+    let mut audience = None;
+    let mut jwks_url = None;
+    let mut claims = ClaimMapping::default();
+
+    for pair in spec
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+    {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("`{pair}` is not a key=value pair"))?;
+        let value = value.trim().to_owned();
+        match key.trim() {
+            "name" => name = Some(value),
+            "issuer" => issuer = Some(value),
+            "audience" => audience = Some(value),
+            "jwks_url" => jwks_url = Some(value),
+            "subject_claim" => claims.subject = value,
+            "email_claim" => claims.email = value,
+            "email_verified_claim" => claims.email_verified = value,
+            other => return Err(format!("unknown oidc provider key `{other}`")),
+        }
+    }
+
+    let name = name.ok_or("name is required")?;
+    // Name is sent as the authorization scheme
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "`{name}` is invalid. name consists of alphanumerics, `-` and `_`"
+        ));
+    }
+    // These schemes are reserved by the builtin authentication and `user` by admin options
+    if RESERVED_OIDC_PROVIDER_NAMES
+        .iter()
+        .any(|reserved| name.eq_ignore_ascii_case(reserved))
+    {
+        return Err(format!("`{name}` is reserved"));
+    }
+
+    Ok(OidcProvider {
+        name,
+        config: OidcConfig {
+            issuer: issuer.ok_or("issuer is required")?,
+            audience: audience.ok_or("audience is required")?,
+            jwks_url,
+            claims,
+        },
+    })
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "Persisted query options")]
+pub struct PersistedQueryOptions {
+    /// Maximum number of automatic persisted queries to cache
+    #[arg(
+        long = "persisted-query-cache-size",
+        default_value_t = config::serve::DEFAULT_PERSISTED_QUERY_CACHE_SIZE,
+        env = env_key!("PERSISTED_QUERY_CACHE_SIZE"),
+    )]
+    pub cache_size: usize,
+    /// GraphQL document files to register on startup. each file is one document
+    #[arg(
+        long = "persisted-query",
+        value_name = "PATH",
+        value_delimiter = ',',
+        env = env_key!("PERSISTED_QUERIES"),
+    )]
+    pub queries: Vec<PathBuf>,
+    /// Only allow registered queries to execute.
+    /// Subscriptions over websocket are also rejected unless their documents are registered
+    #[arg(
+        long = "persisted-queries-only",
+        default_value_t = false,
+        env = env_key!("PERSISTED_QUERIES_ONLY"),
+    )]
+    pub strict: bool,
 }
 
 #[derive(clap::Args, Debug)]
@@ -90,12 +595,94 @@ pub struct ObservabilityOptions {
 
 #[must_use]
 pub fn parse() -> Args {
-    Args::parse()
+    let mut command = Args::command();
+    if let Some(path) = config_path(std::env::args_os()) {
+        command = match ConfigFile::load(path).and_then(|file| file.apply(command.clone())) {
+            Ok(command) => command,
+            Err(err) => command.error(ErrorKind::Io, format!("{err:#}")).exit(),
+        };
+    }
+
+    Args::from_arg_matches_mut(&mut command.get_matches()).unwrap_or_else(|err| err.exit())
 }
 
-impl From<BindOptions> for serve::BindOptions {
-    fn from(BindOptions { addr, port }: BindOptions) -> Self {
-        Self { port, addr }
+/// The config file path needs to be resolved before parsing the other args.
+/// Other subcommands than `migrate` do not read the config file
+fn config_path<I, T>(args: I) -> Option<PathBuf>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let matches = Args::command()
+        .ignore_errors(true)
+        .try_get_matches_from(args)
+        .ok()?;
+    match matches.subcommand() {
+        Some(("migrate", matches)) => matches.get_one::<PathBuf>("config").cloned(),
+        Some(_) => None,
+        None => matches.get_one::<PathBuf>("config").cloned(),
+    }
+}
+
+impl TryFrom<BindOptions> for serve::BindOptions {
+    type Error = anyhow::Error;
+
+    fn try_from(
+        BindOptions {
+            listen,
+            addr,
+            port,
+            unix_socket,
+        }: BindOptions,
+    ) -> Result<Self, Self::Error> {
+        match listen {
+            ListenMode::Tls | ListenMode::Http => Ok(Self::Tcp { addr, port }),
+            ListenMode::Unix => Ok(Self::Unix {
+                path: unix_socket.context("--unix-socket is required")?,
+            }),
+        }
+    }
+}
+
+impl TryFrom<RepositoryOptions> for RepositoryConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(
+        RepositoryOptions { kind, kvsd, sqlite }: RepositoryOptions,
+    ) -> Result<Self, Self::Error> {
+        match kind {
+            RepositoryKind::Kvsd => kvsd.try_into().map(Self::Kvsd),
+            RepositoryKind::Sqlite => Ok(Self::Sqlite {
+                path: sqlite.sqlite_db.context("--sqlite-db is required")?,
+            }),
+        }
+    }
+}
+
+impl TryFrom<KvsdOptions> for KvsdConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(
+        KvsdOptions {
+            kvsd_host,
+            kvsd_port,
+            kvsd_username,
+            kvsd_password,
+            kvsd_pool_size,
+            kvsd_connect_timeout,
+            kvsd_acquire_timeout,
+        }: KvsdOptions,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            host: kvsd_host.context("--kvsd-host is required")?,
+            port: kvsd_port.context("--kvsd-port is required")?,
+            username: kvsd_username.context("--kvsd-username is required")?,
+            password: kvsd_password.context("--kvsd-password is required")?,
+            pool: PoolConfig::default()
+                .with_size(kvsd_pool_size)
+                .with_connect_timeout(kvsd_connect_timeout)
+                .with_acquire_timeout(kvsd_acquire_timeout),
+        })
     }
 }
 
@@ -105,12 +692,153 @@ impl From<ServeOptions> for serve::ServeOptions {
             timeout,
             body_limit_bytes,
             concurrency_limit,
+            rate_limit:
+                RateLimitOptions {
+                    client_ip_rate_limit_per_minute,
+                    client_ip_rate_limit_burst,
+                    principal_rate_limit_per_minute,
+                    principal_rate_limit_burst,
+                },
+            trusted_proxies,
         }: ServeOptions,
     ) -> Self {
         Self {
             timeout,
             body_limit_bytes,
             concurrency_limit,
+            client_ip_rate_limit: RateLimit {
+                requests_per_minute: client_ip_rate_limit_per_minute,
+                burst: client_ip_rate_limit_burst,
+            },
+            principal_rate_limit: RateLimit {
+                requests_per_minute: principal_rate_limit_per_minute,
+                burst: principal_rate_limit_burst,
+            },
+            trusted_proxies: TrustedProxies::new(trusted_proxies),
         }
     }
 }
+
+impl From<QuotaOptions> for QuotaPolicy {
+    fn from(
+        QuotaOptions {
+            free_max_subscriptions,
+            free_mutations_per_minute,
+            free_max_entries_page_size,
+            pro_max_subscriptions,
+            pro_mutations_per_minute,
+            pro_max_entries_page_size,
+            pro_users,
+        }: QuotaOptions,
+    ) -> Self {
+        Self {
+            free: Quota {
+                max_subscriptions: free_max_subscriptions,
+                mutations_per_minute: free_mutations_per_minute,
+                max_entries_page_size: free_max_entries_page_size,
+            },
+            pro: Quota {
+                max_subscriptions: pro_max_subscriptions,
+                mutations_per_minute: pro_mutations_per_minute,
+                max_entries_page_size: pro_max_entries_page_size,
+            },
+            pro_identities: HashSet::new(),
+            pro_user_ids: HashSet::new(),
+        }
+        .with_pro_users(pro_users)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_config_path_of_migrate() {
+        let path = |args: &[&str]| config_path(args.iter().copied());
+
+        assert_eq!(
+            path(&["synd-api", "--config", "synd.toml"]),
+            Some(PathBuf::from("synd.toml"))
+        );
+        assert_eq!(
+            path(&["synd-api", "migrate", "--config", "synd.toml"]),
+            Some(PathBuf::from("synd.toml"))
+        );
+        assert_eq!(
+            path(&["synd-api", "config", "check", "--config", "synd.toml"]),
+            None
+        );
+    }
+
+    #[test]
+    fn reject_zero_rate_limit_burst() {
+        #[derive(Parser)]
+        struct Cli {
+            #[command(flatten)]
+            rate_limit: RateLimitOptions,
+        }
+
+        for flag in [
+            "--client-ip-rate-limit-burst",
+            "--principal-rate-limit-burst",
+        ] {
+            assert!(
+                Cli::try_parse_from(["synd-api", flag, "0"]).is_err(),
+                "{flag}"
+            );
+            assert!(
+                Cli::try_parse_from(["synd-api", flag, "1"]).is_ok(),
+                "{flag}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_oidc_provider_spec() {
+        let provider = parse_oidc_provider(
+            "name=dex, issuer=https://dex.example.com,audience=synd,email_verified_claim=verified",
+        )
+        .unwrap();
+
+        assert_eq!(provider.name, "dex");
+        assert_eq!(provider.config.issuer, "https://dex.example.com");
+        assert_eq!(provider.config.audience, "synd");
+        assert_eq!(provider.config.jwks_url, None);
+        assert_eq!(provider.config.claims.subject, "sub");
+        assert_eq!(provider.config.claims.email_verified, "verified");
+    }
+
+    #[test]
+    fn reject_reserved_oidc_provider_name() {
+        for name in ["github", "google", "token", "user", "GitHub", " token "] {
+            let spec = format!("name={name},issuer=https://example.com,audience=synd");
+            let err = parse_oidc_provider(&spec).unwrap_err();
+            assert!(err.contains("reserved"), "{name}: {err}");
+        }
+    }
+
+    #[test]
+    fn reject_invalid_oidc_provider_spec() {
+        for spec in [
+            "name=,issuer=https://example.com,audience=synd",
+            "name=my provider,issuer=https://example.com,audience=synd",
+            "name=dex,audience=synd",
+            "name=dex,issuer=https://example.com,audience=synd,email_verified_claim",
+            "name=dex,issuer=https://example.com,audience=synd,unknown=1",
+        ] {
+            assert!(parse_oidc_provider(spec).is_err(), "{spec}");
+        }
+    }
+
+    #[test]
+    fn parse_admin_identity_or_user_id() {
+        assert_eq!(
+            parse_admin("github:12345"),
+            Ok(Admin::Identity(Identity::new("github", "12345")))
+        );
+        assert_eq!(parse_admin("user:abc"), Ok(Admin::User("abc".into())));
+        assert!(parse_admin("admin@example.com").is_err());
+        assert!(parse_admin("github:").is_err());
+    }
+}
@@ -6,6 +6,13 @@ use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{client::github::query, config};
 
+/// Authenticated github user
+pub struct GithubUser {
+    /// Identifier of the user which does not change even if the login is changed
+    pub id: i64,
+    pub email: String,
+}
+
 #[derive(Clone)]
 pub struct GithubClient {
     client: reqwest::Client,
@@ -38,13 +45,16 @@ impl GithubClient {
     }
 
     #[tracing::instrument(name = "github::authenticate", skip_all)]
-    pub async fn authenticate(&self, access_token: &str) -> anyhow::Result<String> {
+    pub async fn authenticate(&self, access_token: &str) -> anyhow::Result<GithubUser> {
         let variables = query::authenticate::Variables {};
         let request = query::Authenticate::build_query(variables);
         let response: query::authenticate::ResponseData =
             self.request(access_token, &request).await?;
 
-        Ok(response.viewer.email)
+        let query::authenticate::AuthenticateViewer { database_id, email } = response.viewer;
+        let id = database_id.ok_or_else(|| anyhow::anyhow!("github user id not found"))?;
+
+        Ok(GithubUser { id, email })
     }
 
     async fn request<Body, ResponseData>(
@@ -4,7 +4,8 @@ pub mod authenticate {
     #![allow(dead_code)]
     use std::result::Result;
     pub const OPERATION_NAME: &str = "Authenticate";
-    pub const QUERY: &str = "query Authenticate {\n  viewer {\n    email,\n  }\n}\n";
+    pub const QUERY: &str =
+        "query Authenticate {\n  viewer {\n    databaseId,\n    email,\n  }\n}\n";
     use super::*;
     use serde::{Deserialize, Serialize};
     #[allow(dead_code)]
@@ -23,6 +24,8 @@ pub mod authenticate {
     }
     #[derive(Deserialize, Debug)]
     pub struct AuthenticateViewer {
+        #[serde(rename = "databaseId")]
+        pub database_id: Option<Int>,
         pub email: String,
     }
 }
@@ -14,10 +14,52 @@ pub mod env {
     pub(crate) use env_key;
 }
 
+pub mod archive {
+    pub const DEFAULT_ENTRY_RETENTION: &str = "180days";
+}
+
+pub mod feed {
+    pub const DEFAULT_CACHE_SIZE_BYTES: u64 = 100 * 1024 * 1024;
+    pub const DEFAULT_CACHE_TTL: &str = "3h";
+    pub const DEFAULT_BODY_LIMIT_BYTES: usize = 10 * 1024 * 1024;
+}
+
+pub mod kvsd {
+    pub const DEFAULT_POOL_SIZE: usize = 8;
+    pub const DEFAULT_CONNECT_TIMEOUT: &str = "10s";
+    pub const DEFAULT_ACQUIRE_TIMEOUT: &str = "5s";
+}
+
+pub mod quota {
+    pub const DEFAULT_FREE_MAX_SUBSCRIPTIONS: usize = 100;
+    pub const DEFAULT_FREE_MUTATIONS_PER_MINUTE: usize = 30;
+    pub const DEFAULT_FREE_MAX_ENTRIES_PAGE_SIZE: usize = 200;
+    pub const DEFAULT_PRO_MAX_SUBSCRIPTIONS: usize = 1000;
+    pub const DEFAULT_PRO_MUTATIONS_PER_MINUTE: usize = 120;
+    pub const DEFAULT_PRO_MAX_ENTRIES_PAGE_SIZE: usize = 200;
+}
+
+pub mod realtime {
+    pub const DEFAULT_REFRESH_INTERVAL: &str = "15min";
+}
+
+pub mod search {
+    pub const DEFAULT_INDEX_DIR: &str = "search_index";
+}
+
 pub mod serve {
     pub const DEFAULT_ADDR: &str = "127.0.0.1";
     pub const DEFAULT_PORT: u16 = 5959;
     pub const DEFAULT_REQUEST_TIMEOUT: &str = "30s";
     pub const DEFAULT_REQUEST_BODY_LIMIT_BYTES: usize = 1024 * 2;
     pub const DEFAULT_REQUEST_CONCURRENCY_LIMIT: usize = 100;
+    pub const DEFAULT_CLIENT_IP_RATE_LIMIT_PER_MINUTE: u32 = 600;
+    pub const DEFAULT_CLIENT_IP_RATE_LIMIT_BURST: u32 = 100;
+    pub const DEFAULT_PRINCIPAL_RATE_LIMIT_PER_MINUTE: u32 = 300;
+    pub const DEFAULT_PRINCIPAL_RATE_LIMIT_BURST: u32 = 50;
+    pub const DEFAULT_PERSISTED_QUERY_CACHE_SIZE: usize = 1024;
+}
+
+pub mod tls {
+    pub const DEFAULT_RELOAD_INTERVAL: &str = "1min";
 }
@@ -0,0 +1,179 @@
+//! Configuration file
+//!
+//! The file is a TOML table whose keys are the long flag names without the leading `--`.
+//! Values are written as they are on the command line and repeatable or comma separated
+//! flags are written as arrays.
+//!
+//! ```toml
+//! repository = "sqlite"
+//! sqlite-db = "/var/lib/synd/synd.db"
+//! timeout = "30s"
+//! trusted-proxies = ["10.0.0.0/8"]
+//! feed-cache-ttl = "3h"
+//! ```
+//!
+//! Settings in the file are used as defaults, so they are overridden by environment variables
+//! and flags. Subcommands which accept the same flags, such as `migrate` for kvsd options,
+//! also use them.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use clap::Command;
+use toml::{Table, Value};
+
+/// Flags which can not be set in the file
+const EXCLUDED_KEYS: &[&str] = &["config", "help", "version"];
+
+#[derive(Debug)]
+pub struct ConfigFile {
+    path: PathBuf,
+    table: Table,
+}
+
+impl ConfigFile {
+    pub fn load(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("read config file {}", path.display()))?;
+        let table = content
+            .parse::<Table>()
+            .with_context(|| format!("parse config file {}", path.display()))?;
+
+        Ok(Self { path, table })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Set the values in the file as the defaults of the command
+    pub fn apply(&self, mut command: Command) -> anyhow::Result<Command> {
+        for (key, value) in &self.table {
+            let id = command
+                .get_arguments()
+                .find(|arg| arg.get_long() == Some(key.as_str()))
+                .filter(|_| !EXCLUDED_KEYS.contains(&key.as_str()))
+                .map(|arg| arg.get_id().clone())
+                .with_context(|| format!("{}: unknown key `{key}`", self.path.display()))?;
+
+            let values = match value {
+                Value::Array(values) => values.iter().map(scalar).collect::<Option<Vec<_>>>(),
+                value => scalar(value).map(|value| vec![value]),
+            }
+            .with_context(|| {
+                format!(
+                    "{}: `{key}` must be a string, number, boolean or an array of them",
+                    self.path.display()
+                )
+            })?;
+
+            // Share the settings with subcommands, for instance kvsd options of `migrate`
+            let subcommands = command
+                .get_subcommands()
+                .filter_map(|sub| {
+                    let arg = sub
+                        .get_arguments()
+                        .find(|arg| arg.get_long() == Some(key.as_str()))?;
+                    Some((sub.get_name().to_owned(), arg.get_id().clone()))
+                })
+                .collect::<Vec<_>>();
+            for (name, id) in subcommands {
+                command = command.mut_subcommand(name, |sub| {
+                    sub.mut_arg(id, |arg| arg.default_values(values.clone()))
+                });
+            }
+
+            command = command.mut_arg(id, |arg| arg.default_values(values));
+        }
+
+        Ok(command)
+    }
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.clone()),
+        Value::Integer(value) => Some(value.to_string()),
+        Value::Float(value) => Some(value.to_string()),
+        Value::Boolean(value) => Some(value.to_string()),
+        Value::Datetime(_) | Value::Array(_) | Value::Table(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use clap::CommandFactory as _;
+
+    use crate::{args::Args, serve::layer::client_ip::IpNetwork};
+
+    use super::*;
+
+    fn config_file(content: &str) -> ConfigFile {
+        ConfigFile {
+            path: PathBuf::from("synd.toml"),
+            table: content.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn apply_as_defaults() {
+        let file = config_file(
+            r#"
+            timeout = "10s"
+            concurrency-limit = 5
+            trusted-proxies = ["10.0.0.0/8", "192.168.0.1"]
+            "#,
+        );
+        let matches = file
+            .apply(Args::command())
+            .unwrap()
+            .try_get_matches_from(["synd-api", "--concurrency-limit", "10"])
+            .unwrap();
+
+        assert_eq!(
+            matches.get_one::<Duration>("timeout"),
+            Some(&Duration::from_secs(10))
+        );
+        // Flags take precedence
+        assert_eq!(matches.get_one::<usize>("concurrency_limit"), Some(&10));
+        assert_eq!(
+            matches
+                .get_many::<IpNetwork>("trusted_proxies")
+                .unwrap()
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn apply_to_migrate() {
+        let file = config_file(
+            r#"
+            kvsd-host = "kvsd.example.com"
+            kvsd-port = 7379
+            timeout = "10s"
+            "#,
+        );
+        let matches = file
+            .apply(Args::command())
+            .unwrap()
+            .try_get_matches_from(["synd-api", "migrate", "--kvsd-port", "7380"])
+            .unwrap();
+        let (_, matches) = matches.subcommand().unwrap();
+
+        assert_eq!(
+            matches.get_one::<String>("kvsd_host").map(String::as_str),
+            Some("kvsd.example.com")
+        );
+        assert_eq!(matches.get_one::<u16>("kvsd_port"), Some(&7380));
+    }
+
+    #[test]
+    fn reject_unknown_key() {
+        let file = config_file(r#"no-such-flag = "x""#);
+        assert!(file.apply(Args::command()).is_err());
+    }
+}
@@ -1,73 +1,128 @@
-use std::{sync::Arc, time::Duration};
+use std::sync::Arc;
 
 use anyhow::Context;
 use axum_server::tls_rustls::RustlsConfig;
+use synd_auth::jwt::oidc::OidcJwtService;
 use synd_feed::feed::{
-    cache::{CacheConfig, CacheLayer},
+    cache::{CacheConfig, CacheLayer, FetchCachedFeed},
     parser::FeedService,
 };
 
 use crate::{
-    args::{self, KvsdOptions, TlsOptions},
+    archive::ArchiveFeedService,
+    args::{
+        self, AdminOptions, ArchiveOptions, FeedOptions, OidcOptions, PersistedQueryOptions,
+        QuotaOptions, RealtimeOptions, RepositoryOptions, SearchOptions, TlsOptions,
+    },
     config,
+    gql::PersistedQueries,
     monitor::Monitors,
-    repository::kvsd::KvsdClient,
-    serve::{auth::Authenticator, ServeOptions},
+    realtime::{BroadcastFeedService, EntryBroadcaster, Refresher},
+    repository::{
+        kvsd::{KvsdClient, KvsdConfig},
+        sqlite::SqliteRepository,
+        EntryArchiveRepository, RepositoryConfig, SubscriptionRepository, UserRepository,
+    },
+    search::{IndexFeedService, Indexer, SearchIndex},
+    serve::{auth::Authenticator, tls::CertificateReloader, ServeOptions},
     usecase::{authorize::Authorizer, MakeUsecase, Runtime},
 };
 
 pub struct Dependency {
     pub authenticator: Authenticator,
     pub runtime: Runtime,
-    pub tls_config: RustlsConfig,
+    /// None if the server does not terminate tls
+    pub tls_config: Option<RustlsConfig>,
     pub serve_options: ServeOptions,
     pub monitors: Monitors,
+    pub persisted_queries: PersistedQueries,
 }
 
 impl Dependency {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
-        kvsd: KvsdOptions,
-        tls: TlsOptions,
+        repository: RepositoryOptions,
+        tls: Option<TlsOptions>,
         serve_options: args::ServeOptions,
+        feed: FeedOptions,
+        search: SearchOptions,
+        archive: ArchiveOptions,
+        realtime: RealtimeOptions,
+        quota: QuotaOptions,
+        admin: AdminOptions,
+        oidc: OidcOptions,
+        persisted_query: PersistedQueryOptions,
         monitors: Monitors,
     ) -> anyhow::Result<Self> {
-        let KvsdOptions {
-            kvsd_host,
-            kvsd_port,
-            kvsd_username,
-            kvsd_password,
-        } = kvsd;
-        let kvsd = KvsdClient::connect(
-            kvsd_host,
-            kvsd_port,
-            kvsd_username,
-            kvsd_password,
-            Duration::from_secs(10),
-        )
-        .await?;
+        let (subscription_repo, archive_repo, user_repo) = Self::repositories(repository).await?;
+
+        let search_index = SearchIndex::open(&search.index_dir)
+            .map(Arc::new)
+            .with_context(|| format!("search options: {search:?}"))?;
+        let indexer = Indexer::spawn(Arc::clone(&search_index));
 
-        let feed_service = FeedService::new(config::USER_AGENT, 10 * 1024 * 1024);
-        let cache_feed_service = CacheLayer::with(
+        let feed_service = FeedService::new(config::USER_AGENT, feed.feed_body_limit_bytes);
+        // Index and archive feeds only when they are actually fetched, not when served from cache
+        let feed_service = IndexFeedService::new(feed_service, indexer);
+        let feed_service = ArchiveFeedService::new(
             feed_service,
-            CacheConfig::default()
-                .with_max_cache_size(100 * 1024 * 1024)
-                .with_time_to_live(Duration::from_secs(60 * 60 * 3)),
+            Arc::clone(&archive_repo),
+            archive.entry_retention,
         );
+        // Remember entries of watched feeds across refreshes and others while they are cached
+        let broadcaster =
+            EntryBroadcaster::new(feed.feed_cache_ttl.max(realtime.refresh_interval * 2));
+        let feed_service = BroadcastFeedService::new(feed_service, broadcaster.clone());
+        let cache_feed_service: Arc<dyn FetchCachedFeed> = Arc::new(CacheLayer::with(
+            feed_service,
+            CacheConfig::default()
+                .with_max_cache_size(feed.feed_cache_size_bytes)
+                .with_time_to_live(feed.feed_cache_ttl),
+        ));
 
-        let make_usecase = MakeUsecase {
-            subscription_repo: Arc::new(kvsd),
-            fetch_feed: Arc::new(cache_feed_service),
-        };
+        Refresher::new(
+            Arc::clone(&cache_feed_service),
+            broadcaster.clone(),
+            realtime.refresh_interval,
+        )
+        .spawn();
 
-        let authenticator = Authenticator::new()?;
+        let authenticator = Authenticator::new(user_repo.clone())?
+            .with_admins(admin.admins)
+            .with_oidc_providers(oidc.providers.into_iter().map(|provider| {
+                tracing::info!(
+                    name = provider.name,
+                    issuer = provider.config.issuer,
+                    "Oidc provider"
+                );
+                (provider.name, OidcJwtService::new(provider.config))
+            }));
 
-        let authorizer = Authorizer::new();
+        let authorizer = Authorizer::new()
+            .with_quota(quota.into(), subscription_repo.clone(), user_repo.clone())
+            .with_disabled_users(user_repo.clone());
+
+        let make_usecase = MakeUsecase {
+            subscription_repo,
+            user_repo,
+            fetch_feed: cache_feed_service,
+            search_index,
+            archive_repo,
+            entry_retention: archive.entry_retention,
+            broadcaster,
+        };
 
         let runtime = Runtime::new(make_usecase, authorizer);
 
-        let tls_config = RustlsConfig::from_pem_file(&tls.certificate, &tls.private_key)
-            .await
-            .with_context(|| format!("tls options: {tls:?}"))?;
+        let persisted_queries = PersistedQueries::new(persisted_query.cache_size)
+            .register_files(&persisted_query.queries)
+            .with_context(|| format!("persisted query options: {persisted_query:?}"))?
+            .with_strict(persisted_query.strict);
+
+        let tls_config = match tls {
+            Some(tls) => Some(Self::load_tls_config(tls).await?),
+            None => None,
+        };
 
         Ok(Dependency {
             authenticator,
@@ -75,6 +130,58 @@ impl Dependency {
             tls_config,
             serve_options: serve_options.into(),
             monitors,
+            persisted_queries,
         })
     }
+
+    async fn load_tls_config(tls: TlsOptions) -> anyhow::Result<RustlsConfig> {
+        let (certificate, private_key) = tls.pem_files()?;
+
+        let config = RustlsConfig::from_pem_file(certificate, private_key)
+            .await
+            .with_context(|| format!("tls options: {tls:?}"))?;
+
+        CertificateReloader::new(
+            config.clone(),
+            certificate.to_owned(),
+            private_key.to_owned(),
+            tls.reload_interval,
+        )
+        .spawn();
+
+        Ok(config)
+    }
+
+    async fn repositories(
+        options: RepositoryOptions,
+    ) -> anyhow::Result<(
+        Arc<dyn SubscriptionRepository>,
+        Arc<dyn EntryArchiveRepository>,
+        Arc<dyn UserRepository>,
+    )> {
+        match RepositoryConfig::try_from(options)? {
+            RepositoryConfig::Kvsd(config) => {
+                let kvsd = Self::connect_kvsd(config).await.map(Arc::new)?;
+
+                Ok((kvsd.clone(), kvsd.clone(), kvsd))
+            }
+            RepositoryConfig::Sqlite { path } => {
+                let sqlite = SqliteRepository::connect(path).await.map(Arc::new)?;
+
+                Ok((sqlite.clone(), sqlite.clone(), sqlite))
+            }
+        }
+    }
+
+    pub async fn connect_kvsd(
+        KvsdConfig {
+            host,
+            port,
+            username,
+            password,
+            pool,
+        }: KvsdConfig,
+    ) -> anyhow::Result<KvsdClient> {
+        KvsdClient::connect(host, port, username, password, pool).await
+    }
 }
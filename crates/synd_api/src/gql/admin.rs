@@ -0,0 +1,152 @@
+//! Administrative operations which only admins are authorized to
+
+use async_graphql::{Context, ErrorExtensions, Object, Result, SimpleObject};
+
+use crate::{
+    gql::{internal_error, mutation::ResponseCode, object, run_usecase},
+    principal::Principal,
+    usecase::{
+        admin::{
+            FetchPopularFeeds, FetchPopularFeedsInput, FetchPopularFeedsOutput, ListUsers,
+            ListUsersInput, ListUsersOutput, PurgeFeedCache, PurgeFeedCacheInput,
+            PurgeFeedCacheOutput, RefreshFeed, RefreshFeedError, RefreshFeedInput,
+            RefreshFeedOutput, SetUserDisabled, SetUserDisabledInput, SetUserDisabledOutput,
+        },
+        Output,
+    },
+};
+
+/// Reject non admin principals before running usecases
+fn ensure_admin(cx: &Context<'_>) -> Result<()> {
+    if cx.data_unchecked::<Principal>().is_admin() {
+        Ok(())
+    } else {
+        Err(async_graphql::Error::new("unauthorized error")
+            .extend_with(|_, ext| ext.set("code", ResponseCode::Unauthorized)))
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct AdminUser {
+    pub id: String,
+    /// Number of subscribed feeds
+    pub subscriptions: usize,
+    pub disabled: bool,
+}
+
+#[derive(SimpleObject)]
+pub struct PopularFeed {
+    pub url: String,
+    /// Number of users who subscribe the feed
+    pub subscribers: usize,
+}
+
+pub struct AdminQuery;
+
+#[Object]
+impl AdminQuery {
+    /// Return users ordered by the number of subscriptions
+    async fn users(&self, cx: &Context<'_>) -> Result<Vec<AdminUser>> {
+        let Output {
+            output: ListUsersOutput { users },
+        } = run_usecase!(ListUsers, cx, ListUsersInput {}, internal_error)?;
+
+        Ok(users
+            .into_iter()
+            .map(|user| AdminUser {
+                id: user.user_id,
+                subscriptions: user.subscriptions,
+                disabled: user.disabled,
+            })
+            .collect())
+    }
+
+    /// Return the most subscribed feeds
+    async fn popular_feeds(
+        &self,
+        cx: &Context<'_>,
+        #[graphql(default = 20)] first: Option<i32>,
+    ) -> Result<Vec<PopularFeed>> {
+        #[allow(clippy::cast_sign_loss)]
+        let first = first.unwrap_or(20).clamp(0, 100) as usize;
+        let Output {
+            output: FetchPopularFeedsOutput { feeds },
+        } = run_usecase!(
+            FetchPopularFeeds,
+            cx,
+            FetchPopularFeedsInput { first },
+            internal_error
+        )?;
+
+        Ok(feeds
+            .into_iter()
+            .map(|feed| PopularFeed {
+                url: feed.url,
+                subscribers: feed.subscribers,
+            })
+            .collect())
+    }
+}
+
+pub struct AdminMutation;
+
+#[Object]
+impl AdminMutation {
+    /// Remove the feed from the cache so that it is fetched on next request.
+    /// Return whether the feed was cached
+    async fn purge_feed_cache(&self, cx: &Context<'_>, url: String) -> Result<bool> {
+        let Output {
+            output: PurgeFeedCacheOutput { purged },
+        } = run_usecase!(
+            PurgeFeedCache,
+            cx,
+            PurgeFeedCacheInput { url },
+            internal_error
+        )?;
+
+        Ok(purged)
+    }
+
+    /// Fetch the feed bypassing the cache
+    async fn refresh_feed(&self, cx: &Context<'_>, url: String) -> Result<object::Feed> {
+        let Output {
+            output: RefreshFeedOutput { feed },
+        } = run_usecase!(
+            RefreshFeed,
+            cx,
+            RefreshFeedInput { url },
+            |err: RefreshFeedError| Err(err.extend())
+        )?;
+
+        Ok(feed.into())
+    }
+
+    /// Disable the user. Disabled users are not authorized to any operations
+    async fn disable_user(&self, cx: &Context<'_>, user_id: String) -> Result<bool> {
+        set_user_disabled(cx, user_id, true).await
+    }
+
+    /// Enable the disabled user
+    async fn enable_user(&self, cx: &Context<'_>, user_id: String) -> Result<bool> {
+        set_user_disabled(cx, user_id, false).await
+    }
+}
+
+async fn set_user_disabled(cx: &Context<'_>, user_id: String, disabled: bool) -> Result<bool> {
+    let input = SetUserDisabledInput { user_id, disabled };
+    let Output {
+        output: SetUserDisabledOutput {},
+    } = run_usecase!(SetUserDisabled, cx, input, internal_error)?;
+
+    Ok(true)
+}
+
+/// Entrypoint of admin queries
+pub(super) fn admin_query(cx: &Context<'_>) -> Result<AdminQuery> {
+    ensure_admin(cx).map(|()| AdminQuery)
+}
+
+/// Entrypoint of admin mutations
+pub(super) fn admin_mutation(cx: &Context<'_>) -> Result<AdminMutation> {
+    ensure_admin(cx).map(|()| AdminMutation)
+}
@@ -0,0 +1,198 @@
+//! Batch lookups of resolvers within a request
+//!
+//! Loaders are created for each request so that per-user data is not shared and
+//! loaded values are dropped with the request.
+//! Feeds and subscriptions are loaded in batches. Entries have no per-user state stored yet,
+//! so there is no loader for them.
+
+use std::{collections::HashMap, convert::Infallible, sync::Arc};
+
+use async_graphql::dataloader::{DataLoader, Loader};
+use synd_feed::{
+    feed::cache::FetchCachedFeed,
+    types::{self, FeedUrl},
+};
+
+use crate::{
+    principal::Principal,
+    repository::{types::Subscription, SubscriptionRepository},
+    usecase::MakeUsecase,
+};
+
+/// Load feeds from the feed cache. Feeds which could not be fetched are absent
+pub struct FeedLoader {
+    fetch_feed: Arc<dyn FetchCachedFeed>,
+}
+
+impl Loader<FeedUrl> for FeedLoader {
+    type Value = Arc<types::Feed>;
+    type Error = Infallible;
+
+    async fn load(&self, urls: &[FeedUrl]) -> Result<HashMap<FeedUrl, Self::Value>, Self::Error> {
+        let feeds = self.fetch_feed.fetch_feeds_parallel(urls).await;
+
+        Ok(urls
+            .iter()
+            .zip(feeds)
+            .filter_map(|(url, feed)| match feed {
+                Ok(feed) => Some((url.clone(), feed)),
+                Err(err) => {
+                    tracing::warn!(url, "Failed to load feed: {err}");
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+/// Load subscriptions of the user by feed url
+pub struct SubscriptionLoader {
+    repository: Arc<dyn SubscriptionRepository>,
+    user_id: Option<String>,
+}
+
+impl Loader<FeedUrl> for SubscriptionLoader {
+    type Value = Subscription;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(&self, urls: &[FeedUrl]) -> Result<HashMap<FeedUrl, Self::Value>, Self::Error> {
+        let Some(user_id) = self.user_id.as_deref() else {
+            return Ok(HashMap::new());
+        };
+
+        let subscriptions = self
+            .repository
+            .fetch_subscriptions(user_id)
+            .await
+            .map_err(|err| Arc::new(anyhow::Error::from(err)))?;
+
+        Ok(subscriptions
+            .into_iter()
+            .filter(|subscription| urls.contains(&subscription.url))
+            .map(|subscription| (subscription.url.clone(), subscription))
+            .collect())
+    }
+}
+
+/// Create the loaders for each request
+#[derive(Clone)]
+pub struct MakeLoaders {
+    fetch_feed: Arc<dyn FetchCachedFeed>,
+    subscription_repo: Arc<dyn SubscriptionRepository>,
+}
+
+impl MakeLoaders {
+    pub fn new(make: &MakeUsecase) -> Self {
+        Self {
+            fetch_feed: Arc::clone(&make.fetch_feed),
+            subscription_repo: Arc::clone(&make.subscription_repo),
+        }
+    }
+
+    pub fn make(&self, principal: &Principal) -> Loaders {
+        Loaders {
+            feed: DataLoader::new(
+                FeedLoader {
+                    fetch_feed: Arc::clone(&self.fetch_feed),
+                },
+                tokio::spawn,
+            ),
+            subscription: DataLoader::new(
+                SubscriptionLoader {
+                    repository: Arc::clone(&self.subscription_repo),
+                    user_id: principal.user_id().map(ToOwned::to_owned),
+                },
+                tokio::spawn,
+            ),
+        }
+    }
+}
+
+pub struct Loaders {
+    pub feed: DataLoader<FeedLoader>,
+    pub subscription: DataLoader<SubscriptionLoader>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+    use futures_util::future::join_all;
+
+    use crate::repository::{
+        memory::MemoryRepository,
+        types::{FeedSubscription, SubscriptionUpdate},
+        RepositoryResult,
+    };
+
+    use super::*;
+
+    /// Count the calls of `fetch_subscriptions`
+    struct CountingRepository {
+        inner: MemoryRepository,
+        fetch_count: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl SubscriptionRepository for CountingRepository {
+        async fn put_feed_subscription(&self, feed: FeedSubscription) -> RepositoryResult<()> {
+            self.inner.put_feed_subscription(feed).await
+        }
+
+        async fn delete_feed_subscription(&self, feed: FeedSubscription) -> RepositoryResult<()> {
+            self.inner.delete_feed_subscription(feed).await
+        }
+
+        async fn fetch_subscriptions(&self, user_id: &str) -> RepositoryResult<Vec<Subscription>> {
+            self.fetch_count.fetch_add(1, Ordering::SeqCst);
+            self.inner.fetch_subscriptions(user_id).await
+        }
+
+        async fn update_subscription(
+            &self,
+            user_id: &str,
+            url: &str,
+            update: SubscriptionUpdate,
+        ) -> RepositoryResult<Option<Subscription>> {
+            self.inner.update_subscription(user_id, url, update).await
+        }
+
+        async fn ping(&self) -> RepositoryResult<()> {
+            self.inner.ping().await
+        }
+
+        fn component_id(&self) -> String {
+            self.inner.component_id()
+        }
+    }
+
+    #[tokio::test]
+    async fn load_subscriptions_of_nodes_at_once() {
+        let repository = Arc::new(CountingRepository {
+            inner: MemoryRepository::new(),
+            fetch_count: AtomicUsize::new(0),
+        });
+        let loader = DataLoader::new(
+            SubscriptionLoader {
+                repository: repository.clone(),
+                user_id: Some("user".into()),
+            },
+            tokio::spawn,
+        );
+        let urls = repository
+            .inner
+            .fetch_subscribed_feed_urls("user")
+            .await
+            .unwrap();
+        assert!(urls.len() > 1);
+
+        // Resolvers of the nodes load their subscriptions concurrently
+        let subscriptions = join_all(urls.iter().map(|url| loader.load_one(url.clone()))).await;
+
+        assert!(subscriptions
+            .into_iter()
+            .all(|subscription| subscription.unwrap().is_some()));
+        assert_eq!(repository.fetch_count.load(Ordering::SeqCst), 1);
+    }
+}
@@ -2,20 +2,36 @@ mod query;
 pub use query::Query;
 
 mod mutation;
-use async_graphql::{EmptySubscription, Schema, SchemaBuilder};
+use async_graphql::{extensions::ApolloPersistedQueries, Schema, SchemaBuilder};
 pub use mutation::Mutation;
 
-use crate::{gql::mutation::ResponseCode, principal::Principal, usecase};
+mod subscription;
+pub use subscription::Subscription;
+
+mod admin;
+
+pub mod loader;
+mod persisted_query;
+pub use persisted_query::PersistedQueries;
+
+use crate::{gql::mutation::ResponseCode, principal::Principal, search::SearchError, usecase};
 
 pub mod object;
 pub mod scalar;
 
-pub type SyndSchema = Schema<Query, Mutation, EmptySubscription>;
+pub type SyndSchema = Schema<Query, Mutation, Subscription>;
 
 pub mod handler {
-    use async_graphql::http::GraphiQLSource;
-    use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
-    use axum::{response::IntoResponse, Extension};
+    use async_graphql::{
+        http::{GraphiQLSource, ALL_WEBSOCKET_PROTOCOLS},
+        Data,
+    };
+    use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
+    use axum::{
+        extract::WebSocketUpgrade,
+        response::{IntoResponse, Response},
+        Extension,
+    };
     use synd_o11y::audit_span;
     use tokio_metrics::TaskMonitor;
     use tracing::Instrument;
@@ -23,28 +39,70 @@ pub mod handler {
     use crate::{principal::Principal, serve::Context};
 
     pub async fn graphiql() -> impl IntoResponse {
-        axum::response::Html(GraphiQLSource::build().endpoint("/graphql").finish())
+        axum::response::Html(
+            GraphiQLSource::build()
+                .endpoint("/graphql")
+                .subscription_endpoint("/graphql/ws")
+                .finish(),
+        )
     }
 
     pub async fn graphql(
         Extension(Context {
             schema,
             gql_monitor,
+            make_loaders,
         }): Extension<Context>,
         Extension(principal): Extension<Principal>,
         req: GraphQLRequest,
     ) -> GraphQLResponse {
         // Inject authentication
-        let req = req.into_inner().data(principal);
+        let loaders = make_loaders.make(&principal);
+        let req = req.into_inner().data(principal).data(loaders);
         TaskMonitor::instrument(&gql_monitor, schema.execute(req).instrument(audit_span!()))
             .await
             .into()
     }
+
+    /// Serve graphql subscriptions over websocket
+    pub async fn graphql_ws(
+        Extension(Context {
+            schema,
+            make_loaders,
+            ..
+        }): Extension<Context>,
+        Extension(principal): Extension<Principal>,
+        protocol: GraphQLProtocol,
+        upgrade: WebSocketUpgrade,
+    ) -> Response {
+        upgrade
+            .protocols(ALL_WEBSOCKET_PROTOCOLS)
+            .on_upgrade(move |stream| {
+                // Inject authentication
+                let mut data = Data::default();
+                data.insert(make_loaders.make(&principal));
+                data.insert(principal);
+
+                GraphQLWebSocket::new(stream, schema, protocol)
+                    .with_data(data)
+                    .serve()
+            })
+    }
 }
 
 #[must_use]
-pub fn schema_builder() -> SchemaBuilder<Query, Mutation, EmptySubscription> {
-    let schema = Schema::build(Query, Mutation, EmptySubscription);
+pub fn schema_builder(
+    persisted_queries: PersistedQueries,
+) -> SchemaBuilder<Query, Mutation, Subscription> {
+    let mut schema = Schema::build(Query, Mutation, Subscription);
+
+    // Must precede persisted queries extension to inspect the request as sent
+    if persisted_queries.is_strict() {
+        schema = schema.extension(persisted_query::RegisteredQueriesOnly(
+            persisted_queries.clone(),
+        ));
+    }
+    let schema = schema.extension(ApolloPersistedQueries::new(persisted_queries));
 
     if cfg!(not(feature = "introspection")) {
         schema
@@ -73,6 +131,7 @@ where
             usecase::Error::Usecase(_) => unreachable!(),
             usecase::Error::Unauthorized(_) => ext.set("code", ResponseCode::Unauthorized),
             usecase::Error::Repository(_) => ext.set("code", ResponseCode::InternalError),
+            usecase::Error::QuotaExceeded(_) => ext.set("code", ResponseCode::QuotaExceeded),
         })
     }
 }
@@ -84,6 +143,39 @@ impl async_graphql::ErrorExtensions for usecase::FetchEntriesError {
     }
 }
 
+impl async_graphql::ErrorExtensions for usecase::SearchEntriesError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(format!("{self}")).extend_with(|_, ext| match self {
+            usecase::SearchEntriesError::Search(SearchError::InvalidQuery(_)) => {
+                ext.set("code", ResponseCode::InvalidSearchQuery);
+            }
+            usecase::SearchEntriesError::Search(_) => {
+                ext.set("code", ResponseCode::InternalError);
+            }
+            usecase::SearchEntriesError::OffsetTooLarge => {
+                ext.set("code", ResponseCode::InvalidInput);
+            }
+        })
+    }
+}
+
+impl async_graphql::ErrorExtensions for usecase::admin::RefreshFeedError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(format!("{self}")).extend_with(|_, ext| match self {
+            usecase::admin::RefreshFeedError::FetchFeed(_) => {
+                ext.set("code", ResponseCode::InvalidFeedUrl);
+            }
+        })
+    }
+}
+
+impl async_graphql::ErrorExtensions for usecase::WatchNewEntriesError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(format!("{self}"))
+            .extend_with(|_, ext| ext.set("code", ResponseCode::InternalError))
+    }
+}
+
 impl async_graphql::ErrorExtensions for usecase::FetchSubscribedFeedsError {
     fn extend(&self) -> async_graphql::Error {
         async_graphql::Error::new(format!("{self}"))
@@ -91,6 +183,14 @@ impl async_graphql::ErrorExtensions for usecase::FetchSubscribedFeedsError {
     }
 }
 
+/// Convert usecase errors which do not have specific response codes
+fn internal_error<T>(err: anyhow::Error) -> async_graphql::Result<T> {
+    Err(async_graphql::ErrorExtensions::extend_with(
+        async_graphql::Error::new(format!("{err}")),
+        |_, ext| ext.set("code", ResponseCode::InternalError),
+    ))
+}
+
 macro_rules! run_usecase {
     ($usecase:ty, $cx:expr, $input:expr,$err_handle:expr) => {{
         let runtime = $cx.data_unchecked::<crate::usecase::Runtime>();
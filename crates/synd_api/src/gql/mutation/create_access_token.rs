@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use async_graphql::{InputObject, Object, Union};
+
+use crate::{
+    gql::{
+        mutation::ResponseStatus,
+        object::{AccessToken, AccessTokenScope},
+    },
+    repository, usecase,
+};
+
+#[derive(InputObject)]
+pub struct CreateAccessTokenInput {
+    /// Name to identify the token
+    pub name: String,
+    pub scopes: Vec<AccessTokenScope>,
+    /// Number of days until the token expires. The token does not expire if not specified
+    pub expires_in_days: Option<u32>,
+}
+
+impl From<CreateAccessTokenInput> for usecase::CreateAccessTokenInput {
+    fn from(value: CreateAccessTokenInput) -> Self {
+        usecase::CreateAccessTokenInput {
+            name: value.name,
+            scopes: value.scopes.into_iter().map(Into::into).collect(),
+            expires_in: value
+                .expires_in_days
+                .map(|days| Duration::from_secs(u64::from(days) * 60 * 60 * 24)),
+        }
+    }
+}
+
+#[derive(Union)]
+pub enum CreateAccessTokenResponse {
+    Success(CreateAccessTokenSuccess),
+    Error(CreateAccessTokenError),
+}
+
+pub struct CreateAccessTokenSuccess {
+    pub status: ResponseStatus,
+    pub token: String,
+    pub access_token: repository::types::AccessToken,
+}
+
+#[Object]
+impl CreateAccessTokenSuccess {
+    pub async fn status(&self) -> ResponseStatus {
+        self.status.clone()
+    }
+
+    /// Token to be sent with `token` scheme. It can not be retrieved again
+    pub async fn token(&self) -> &str {
+        self.token.as_str()
+    }
+
+    /// Created access token
+    pub async fn access_token(&self) -> AccessToken {
+        self.access_token.clone().into()
+    }
+}
+
+pub struct CreateAccessTokenError {
+    pub status: ResponseStatus,
+    pub message: String,
+}
+
+#[Object]
+impl CreateAccessTokenError {
+    pub async fn status(&self) -> ResponseStatus {
+        self.status.clone()
+    }
+
+    /// Error message
+    pub async fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+
+impl From<usecase::CreateAccessTokenError> for CreateAccessTokenResponse {
+    fn from(err: usecase::CreateAccessTokenError) -> Self {
+        let status = match err {
+            usecase::CreateAccessTokenError::InvalidInput(_) => ResponseStatus::invalid_input(),
+        };
+        CreateAccessTokenResponse::Error(CreateAccessTokenError {
+            status,
+            message: format!("{err}"),
+        })
+    }
+}
+
+impl From<usecase::Output<usecase::CreateAccessTokenOutput>> for CreateAccessTokenResponse {
+    fn from(output: usecase::Output<usecase::CreateAccessTokenOutput>) -> Self {
+        let usecase::CreateAccessTokenOutput {
+            token,
+            access_token,
+        } = output.output;
+
+        CreateAccessTokenResponse::Success(CreateAccessTokenSuccess {
+            status: ResponseStatus::ok(),
+            token,
+            access_token,
+        })
+    }
+}
@@ -0,0 +1,73 @@
+use async_graphql::{InputObject, Object, Union};
+
+use crate::{gql::mutation::ResponseStatus, usecase};
+
+#[derive(InputObject)]
+pub struct DeleteMyAccountInput {
+    /// Token issued by `requestAccountDeletion`
+    pub confirmation_token: String,
+}
+
+impl From<DeleteMyAccountInput> for usecase::DeleteMyAccountInput {
+    fn from(value: DeleteMyAccountInput) -> Self {
+        usecase::DeleteMyAccountInput {
+            confirmation_token: value.confirmation_token,
+        }
+    }
+}
+
+#[derive(Union)]
+pub enum DeleteMyAccountResponse {
+    Success(DeleteMyAccountSuccess),
+    Error(DeleteMyAccountError),
+}
+
+pub struct DeleteMyAccountSuccess {
+    pub status: ResponseStatus,
+}
+
+#[Object]
+impl DeleteMyAccountSuccess {
+    pub async fn status(&self) -> ResponseStatus {
+        self.status.clone()
+    }
+}
+
+pub struct DeleteMyAccountError {
+    pub status: ResponseStatus,
+    pub message: String,
+}
+
+#[Object]
+impl DeleteMyAccountError {
+    pub async fn status(&self) -> ResponseStatus {
+        self.status.clone()
+    }
+
+    /// Error message
+    pub async fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+
+impl From<usecase::DeleteMyAccountError> for DeleteMyAccountResponse {
+    fn from(err: usecase::DeleteMyAccountError) -> Self {
+        let status = match err {
+            usecase::DeleteMyAccountError::InvalidConfirmationToken => {
+                ResponseStatus::invalid_confirmation_token()
+            }
+        };
+        DeleteMyAccountResponse::Error(DeleteMyAccountError {
+            status,
+            message: format!("{err}"),
+        })
+    }
+}
+
+impl From<usecase::Output<usecase::DeleteMyAccountOutput>> for DeleteMyAccountResponse {
+    fn from(_output: usecase::Output<usecase::DeleteMyAccountOutput>) -> Self {
+        DeleteMyAccountResponse::Success(DeleteMyAccountSuccess {
+            status: ResponseStatus::ok(),
+        })
+    }
+}
@@ -1,12 +1,27 @@
 use async_graphql::{Context, Enum, Interface, Object, SimpleObject};
 
 use crate::{
-    gql::run_usecase,
-    usecase::{SubscribeFeed, SubscribeFeedError, UnsubscribeFeed},
+    gql::{
+        admin::{self, AdminMutation},
+        run_usecase,
+    },
+    usecase::{
+        CreateAccessToken, CreateAccessTokenError, DeleteMyAccount, DeleteMyAccountError,
+        RequestAccountDeletion, RequestAccountDeletionInput, RevokeAccessToken,
+        RevokeAccessTokenError, SubscribeFeed, SubscribeFeedError, SubscribeFeeds, UnsubscribeFeed,
+        UnsubscribeFeeds, UpdateSubscription, UpdateSubscriptionError,
+    },
 };
 
+pub mod create_access_token;
+pub mod delete_my_account;
+pub mod request_account_deletion;
+pub mod revoke_access_token;
 pub mod subscribe_feed;
+pub mod subscribe_feeds;
 pub mod unsubscribe_feed;
+pub mod unsubscribe_feeds;
+pub mod update_subscription;
 
 #[derive(Enum, PartialEq, Eq, Clone, Copy)]
 pub enum ResponseCode {
@@ -16,6 +31,16 @@ pub enum ResponseCode {
     Unauthorized,
     /// Given url is not valid feed url
     InvalidFeedUrl,
+    /// Requested resource not found
+    NotFound,
+    /// Given search query is not valid
+    InvalidSearchQuery,
+    /// User's quota such as the number of subscriptions is exceeded
+    QuotaExceeded,
+    /// Given confirmation token is invalid or expired
+    InvalidConfirmationToken,
+    /// Given input is not valid
+    InvalidInput,
     /// Something went wrong
     InternalError,
 }
@@ -46,6 +71,24 @@ impl ResponseStatus {
         }
     }
 
+    fn not_found() -> Self {
+        Self {
+            code: ResponseCode::NotFound,
+        }
+    }
+
+    fn invalid_confirmation_token() -> Self {
+        Self {
+            code: ResponseCode::InvalidConfirmationToken,
+        }
+    }
+
+    fn invalid_input() -> Self {
+        Self {
+            code: ResponseCode::InvalidInput,
+        }
+    }
+
     fn internal() -> Self {
         Self {
             code: ResponseCode::InternalError,
@@ -58,7 +101,14 @@ impl ResponseStatus {
 #[graphql(field(name = "status", method = "status", ty = "ResponseStatus"))]
 enum MutationResponse {
     SubscribeFeed(subscribe_feed::SubscribeFeedSuccess),
+    SubscribeFeeds(subscribe_feeds::SubscribeFeedsSuccess),
     UnsubscribeFeed(unsubscribe_feed::UnsubscribeFeedSuccess),
+    UnsubscribeFeeds(unsubscribe_feeds::UnsubscribeFeedsSuccess),
+    UpdateSubscription(update_subscription::UpdateSubscriptionSuccess),
+    RequestAccountDeletion(request_account_deletion::RequestAccountDeletionSuccess),
+    DeleteMyAccount(delete_my_account::DeleteMyAccountSuccess),
+    CreateAccessToken(create_access_token::CreateAccessTokenSuccess),
+    RevokeAccessToken(revoke_access_token::RevokeAccessTokenSuccess),
 }
 
 #[derive(Interface)]
@@ -68,13 +118,25 @@ enum MutationResponse {
 )]
 enum ErrorResponse {
     SubscribeFeed(subscribe_feed::SubscribeFeedError),
+    SubscribeFeeds(subscribe_feeds::SubscribeFeedsError),
     UnsubscribeFeed(unsubscribe_feed::UnsubscribeFeedError),
+    UnsubscribeFeeds(unsubscribe_feeds::UnsubscribeFeedsError),
+    UpdateSubscription(update_subscription::UpdateSubscriptionError),
+    RequestAccountDeletion(request_account_deletion::RequestAccountDeletionError),
+    DeleteMyAccount(delete_my_account::DeleteMyAccountError),
+    CreateAccessToken(create_access_token::CreateAccessTokenError),
+    RevokeAccessToken(revoke_access_token::RevokeAccessTokenError),
 }
 
 pub struct Mutation;
 
 #[Object]
 impl Mutation {
+    /// Administrative mutations. Only admins are authorized
+    async fn admin(&self, cx: &Context<'_>) -> async_graphql::Result<AdminMutation> {
+        admin::admin_mutation(cx)
+    }
+
     /// Subscribe feed
     async fn subscribe_feed(
         &self,
@@ -86,6 +148,18 @@ impl Mutation {
         ))
     }
 
+    /// Subscribe multiple feeds.
+    /// Feeds are fetched in parallel and the status is reported for each url
+    async fn subscribe_feeds(
+        &self,
+        cx: &Context<'_>,
+        inputs: Vec<subscribe_feed::SubscribeFeedInput>,
+    ) -> async_graphql::Result<subscribe_feeds::SubscribeFeedsResponse> {
+        run_usecase!(SubscribeFeeds, cx, inputs, |err: anyhow::Error| Ok(
+            err.into()
+        ))
+    }
+
     /// Unsubscribe feed
     /// If given feed is not subscribed, this mutation will succeed
     async fn unsubscribe_feed(
@@ -97,4 +171,81 @@ impl Mutation {
             err.into()
         ))
     }
+
+    /// Unsubscribe multiple feeds
+    /// Feeds which are not subscribed are reported as succeeded
+    async fn unsubscribe_feeds(
+        &self,
+        cx: &Context<'_>,
+        inputs: Vec<unsubscribe_feed::UnsubscribeFeedInput>,
+    ) -> async_graphql::Result<unsubscribe_feeds::UnsubscribeFeedsResponse> {
+        run_usecase!(UnsubscribeFeeds, cx, inputs, |err: anyhow::Error| Ok(
+            err.into()
+        ))
+    }
+
+    /// Update subscription metadata such as title, folder and tags
+    async fn update_subscription(
+        &self,
+        cx: &Context<'_>,
+        input: update_subscription::UpdateSubscriptionInput,
+    ) -> async_graphql::Result<update_subscription::UpdateSubscriptionResponse> {
+        run_usecase!(
+            UpdateSubscription,
+            cx,
+            input,
+            |err: UpdateSubscriptionError| Ok(err.into())
+        )
+    }
+    /// Issue a confirmation token which is required to delete the account
+    async fn request_account_deletion(
+        &self,
+        cx: &Context<'_>,
+    ) -> async_graphql::Result<request_account_deletion::RequestAccountDeletionResponse> {
+        run_usecase!(
+            RequestAccountDeletion,
+            cx,
+            RequestAccountDeletionInput {},
+            |err: anyhow::Error| Ok(err.into())
+        )
+    }
+
+    /// Delete all the data stored for the user
+    async fn delete_my_account(
+        &self,
+        cx: &Context<'_>,
+        input: delete_my_account::DeleteMyAccountInput,
+    ) -> async_graphql::Result<delete_my_account::DeleteMyAccountResponse> {
+        run_usecase!(DeleteMyAccount, cx, input, |err: DeleteMyAccountError| Ok(
+            err.into()
+        ))
+    }
+
+    /// Issue a personal access token to be sent with `token` scheme
+    async fn create_access_token(
+        &self,
+        cx: &Context<'_>,
+        input: create_access_token::CreateAccessTokenInput,
+    ) -> async_graphql::Result<create_access_token::CreateAccessTokenResponse> {
+        run_usecase!(
+            CreateAccessToken,
+            cx,
+            input,
+            |err: CreateAccessTokenError| Ok(err.into())
+        )
+    }
+
+    /// Revoke the personal access token. Revoked token is rejected immediately
+    async fn revoke_access_token(
+        &self,
+        cx: &Context<'_>,
+        input: revoke_access_token::RevokeAccessTokenInput,
+    ) -> async_graphql::Result<revoke_access_token::RevokeAccessTokenResponse> {
+        run_usecase!(
+            RevokeAccessToken,
+            cx,
+            input,
+            |err: RevokeAccessTokenError| Ok(err.into())
+        )
+    }
 }
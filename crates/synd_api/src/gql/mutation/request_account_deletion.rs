@@ -0,0 +1,79 @@
+use async_graphql::{Object, Union};
+use synd_feed::types::Time;
+
+use crate::{
+    gql::{mutation::ResponseStatus, scalar::Rfc3339Time},
+    usecase,
+};
+
+#[derive(Union)]
+pub enum RequestAccountDeletionResponse {
+    Success(RequestAccountDeletionSuccess),
+    Error(RequestAccountDeletionError),
+}
+
+pub struct RequestAccountDeletionSuccess {
+    pub status: ResponseStatus,
+    pub confirmation_token: String,
+    pub expires_at: Time,
+}
+
+#[Object]
+impl RequestAccountDeletionSuccess {
+    pub async fn status(&self) -> ResponseStatus {
+        self.status.clone()
+    }
+
+    /// Token to be passed to `deleteMyAccount`
+    pub async fn confirmation_token(&self) -> &str {
+        self.confirmation_token.as_str()
+    }
+
+    /// The time at which the token expires
+    pub async fn expires_at(&self) -> Rfc3339Time {
+        self.expires_at.into()
+    }
+}
+
+pub struct RequestAccountDeletionError {
+    pub status: ResponseStatus,
+    pub message: String,
+}
+
+#[Object]
+impl RequestAccountDeletionError {
+    pub async fn status(&self) -> ResponseStatus {
+        self.status.clone()
+    }
+
+    /// Error message
+    pub async fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+
+impl From<anyhow::Error> for RequestAccountDeletionResponse {
+    fn from(err: anyhow::Error) -> Self {
+        RequestAccountDeletionResponse::Error(RequestAccountDeletionError {
+            status: ResponseStatus::internal(),
+            message: format!("{err}"),
+        })
+    }
+}
+
+impl From<usecase::Output<usecase::RequestAccountDeletionOutput>>
+    for RequestAccountDeletionResponse
+{
+    fn from(output: usecase::Output<usecase::RequestAccountDeletionOutput>) -> Self {
+        let usecase::RequestAccountDeletionOutput {
+            confirmation_token,
+            expires_at,
+        } = output.output;
+
+        RequestAccountDeletionResponse::Success(RequestAccountDeletionSuccess {
+            status: ResponseStatus::ok(),
+            confirmation_token,
+            expires_at,
+        })
+    }
+}
@@ -0,0 +1,69 @@
+use async_graphql::{InputObject, Object, Union, ID};
+
+use crate::{gql::mutation::ResponseStatus, usecase};
+
+#[derive(InputObject)]
+pub struct RevokeAccessTokenInput {
+    /// Id of the access token to revoke
+    pub id: ID,
+}
+
+impl From<RevokeAccessTokenInput> for usecase::RevokeAccessTokenInput {
+    fn from(value: RevokeAccessTokenInput) -> Self {
+        usecase::RevokeAccessTokenInput { id: value.id.0 }
+    }
+}
+
+#[derive(Union)]
+pub enum RevokeAccessTokenResponse {
+    Success(RevokeAccessTokenSuccess),
+    Error(RevokeAccessTokenError),
+}
+
+pub struct RevokeAccessTokenSuccess {
+    pub status: ResponseStatus,
+}
+
+#[Object]
+impl RevokeAccessTokenSuccess {
+    pub async fn status(&self) -> ResponseStatus {
+        self.status.clone()
+    }
+}
+
+pub struct RevokeAccessTokenError {
+    pub status: ResponseStatus,
+    pub message: String,
+}
+
+#[Object]
+impl RevokeAccessTokenError {
+    pub async fn status(&self) -> ResponseStatus {
+        self.status.clone()
+    }
+
+    /// Error message
+    pub async fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+
+impl From<usecase::RevokeAccessTokenError> for RevokeAccessTokenResponse {
+    fn from(err: usecase::RevokeAccessTokenError) -> Self {
+        let status = match err {
+            usecase::RevokeAccessTokenError::NotFound => ResponseStatus::not_found(),
+        };
+        RevokeAccessTokenResponse::Error(RevokeAccessTokenError {
+            status,
+            message: format!("{err}"),
+        })
+    }
+}
+
+impl From<usecase::Output<usecase::RevokeAccessTokenOutput>> for RevokeAccessTokenResponse {
+    fn from(_output: usecase::Output<usecase::RevokeAccessTokenOutput>) -> Self {
+        RevokeAccessTokenResponse::Success(RevokeAccessTokenSuccess {
+            status: ResponseStatus::ok(),
+        })
+    }
+}
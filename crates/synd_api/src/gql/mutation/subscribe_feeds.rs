@@ -0,0 +1,138 @@
+use async_graphql::{Object, Union};
+
+use crate::{
+    gql::{
+        mutation::{subscribe_feed, ResponseStatus},
+        object::{self, Feed},
+    },
+    usecase,
+};
+
+impl From<Vec<subscribe_feed::SubscribeFeedInput>> for usecase::SubscribeFeedsInput {
+    fn from(inputs: Vec<subscribe_feed::SubscribeFeedInput>) -> Self {
+        usecase::SubscribeFeedsInput {
+            urls: inputs.into_iter().map(|input| input.url).collect(),
+        }
+    }
+}
+
+#[derive(Union)]
+pub enum SubscribeFeedsResponse {
+    Success(SubscribeFeedsSuccess),
+    Error(SubscribeFeedsError),
+}
+
+pub struct SubscribeFeedsSuccess {
+    pub status: ResponseStatus,
+    pub results: Vec<SubscribeFeedResult>,
+}
+
+#[Object]
+impl SubscribeFeedsSuccess {
+    pub async fn status(&self) -> ResponseStatus {
+        self.status.clone()
+    }
+
+    /// Result of each url in the order of inputs
+    pub async fn results(&self) -> &[SubscribeFeedResult] {
+        self.results.as_slice()
+    }
+}
+
+pub struct SubscribeFeedResult {
+    pub url: String,
+    pub status: ResponseStatus,
+    pub feed: Option<object::Feed>,
+    pub message: Option<String>,
+}
+
+#[Object]
+impl SubscribeFeedResult {
+    /// Requested feed url
+    pub async fn url(&self) -> &str {
+        self.url.as_str()
+    }
+
+    pub async fn status(&self) -> ResponseStatus {
+        self.status.clone()
+    }
+
+    /// Subscribed feed if succeeded
+    pub async fn feed(&self) -> Option<&object::Feed> {
+        self.feed.as_ref()
+    }
+
+    /// Error message if failed
+    pub async fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+}
+
+pub struct SubscribeFeedsError {
+    pub status: ResponseStatus,
+    pub message: String,
+}
+
+#[Object]
+impl SubscribeFeedsError {
+    pub async fn status(&self) -> ResponseStatus {
+        self.status.clone()
+    }
+
+    /// Error message
+    pub async fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+
+impl From<ResponseStatus> for SubscribeFeedsResponse {
+    fn from(status: ResponseStatus) -> Self {
+        SubscribeFeedsResponse::Error(SubscribeFeedsError {
+            status,
+            message: "Unauthorized".into(),
+        })
+    }
+}
+
+impl From<anyhow::Error> for SubscribeFeedsResponse {
+    fn from(err: anyhow::Error) -> Self {
+        SubscribeFeedsResponse::Error(SubscribeFeedsError {
+            status: ResponseStatus::internal(),
+            message: format!("{err}"),
+        })
+    }
+}
+
+impl From<usecase::Output<usecase::SubscribeFeedsOutput>> for SubscribeFeedsResponse {
+    fn from(output: usecase::Output<usecase::SubscribeFeedsOutput>) -> Self {
+        let results = output
+            .output
+            .results
+            .into_iter()
+            .map(
+                |usecase::SubscribeFeedsResult { url, result }| match result {
+                    Ok(feed) => SubscribeFeedResult {
+                        url,
+                        status: ResponseStatus::ok(),
+                        feed: Some(Feed::from(feed)),
+                        message: None,
+                    },
+                    Err(err) => {
+                        let err = subscribe_feed::SubscribeFeedError::from(err);
+                        SubscribeFeedResult {
+                            url,
+                            status: err.status,
+                            feed: None,
+                            message: Some(err.message),
+                        }
+                    }
+                },
+            )
+            .collect();
+
+        SubscribeFeedsResponse::Success(SubscribeFeedsSuccess {
+            status: ResponseStatus::ok(),
+            results,
+        })
+    }
+}
@@ -0,0 +1,104 @@
+use async_graphql::{Object, SimpleObject, Union};
+
+use crate::{
+    gql::mutation::{unsubscribe_feed, ResponseStatus},
+    usecase,
+};
+
+impl From<Vec<unsubscribe_feed::UnsubscribeFeedInput>> for usecase::UnsubscribeFeedsInput {
+    fn from(inputs: Vec<unsubscribe_feed::UnsubscribeFeedInput>) -> Self {
+        usecase::UnsubscribeFeedsInput {
+            urls: inputs.into_iter().map(|input| input.url).collect(),
+        }
+    }
+}
+
+#[derive(Union)]
+pub enum UnsubscribeFeedsResponse {
+    Success(UnsubscribeFeedsSuccess),
+    Error(UnsubscribeFeedsError),
+}
+
+pub struct UnsubscribeFeedsSuccess {
+    pub status: ResponseStatus,
+    pub results: Vec<UnsubscribeFeedResult>,
+}
+
+#[Object]
+impl UnsubscribeFeedsSuccess {
+    pub async fn status(&self) -> ResponseStatus {
+        self.status.clone()
+    }
+
+    /// Result of each url in the order of inputs
+    pub async fn results(&self) -> &[UnsubscribeFeedResult] {
+        self.results.as_slice()
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct UnsubscribeFeedResult {
+    /// Requested feed url
+    pub url: String,
+    /// `NOT_FOUND` if the feed was not subscribed
+    pub status: ResponseStatus,
+}
+
+pub struct UnsubscribeFeedsError {
+    pub status: ResponseStatus,
+    pub message: String,
+}
+
+#[Object]
+impl UnsubscribeFeedsError {
+    pub async fn status(&self) -> ResponseStatus {
+        self.status.clone()
+    }
+
+    /// Error message
+    pub async fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+
+impl From<ResponseStatus> for UnsubscribeFeedsResponse {
+    fn from(status: ResponseStatus) -> Self {
+        UnsubscribeFeedsResponse::Error(UnsubscribeFeedsError {
+            status,
+            message: "Unauthorized".into(),
+        })
+    }
+}
+
+impl From<anyhow::Error> for UnsubscribeFeedsResponse {
+    fn from(err: anyhow::Error) -> Self {
+        UnsubscribeFeedsResponse::Error(UnsubscribeFeedsError {
+            status: ResponseStatus::internal(),
+            message: format!("{err}"),
+        })
+    }
+}
+
+impl From<usecase::Output<usecase::UnsubscribeFeedsOutput>> for UnsubscribeFeedsResponse {
+    fn from(output: usecase::Output<usecase::UnsubscribeFeedsOutput>) -> Self {
+        let results = output
+            .output
+            .results
+            .into_iter()
+            .map(
+                |usecase::UnsubscribeFeedsResult { url, status }| UnsubscribeFeedResult {
+                    url,
+                    status: match status {
+                        usecase::UnsubscribeStatus::Unsubscribed => ResponseStatus::ok(),
+                        usecase::UnsubscribeStatus::NotSubscribed => ResponseStatus::not_found(),
+                    },
+                },
+            )
+            .collect();
+
+        UnsubscribeFeedsResponse::Success(UnsubscribeFeedsSuccess {
+            status: ResponseStatus::ok(),
+            results,
+        })
+    }
+}
@@ -0,0 +1,114 @@
+use async_graphql::{InputObject, MaybeUndefined, Object, Union};
+
+use crate::{
+    gql::{mutation::ResponseStatus, object},
+    repository::types::SubscriptionUpdate,
+    usecase::{self, UpdateSubscriptionError as UsecaseUpdateSubscriptionError},
+};
+
+#[derive(InputObject)]
+pub struct UpdateSubscriptionInput {
+    /// Subscribed feed url to update
+    pub url: String,
+    /// User defined title. null removes the title
+    pub title: MaybeUndefined<String>,
+    /// Folder of the subscription. null removes the folder
+    pub folder: MaybeUndefined<String>,
+    /// Tags of the subscription. replace existing tags
+    pub tags: Option<Vec<String>>,
+    /// Pause or resume the subscription
+    pub paused: Option<bool>,
+}
+
+impl From<UpdateSubscriptionInput> for usecase::UpdateSubscriptionInput {
+    fn from(value: UpdateSubscriptionInput) -> Self {
+        let UpdateSubscriptionInput {
+            url,
+            title,
+            folder,
+            tags,
+            paused,
+        } = value;
+
+        usecase::UpdateSubscriptionInput {
+            url,
+            update: SubscriptionUpdate {
+                title: title.into(),
+                folder: folder.into(),
+                tags,
+                paused,
+            },
+        }
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Union)]
+pub enum UpdateSubscriptionResponse {
+    Success(UpdateSubscriptionSuccess),
+    Error(UpdateSubscriptionError),
+}
+
+pub struct UpdateSubscriptionSuccess {
+    pub status: ResponseStatus,
+    /// Updated subscription
+    pub subscription: object::FeedSubscription,
+}
+
+#[Object]
+impl UpdateSubscriptionSuccess {
+    pub async fn status(&self) -> ResponseStatus {
+        self.status.clone()
+    }
+
+    pub async fn subscription(&self) -> &object::FeedSubscription {
+        &self.subscription
+    }
+}
+
+pub struct UpdateSubscriptionError {
+    pub status: ResponseStatus,
+    pub message: String,
+}
+
+#[Object]
+impl UpdateSubscriptionError {
+    pub async fn status(&self) -> ResponseStatus {
+        self.status.clone()
+    }
+
+    /// Error message
+    pub async fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+
+impl From<ResponseStatus> for UpdateSubscriptionResponse {
+    fn from(status: ResponseStatus) -> Self {
+        UpdateSubscriptionResponse::Error(UpdateSubscriptionError {
+            status,
+            message: "Unauthorized".into(),
+        })
+    }
+}
+
+impl From<usecase::Output<usecase::UpdateSubscriptionOutput>> for UpdateSubscriptionResponse {
+    fn from(output: usecase::Output<usecase::UpdateSubscriptionOutput>) -> Self {
+        UpdateSubscriptionResponse::Success(UpdateSubscriptionSuccess {
+            status: ResponseStatus::ok(),
+            subscription: output.output.subscription.into(),
+        })
+    }
+}
+
+impl From<UsecaseUpdateSubscriptionError> for UpdateSubscriptionResponse {
+    fn from(err: UsecaseUpdateSubscriptionError) -> Self {
+        let status = match err {
+            UsecaseUpdateSubscriptionError::NotFound { .. } => ResponseStatus::not_found(),
+        };
+        UpdateSubscriptionResponse::Error(UpdateSubscriptionError {
+            status,
+            message: format!("{err}"),
+        })
+    }
+}
@@ -1,7 +1,8 @@
-use std::convert::Infallible;
-
 use async_graphql::connection::CursorType;
-use synd_feed::types;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use thiserror::Error;
+
+use crate::usecase;
 
 pub enum Id {
     V1(IdV1),
@@ -14,9 +15,17 @@ pub enum IdV1 {
 pub struct FeedIdV1(String);
 
 impl FeedIdV1 {
+    const PREFIX: &'static str = "v1:feed:";
+
     pub fn new(url: impl AsRef<str>) -> Self {
         let url = url.as_ref();
-        Self(format!("v1:feed:{url}"))
+        Self(format!("{}{url}", Self::PREFIX))
+    }
+
+    /// Return feed url of given feed id.
+    /// If given value is not a feed id, it is regarded as a feed url
+    pub fn url_of(id_or_url: &str) -> &str {
+        id_or_url.strip_prefix(Self::PREFIX).unwrap_or(id_or_url)
     }
 }
 
@@ -26,23 +35,75 @@ impl From<FeedIdV1> for async_graphql::ID {
     }
 }
 
-pub(in crate::gql) struct EntryId<'a>(types::EntryId<'a>);
+/// Opaque cursor of entries which encodes the sort key of the entry
+pub(in crate::gql) struct EntryCursor(usecase::EntryCursor);
 
-impl<'a> CursorType for EntryId<'a> {
-    type Error = Infallible;
+#[derive(Error, Debug)]
+pub(in crate::gql) enum EntryCursorError {
+    #[error("invalid cursor")]
+    Decode,
+}
+
+impl CursorType for EntryCursor {
+    type Error = EntryCursorError;
 
     fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
-        let s = s.to_string();
-        Ok(EntryId(s.into()))
+        let bytes = URL_SAFE_NO_PAD
+            .decode(s)
+            .map_err(|_| EntryCursorError::Decode)?;
+        let cursor = serde_json::from_slice(&bytes).map_err(|_| EntryCursorError::Decode)?;
+        Ok(EntryCursor(cursor))
     }
 
     fn encode_cursor(&self) -> String {
-        self.0.to_string()
+        let bytes = serde_json::to_vec(&self.0).expect("serialize cursor never fail");
+        URL_SAFE_NO_PAD.encode(bytes)
     }
 }
 
-impl<'a> From<types::EntryId<'a>> for EntryId<'a> {
-    fn from(value: types::EntryId<'a>) -> Self {
+impl From<usecase::EntryCursor> for EntryCursor {
+    fn from(value: usecase::EntryCursor) -> Self {
         Self(value)
     }
 }
+
+impl From<EntryCursor> for usecase::EntryCursor {
+    fn from(value: EntryCursor) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_of_feed_id() {
+        let url = "https://example.com/feed.xml";
+        let id = async_graphql::ID::from(FeedIdV1::new(url));
+
+        assert_eq!(FeedIdV1::url_of(&id), url);
+        // Urls are returned as they are
+        assert_eq!(FeedIdV1::url_of(url), url);
+    }
+
+    #[test]
+    fn entry_cursor_round_trip() {
+        let cursor = EntryCursor(usecase::EntryCursor {
+            published: None,
+            feed_url: "https://example.com/feed.xml".into(),
+            entry_id: "entry-1".into(),
+        });
+        let decoded = EntryCursor::decode_cursor(&cursor.encode_cursor()).unwrap();
+        assert_eq!(decoded.0, cursor.0);
+    }
+
+    #[test]
+    fn malformed_entry_cursor() {
+        // Not base64
+        assert!(EntryCursor::decode_cursor("!!!").is_err());
+        // Base64 but not a cursor
+        let not_cursor = URL_SAFE_NO_PAD.encode(br#"{"foo":1}"#);
+        assert!(EntryCursor::decode_cursor(&not_cursor).is_err());
+    }
+}
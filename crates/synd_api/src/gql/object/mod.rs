@@ -2,12 +2,15 @@ use std::{borrow::Cow, sync::Arc};
 
 use async_graphql::{
     connection::{Connection, ConnectionNameType, Edge, EdgeNameType, EmptyFields},
-    Enum, Object, SimpleObject, ID,
+    Context, Enum, Object, Result, SimpleObject, ID,
 };
 use feed_rs::model as feedrs;
 use synd_feed::types;
 
-use crate::gql::scalar;
+use crate::{
+    gql::{loader::Loaders, scalar},
+    repository, search, usecase,
+};
 
 use self::id::FeedIdV1;
 
@@ -36,7 +39,25 @@ impl From<feedrs::Link> for Link {
 
 pub struct Entry<'a> {
     meta: Cow<'a, types::FeedMeta>,
-    entry: types::Entry,
+    entry: EntrySource,
+}
+
+/// Entry is served from the fetched feed or the archive
+pub enum EntrySource {
+    Feed(types::Entry),
+    Archive(repository::types::ArchivedEntry),
+}
+
+impl From<types::Entry> for EntrySource {
+    fn from(entry: types::Entry) -> Self {
+        EntrySource::Feed(entry)
+    }
+}
+
+impl From<repository::types::ArchivedEntry> for EntrySource {
+    fn from(entry: repository::types::ArchivedEntry) -> Self {
+        EntrySource::Archive(entry)
+    }
 }
 
 #[Object]
@@ -47,35 +68,63 @@ impl<'a> Entry<'a> {
     }
     /// Entry title
     async fn title(&self) -> Option<&str> {
-        self.entry.title()
+        match &self.entry {
+            EntrySource::Feed(entry) => entry.title(),
+            EntrySource::Archive(entry) => entry.title.as_deref(),
+        }
     }
 
     /// Time at which the entry was last modified
     async fn updated(&self) -> Option<scalar::Rfc3339Time> {
-        self.entry.updated().map(Into::into)
+        match &self.entry {
+            EntrySource::Feed(entry) => entry.updated(),
+            EntrySource::Archive(entry) => entry.updated,
+        }
+        .map(Into::into)
     }
 
     /// The time at which the entry published
     async fn published(&self) -> Option<scalar::Rfc3339Time> {
-        self.entry.published().map(Into::into)
+        match &self.entry {
+            EntrySource::Feed(entry) => entry.published(),
+            EntrySource::Archive(entry) => entry.published,
+        }
+        .map(Into::into)
     }
 
     /// Entry summary. If there is no summary of the entry, return the content(is this bad api?)
     async fn summary(&self) -> Option<&str> {
-        self.entry.summary().or(self.entry.content())
+        match &self.entry {
+            EntrySource::Feed(entry) => entry.summary().or(entry.content()),
+            EntrySource::Archive(entry) => entry.summary.as_deref().or(entry.content.as_deref()),
+        }
     }
 
     /// Link to websiteurl at which this entry is published
     async fn website_url(&self) -> Option<&str> {
-        self.entry.website_url(self.meta.r#type())
+        match &self.entry {
+            EntrySource::Feed(entry) => entry.website_url(self.meta.r#type()),
+            EntrySource::Archive(entry) => entry.website_url.as_deref(),
+        }
+    }
+
+    /// User's subscription of the feed to which this entry belongs
+    async fn subscription(&self, cx: &Context<'_>) -> Result<Option<FeedSubscription>> {
+        let loaders = cx.data::<Loaders>()?;
+        let subscription = loaders
+            .subscription
+            .load_one(self.meta.url().to_owned())
+            .await?;
+
+        Ok(subscription.map(Into::into))
     }
 }
 
 impl<'a> Entry<'a> {
-    pub fn new(meta: impl Into<Cow<'a, types::FeedMeta>>, entry: types::Entry) -> Self {
+    pub fn new(meta: impl Into<Cow<'a, types::FeedMeta>>, entry: impl Into<EntrySource>) -> Self {
         Self {
             meta: meta.into(),
-            entry,
+            entry: entry.into(),
         }
     }
 }
@@ -90,33 +139,39 @@ pub enum FeedType {
     JSON,
 }
 
-pub struct Feed(Arc<types::Feed>);
+pub struct Feed {
+    feed: Arc<types::Feed>,
+    subscription: Option<FeedSubscription>,
+}
 
 #[Object]
 impl Feed {
     /// Feed Id
     async fn id(&self) -> ID {
-        FeedIdV1::new(self.0.meta().url()).into()
+        FeedIdV1::new(self.feed.meta().url()).into()
     }
 
     /// Undering feed specification
     async fn r#type(&self) -> FeedType {
-        self.0.meta().r#type().clone().into()
+        self.feed.meta().r#type().clone().into()
     }
 
-    /// Feed title
+    /// Feed title. If user defined title exists, return it
     async fn title(&self) -> Option<&str> {
-        self.0.meta().title()
+        self.subscription
+            .as_ref()
+            .and_then(|subscription| subscription.0.title.as_deref())
+            .or(self.feed.meta().title())
     }
 
     /// Feed URL
     async fn url(&self) -> &str {
-        self.0.meta().url()
+        self.feed.meta().url()
     }
 
     /// The time at which the feed was last modified
     async fn updated(&self) -> Option<scalar::Rfc3339Time> {
-        self.0.meta().updated().map(Into::into)
+        self.feed.meta().updated().map(Into::into)
     }
 
     /// Feed entries
@@ -134,9 +189,9 @@ impl Feed {
     > {
         #[allow(clippy::cast_sign_loss)]
         let first = first.unwrap_or(5).max(0) as usize;
-        let meta = self.0.meta();
+        let meta = self.feed.meta();
         let entries = self
-            .0
+            .feed
             .entries()
             .map(|entry| Entry::new(meta, entry.clone()))
             .take(first)
@@ -157,7 +212,7 @@ impl Feed {
     async fn authors(&self) -> Connection<usize, String> {
         let mut c = Connection::new(false, false);
         c.edges.extend(
-            self.0
+            self.feed
                 .meta()
                 .authors()
                 .enumerate()
@@ -169,13 +224,13 @@ impl Feed {
 
     /// Description of feed
     async fn description(&self) -> Option<&str> {
-        self.0.meta().description()
+        self.feed.meta().description()
     }
 
     async fn links(&self) -> Connection<usize, Link> {
         let mut c = Connection::new(false, false);
         c.edges.extend(
-            self.0
+            self.feed
                 .meta()
                 .links()
                 .map(|link| Link::from(link.clone()))
@@ -187,11 +242,16 @@ impl Feed {
     }
 
     async fn website_url(&self) -> Option<&str> {
-        self.0.meta().website_url()
+        self.feed.meta().website_url()
     }
 
     async fn generator(&self) -> Option<&str> {
-        self.0.meta().generator()
+        self.feed.meta().generator()
+    }
+
+    /// Subscription metadata of this feed
+    async fn subscription(&self) -> Option<&FeedSubscription> {
+        self.subscription.as_ref()
     }
 }
 
@@ -212,7 +272,185 @@ impl EdgeNameType for FeedEntryEdgeName {
 }
 
 impl From<Arc<types::Feed>> for Feed {
-    fn from(value: Arc<types::Feed>) -> Self {
+    fn from(feed: Arc<types::Feed>) -> Self {
+        Self {
+            feed,
+            subscription: None,
+        }
+    }
+}
+
+impl From<(Arc<types::Feed>, repository::types::Subscription)> for Feed {
+    fn from((feed, subscription): (Arc<types::Feed>, repository::types::Subscription)) -> Self {
+        Self {
+            feed,
+            subscription: Some(subscription.into()),
+        }
+    }
+}
+
+/// User's subscription metadata of the feed
+#[derive(Clone)]
+pub struct FeedSubscription(repository::types::Subscription);
+
+#[Object]
+impl FeedSubscription {
+    /// Subscribed feed url
+    async fn url(&self) -> &str {
+        self.0.url.as_str()
+    }
+
+    /// User defined title
+    async fn title(&self) -> Option<&str> {
+        self.0.title.as_deref()
+    }
+
+    /// Folder to which the subscription belongs
+    async fn folder(&self) -> Option<&str> {
+        self.0.folder.as_deref()
+    }
+
+    /// Tags of the subscription
+    async fn tags(&self) -> &[String] {
+        self.0.tags.as_slice()
+    }
+
+    /// The time at which the feed was subscribed
+    async fn created_at(&self) -> Option<scalar::Rfc3339Time> {
+        self.0.created_at.map(Into::into)
+    }
+
+    /// Whether the subscription is paused
+    async fn paused(&self) -> bool {
+        self.0.paused
+    }
+}
+
+impl From<repository::types::Subscription> for FeedSubscription {
+    fn from(value: repository::types::Subscription) -> Self {
+        Self(value)
+    }
+}
+
+/// Everything stored about the user
+#[derive(SimpleObject)]
+pub struct MyData {
+    pub user_id: String,
+    pub email: Option<String>,
+    /// Provider identities linked to the user
+    pub identities: Vec<LinkedIdentity>,
+    /// The time at which the user was created
+    pub created_at: Option<scalar::Rfc3339Time>,
+    pub access_tokens: Vec<AccessToken>,
+    /// Whether the user is disabled by admin
+    pub disabled: bool,
+    pub subscriptions: Vec<FeedSubscription>,
+    /// Archived entries of the subscribed feeds
+    pub archived_entries: Vec<ArchivedEntryData>,
+    pub exported_at: scalar::Rfc3339Time,
+}
+
+impl From<usecase::ExportMyDataOutput> for MyData {
+    fn from(output: usecase::ExportMyDataOutput) -> Self {
+        Self {
+            user_id: output.user_id,
+            email: output.email,
+            identities: output.identities.into_iter().map(Into::into).collect(),
+            created_at: output.created_at.map(Into::into),
+            access_tokens: output.access_tokens.into_iter().map(Into::into).collect(),
+            disabled: output.disabled,
+            subscriptions: output.subscriptions.into_iter().map(Into::into).collect(),
+            archived_entries: output
+                .archived_entries
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            exported_at: output.exported_at.into(),
+        }
+    }
+}
+
+/// Identity of the user at an authentication provider
+#[derive(SimpleObject)]
+pub struct LinkedIdentity {
+    pub provider: String,
+    pub subject: String,
+}
+
+impl From<repository::types::Identity> for LinkedIdentity {
+    fn from(identity: repository::types::Identity) -> Self {
+        Self {
+            provider: identity.provider,
+            subject: identity.subject,
+        }
+    }
+}
+
+/// Archived entry in the exported data
+#[derive(SimpleObject)]
+pub struct ArchivedEntryData {
+    pub feed_url: String,
+    pub entry_id: String,
+    pub title: Option<String>,
+    pub website_url: Option<String>,
+    pub published: Option<scalar::Rfc3339Time>,
+    pub archived_at: scalar::Rfc3339Time,
+}
+
+impl From<repository::types::ArchivedEntry> for ArchivedEntryData {
+    fn from(entry: repository::types::ArchivedEntry) -> Self {
+        Self {
+            feed_url: entry.feed_url,
+            entry_id: entry.entry_id,
+            title: entry.title,
+            website_url: entry.website_url,
+            published: entry.published.map(Into::into),
+            archived_at: entry.archived_at.into(),
+        }
+    }
+}
+
+/// Operations allowed for a personal access token
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+#[graphql(remote = "crate::principal::Scope")]
+pub enum AccessTokenScope {
+    /// Read subscriptions and entries
+    Read,
+    /// Modify subscriptions
+    Write,
+}
+
+/// Personal access token. The token itself is only returned on creation
+pub struct AccessToken(repository::types::AccessToken);
+
+#[Object]
+impl AccessToken {
+    async fn id(&self) -> ID {
+        self.0.id.as_str().into()
+    }
+
+    /// Name given to identify the token
+    async fn name(&self) -> &str {
+        self.0.name.as_str()
+    }
+
+    async fn scopes(&self) -> Vec<AccessTokenScope> {
+        self.0.scopes.iter().copied().map(Into::into).collect()
+    }
+
+    /// The time at which the token was created
+    async fn created_at(&self) -> scalar::Rfc3339Time {
+        self.0.created_at.into()
+    }
+
+    /// The time at which the token expires. Null if the token does not expire
+    async fn expires_at(&self) -> Option<scalar::Rfc3339Time> {
+        self.0.expires_at.map(Into::into)
+    }
+}
+
+impl From<repository::types::AccessToken> for AccessToken {
+    fn from(value: repository::types::AccessToken) -> Self {
         Self(value)
     }
 }
@@ -243,3 +481,69 @@ impl<'a> From<&'a types::FeedMeta> for FeedMeta<'a> {
         Self(Cow::Borrowed(value))
     }
 }
+
+/// Entry which matched the search query
+pub struct SearchHit(search::SearchHit);
+
+#[Object]
+impl SearchHit {
+    /// Relevance score of the entry. higher is more relevant
+    async fn score(&self) -> f32 {
+        self.0.score
+    }
+
+    /// Url of the feed to which the entry belongs
+    async fn feed_url(&self) -> &str {
+        self.0.feed_url.as_str()
+    }
+
+    /// Entry title
+    async fn title(&self) -> Option<&str> {
+        self.0.title.as_deref()
+    }
+
+    /// Entry summary
+    async fn summary(&self) -> Option<&str> {
+        self.0.summary.as_deref()
+    }
+
+    /// Link to websiteurl at which this entry is published
+    async fn website_url(&self) -> Option<&str> {
+        self.0.website_url.as_deref()
+    }
+
+    /// The time at which the entry published
+    async fn published(&self) -> Option<scalar::Rfc3339Time> {
+        self.0.published.map(Into::into)
+    }
+
+    /// Title in which matched terms are emphasized with `<b>` tag
+    async fn title_snippet(&self) -> &str {
+        self.0.title_snippet.as_str()
+    }
+
+    /// Fragment of the summary or content in which matched terms are emphasized with `<b>` tag
+    async fn content_snippet(&self) -> &str {
+        self.0.content_snippet.as_str()
+    }
+
+    /// Feed to which the entry belongs. Null if the feed could not be fetched
+    async fn feed(&self, cx: &Context<'_>) -> Result<Option<Feed>> {
+        let loaders = cx.data::<Loaders>()?;
+        let url = self.0.feed_url.clone();
+        let Some(feed) = loaders.feed.load_one(url.clone()).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(match loaders.subscription.load_one(url).await? {
+            Some(subscription) => Feed::from((feed, subscription)),
+            None => Feed::from(feed),
+        }))
+    }
+}
+
+impl From<search::SearchHit> for SearchHit {
+    fn from(value: search::SearchHit) -> Self {
+        Self(value)
+    }
+}
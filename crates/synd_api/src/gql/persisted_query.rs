@@ -0,0 +1,270 @@
+//! Automatic persisted queries
+//! <https://www.apollographql.com/docs/apollo-server/performance/apq/>
+//!
+//! Clients send the sha256 hash of the document first and the full document only
+//! if the server does not know the hash yet.
+//! Documents registered on startup are always known and, in strict mode,
+//! they are the only documents allowed to execute.
+//! Strict mode applies to every request including subscriptions over websocket,
+//! so subscription documents have to be registered as well.
+
+use std::{collections::HashMap, fs, path::Path, sync::Arc};
+
+use anyhow::Context as _;
+use async_graphql::{
+    extensions::{
+        apollo_persisted_queries::{CacheStorage, LruCacheStorage},
+        Extension, ExtensionContext, ExtensionFactory, NextPrepareRequest,
+    },
+    parser::{parse_query, types::ExecutableDocument},
+    Request, ServerError, ServerResult, Value,
+};
+use sha2::{Digest, Sha256};
+use synd_o11y::metric;
+
+/// Queries known to the server
+#[derive(Clone)]
+pub struct PersistedQueries {
+    registered: Arc<HashMap<String, ExecutableDocument>>,
+    cache: LruCacheStorage,
+    /// Only registered queries are allowed to execute
+    strict: bool,
+}
+
+impl Default for PersistedQueries {
+    fn default() -> Self {
+        Self::new(crate::config::serve::DEFAULT_PERSISTED_QUERY_CACHE_SIZE)
+    }
+}
+
+impl PersistedQueries {
+    pub fn new(cache_size: usize) -> Self {
+        Self {
+            registered: Arc::new(HashMap::new()),
+            cache: LruCacheStorage::new(cache_size),
+            strict: false,
+        }
+    }
+
+    /// Register the documents in given files. each file is treated as one document.
+    /// Documents are hashed after normalizing line endings so that the hashes match
+    /// the documents which synd-term embeds from the same files regardless of the checkout
+    pub fn register_files<P: AsRef<Path>>(
+        self,
+        paths: impl IntoIterator<Item = P>,
+    ) -> anyhow::Result<Self> {
+        let mut registered = HashMap::new();
+        for path in paths {
+            let path = path.as_ref();
+            let query = fs::read_to_string(path)
+                .map(|query| normalize(&query))
+                .with_context(|| format!("read persisted query {}", path.display()))?;
+            let document = parse_query(&query)
+                .with_context(|| format!("parse persisted query {}", path.display()))?;
+
+            let hash = sha256_hex(&query);
+
+            tracing::info!(path = %path.display(), hash, "Register persisted query");
+            registered.insert(hash, document);
+        }
+
+        Ok(Self {
+            registered: Arc::new(registered),
+            ..self
+        })
+    }
+
+    #[must_use]
+    pub fn with_strict(self, strict: bool) -> Self {
+        Self { strict, ..self }
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    fn is_registered(&self, hash: &str) -> bool {
+        self.registered.contains_key(hash)
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheStorage for PersistedQueries {
+    async fn get(&self, key: String) -> Option<ExecutableDocument> {
+        let document = match self.registered.get(&key) {
+            Some(document) => Some(document.clone()),
+            None => self.cache.get(key).await,
+        };
+
+        metric!(
+            monotonic_counter.graphql.persisted_query.lookup = 1,
+            hit = document.is_some()
+        );
+        document
+    }
+
+    async fn set(&self, key: String, query: ExecutableDocument) {
+        if !self.strict {
+            self.cache.set(key, query).await;
+        }
+    }
+}
+
+/// Reject documents which are not registered
+pub struct RegisteredQueriesOnly(pub PersistedQueries);
+
+impl ExtensionFactory for RegisteredQueriesOnly {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(RegisteredQueriesOnlyExtension(self.0.clone()))
+    }
+}
+
+struct RegisteredQueriesOnlyExtension(PersistedQueries);
+
+#[async_trait::async_trait]
+impl Extension for RegisteredQueriesOnlyExtension {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        let hash = if request.query.is_empty() {
+            match request.extensions.get("persistedQuery") {
+                Some(Value::Object(persisted)) => match persisted.get("sha256Hash") {
+                    Some(Value::String(hash)) => hash.clone(),
+                    _ => String::new(),
+                },
+                _ => String::new(),
+            }
+        } else {
+            sha256_hex(&request.query)
+        };
+
+        if self.0.is_registered(&hash) {
+            next.run(ctx, request).await
+        } else {
+            metric!(monotonic_counter.graphql.persisted_query.rejected = 1);
+            Err(ServerError::new("PersistedQueryNotRegistered", None))
+        }
+    }
+}
+
+fn sha256_hex(query: &str) -> String {
+    format!("{:x}", Sha256::digest(query.as_bytes()))
+}
+
+/// Remove byte order mark and convert CRLF to LF
+fn normalize(query: &str) -> String {
+    query
+        .strip_prefix('\u{feff}')
+        .unwrap_or(query)
+        .replace("\r\n", "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+
+    use async_graphql::{Name, Response};
+
+    use super::*;
+
+    const QUERY: &str = "query Typename {\n  __typename\n}\n";
+
+    fn register(query: &str) -> (tempfile::NamedTempFile, PersistedQueries) {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(query.as_bytes()).unwrap();
+        let queries = PersistedQueries::new(16)
+            .register_files([file.path()])
+            .unwrap();
+        (file, queries)
+    }
+
+    fn request(query: &str, hash: &str) -> Request {
+        let mut request = Request::new(query);
+        let persisted = [
+            (Name::new("version"), Value::from(1)),
+            (Name::new("sha256Hash"), Value::from(hash)),
+        ];
+        request.extensions.insert(
+            "persistedQuery".into(),
+            Value::Object(persisted.into_iter().collect()),
+        );
+        request
+    }
+
+    fn error_message(response: &Response) -> Option<&str> {
+        response.errors.first().map(|err| err.message.as_str())
+    }
+
+    #[tokio::test]
+    async fn lookup_registered_and_cached_documents() {
+        let (_file, queries) = register(QUERY);
+
+        assert!(queries.get(sha256_hex(QUERY)).await.is_some());
+        assert!(queries.get(sha256_hex("{ __typename }")).await.is_none());
+
+        queries
+            .set(
+                sha256_hex("{ __typename }"),
+                parse_query("{ __typename }").unwrap(),
+            )
+            .await;
+        assert!(queries.get(sha256_hex("{ __typename }")).await.is_some());
+
+        // Strict mode does not cache unregistered documents
+        let strict = queries.with_strict(true);
+        let other = "{ a: __typename }";
+        strict
+            .set(sha256_hex(other), parse_query(other).unwrap())
+            .await;
+        assert!(strict.get(sha256_hex(other)).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn hash_of_normalized_document() {
+        let (_file, queries) = register(&format!("\u{feff}{}", QUERY.replace('\n', "\r\n")));
+
+        assert!(queries.is_registered(&sha256_hex(QUERY)));
+    }
+
+    #[tokio::test]
+    async fn send_document_if_persisted_query_not_found() {
+        let schema = crate::gql::schema_builder(PersistedQueries::new(16)).finish();
+        let query = "{ __typename }";
+        let hash = sha256_hex(query);
+
+        let response = schema.execute(request("", &hash)).await;
+        assert_eq!(error_message(&response), Some("PersistedQueryNotFound"));
+
+        let response = schema.execute(request(query, &hash)).await;
+        assert!(response.is_ok(), "{:?}", response.errors);
+
+        let response = schema.execute(request("", &hash)).await;
+        assert!(response.is_ok(), "{:?}", response.errors);
+    }
+
+    #[tokio::test]
+    async fn strict_mode_rejects_unregistered_documents() {
+        let (_file, queries) = register(QUERY);
+        let schema = crate::gql::schema_builder(queries.with_strict(true)).finish();
+
+        let response = schema.execute(request("", &sha256_hex(QUERY))).await;
+        assert!(response.is_ok(), "{:?}", response.errors);
+        let response = schema.execute(Request::new(QUERY)).await;
+        assert!(response.is_ok(), "{:?}", response.errors);
+
+        let query = "{ __typename }";
+        let response = schema.execute(request(query, &sha256_hex(query))).await;
+        assert_eq!(
+            error_message(&response),
+            Some("PersistedQueryNotRegistered")
+        );
+        let response = schema.execute(Request::new(query)).await;
+        assert_eq!(
+            error_message(&response),
+            Some("PersistedQueryNotRegistered")
+        );
+    }
+}
@@ -1,17 +1,23 @@
 use async_graphql::{
-    connection::{Connection, Edge},
+    connection::{self, Connection, Edge},
     Context, Object, Result,
 };
 
 use crate::{
     gql::{
+        admin::{self, AdminQuery},
+        internal_error,
         object::{self, id, Entry},
         run_usecase,
+        scalar::Rfc3339Time,
     },
+    repository::types::SubscriptionFilter,
     usecase::{
+        EntryFilter, ExportMyData, ExportMyDataInput, FetchAccessTokens, FetchAccessTokensInput,
         FetchEntries, FetchEntriesError, FetchEntriesInput, FetchEntriesOutput,
         FetchSubscribedFeeds, FetchSubscribedFeedsError, FetchSubscribedFeedsInput,
-        FetchSubscribedFeedsOutput, Output,
+        FetchSubscribedFeedsOutput, Output, SearchEntries, SearchEntriesError, SearchEntriesInput,
+        SearchEntriesOutput,
     },
 };
 
@@ -25,6 +31,8 @@ impl Subscription {
         cx: &Context<'_>,
         after: Option<String>,
         #[graphql(default = 20)] first: Option<i32>,
+        #[graphql(desc = "Return only feeds in the folder")] folder: Option<String>,
+        #[graphql(desc = "Return only feeds which have the tag")] tag: Option<String>,
     ) -> Result<Connection<String, object::Feed>> {
         #[allow(clippy::cast_sign_loss)]
         let first = first.unwrap_or(10).min(100) as usize;
@@ -32,6 +40,7 @@ impl Subscription {
         let input = FetchSubscribedFeedsInput {
             after,
             first: first + 1,
+            filter: SubscriptionFilter { folder, tag },
         };
         let Output {
             output: FetchSubscribedFeedsOutput { feeds },
@@ -48,7 +57,7 @@ impl Subscription {
         let edges = feeds
             .into_iter()
             .take(first)
-            .map(|feed| (feed.meta().url().to_owned(), feed))
+            .map(|(feed, subscription)| (subscription.url.clone(), (feed, subscription)))
             .map(|(cursor, feed)| (cursor, object::Feed::from(feed)))
             .map(|(cursor, feed)| Edge::new(cursor, feed));
 
@@ -58,40 +67,127 @@ impl Subscription {
     }
 
     /// Return subscribed latest entries order by published time.
+    #[allow(clippy::too_many_arguments)]
     async fn entries<'cx>(
         &self,
         cx: &Context<'_>,
         after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+        #[graphql(desc = "Return only entries of feeds in the folder")] folder: Option<String>,
+        #[graphql(desc = "Return only entries of feeds which have the tag")] tag: Option<String>,
+        #[graphql(desc = "Feed ids or urls to filter entries")] feeds: Option<Vec<String>>,
+        #[graphql(desc = "Return only entries published at or after this time")]
+        published_after: Option<Rfc3339Time>,
+        #[graphql(desc = "Return only entries published before this time")]
+        published_before: Option<Rfc3339Time>,
+        #[graphql(desc = "Return only entries whose title or summary contains the keyword")]
+        keyword: Option<String>,
+    ) -> Result<Connection<id::EntryCursor, Entry<'cx>>> {
+        let entry_filter = EntryFilter {
+            feed_urls: feeds.map(|feeds| {
+                feeds
+                    .iter()
+                    .map(|feed| id::FeedIdV1::url_of(feed).to_owned())
+                    .collect()
+            }),
+            published_after: published_after.map(Into::into),
+            published_before: published_before.map(Into::into),
+            keyword,
+        };
+
+        connection::query(
+            after,
+            before,
+            first,
+            last,
+            |after, before, first, last| async move {
+                // Default to the first 20 entries if neither first nor last is given
+                let first = match (first, last) {
+                    (None, None) => Some(20),
+                    (first, _) => first.map(|first| first.min(200)),
+                };
+                let last = last.map(|last| last.min(200));
+                let input = FetchEntriesInput {
+                    after: after.map(Into::into),
+                    before: before.map(Into::into),
+                    first,
+                    last,
+                    filter: SubscriptionFilter { folder, tag },
+                    entry_filter,
+                };
+                let Output {
+                    output:
+                        FetchEntriesOutput {
+                            entries,
+                            feeds,
+                            has_previous_page,
+                            has_next_page,
+                        },
+                } = run_usecase!(FetchEntries, cx, input, |err: FetchEntriesError| Err(
+                    async_graphql::ErrorExtensions::extend(&err)
+                ))?;
+
+                let mut connection = Connection::new(has_previous_page, has_next_page);
+
+                let edges = entries.into_iter().map(move |(entry, cursor)| {
+                    let meta = feeds
+                        .get(&cursor.feed_url)
+                        .expect("FeedMeta not found. this is a bug")
+                        .clone();
+                    let node = Entry::new(meta, entry);
+                    Edge::new(cursor.into(), node)
+                });
+
+                connection.edges.extend(edges);
+
+                Ok::<_, async_graphql::Error>(connection)
+            },
+        )
+        .await
+    }
+
+    /// Full-text search over subscribed entries. Results are ordered by relevance
+    async fn search(
+        &self,
+        cx: &Context<'_>,
+        #[graphql(desc = "Search query. terms are matched against title, summary and content")]
+        query: String,
+        after: Option<String>,
         #[graphql(default = 20)] first: Option<i32>,
-    ) -> Result<Connection<id::EntryId, Entry<'cx>>> {
+    ) -> Result<Connection<usize, object::SearchHit>> {
         #[allow(clippy::cast_sign_loss)]
-        let first = first.unwrap_or(20).min(200) as usize;
-        let has_prev = after.is_some();
-        let input = FetchEntriesInput {
-            after: after.map(Into::into),
-            first: first + 1,
+        let first = first.unwrap_or(20).max(0) as usize;
+        let offset = match after {
+            Some(after) => <usize as connection::CursorType>::decode_cursor(&after)?
+                .checked_add(1)
+                .ok_or_else(|| {
+                    async_graphql::ErrorExtensions::extend(&SearchEntriesError::OffsetTooLarge)
+                })?,
+            None => 0,
+        };
+        let input = SearchEntriesInput {
+            query,
+            offset,
+            first,
         };
         let Output {
-            output: FetchEntriesOutput { entries, feeds },
-        } = run_usecase!(FetchEntries, cx, input, |err: FetchEntriesError| Err(
+            output:
+                SearchEntriesOutput {
+                    hits,
+                    has_next_page,
+                },
+        } = run_usecase!(SearchEntries, cx, input, |err: SearchEntriesError| Err(
             async_graphql::ErrorExtensions::extend(&err)
         ))?;
 
-        let has_next = entries.len() > first;
-        let mut connection = Connection::new(has_prev, has_next);
+        let mut connection = Connection::new(offset > 0, has_next_page);
 
-        let edges = entries
+        let edges = hits
             .into_iter()
-            .take(first)
-            .map(move |(entry, feed_url)| {
-                let meta = feeds
-                    .get(&feed_url)
-                    .expect("FeedMeta not found. this is a bug")
-                    .clone();
-                let cursor = entry.id().into();
-                let node = Entry::new(meta, entry);
-                Edge::new(cursor, node)
-            });
+            .enumerate()
+            .map(|(i, hit)| Edge::new(offset + i, object::SearchHit::from(hit)));
 
         connection.edges.extend(edges);
 
@@ -106,4 +202,29 @@ impl Query {
     async fn subscription(&self) -> Subscription {
         Subscription {}
     }
+
+    /// Export everything stored about the user
+    async fn export_my_data(&self, cx: &Context<'_>) -> Result<object::MyData> {
+        let Output { output } =
+            run_usecase!(ExportMyData, cx, ExportMyDataInput {}, internal_error)?;
+
+        Ok(output.into())
+    }
+
+    /// Personal access tokens issued for the user
+    async fn access_tokens(&self, cx: &Context<'_>) -> Result<Vec<object::AccessToken>> {
+        let Output { output } = run_usecase!(
+            FetchAccessTokens,
+            cx,
+            FetchAccessTokensInput {},
+            internal_error
+        )?;
+
+        Ok(output.access_tokens.into_iter().map(Into::into).collect())
+    }
+
+    /// Administrative queries. Only admins are authorized
+    async fn admin(&self, cx: &Context<'_>) -> Result<AdminQuery> {
+        admin::admin_query(cx)
+    }
 }
@@ -27,3 +27,9 @@ impl From<synd_feed::types::Time> for Rfc3339Time {
         Self(value)
     }
 }
+
+impl From<Rfc3339Time> for synd_feed::types::Time {
+    fn from(value: Rfc3339Time) -> Self {
+        value.0
+    }
+}
@@ -0,0 +1,39 @@
+use std::borrow::Cow;
+
+use async_graphql::{Context, Subscription as GraphQLSubscription};
+use futures_util::{stream, Stream, StreamExt};
+
+use crate::{
+    gql::{object::Entry, run_usecase},
+    usecase::{WatchNewEntries, WatchNewEntriesError, WatchNewEntriesInput, WatchNewEntriesOutput},
+};
+
+pub struct Subscription;
+
+#[GraphQLSubscription]
+impl Subscription {
+    /// Entries of subscribed feeds which are newly found by background refresh.
+    /// Subscriptions changed after the start are not reflected
+    async fn new_entries(
+        &self,
+        cx: &Context<'_>,
+    ) -> async_graphql::Result<impl Stream<Item = Entry<'static>>> {
+        let crate::usecase::Output {
+            output: WatchNewEntriesOutput { new_entries },
+        } = run_usecase!(
+            WatchNewEntries,
+            cx,
+            WatchNewEntriesInput {},
+            |err: WatchNewEntriesError| Err(async_graphql::ErrorExtensions::extend(&err))
+        )?;
+
+        Ok(new_entries.flat_map(|new_entries| {
+            let entries = new_entries
+                .entries
+                .iter()
+                .map(|entry| Entry::new(Cow::Owned(new_entries.meta.clone()), entry.clone()))
+                .collect::<Vec<_>>();
+            stream::iter(entries)
+        }))
+    }
+}
@@ -1,13 +1,17 @@
 #![allow(clippy::new_without_default)]
 
+pub mod archive;
 pub mod args;
 pub mod client;
 pub mod config;
+pub mod config_file;
 pub mod dependency;
 pub mod gql;
 pub mod monitor;
 pub mod principal;
+pub mod realtime;
 pub mod repository;
+pub mod search;
 pub mod serve;
 pub mod service;
 pub mod shutdown;
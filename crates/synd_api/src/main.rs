@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use clap::{CommandFactory as _, FromArgMatches as _};
 use fdlimit::Outcome;
 use synd_o11y::{
     metric,
@@ -10,12 +11,16 @@ use tokio_metrics::RuntimeMonitor;
 use tracing::{error, info};
 
 use synd_api::{
-    args::{self, Args, ObservabilityOptions},
+    args::{
+        self, Args, Command, ConfigCheckOptions, ConfigCommand, ListenMode, MigrateOptions,
+        ObservabilityOptions,
+    },
     config,
+    config_file::ConfigFile,
     dependency::Dependency,
     monitor::Monitors,
-    repository::kvsd::ConnectKvsdFailed,
-    serve::listen_and_serve,
+    repository::{kvsd::ConnectKvsdFailed, RepositoryConfig},
+    serve::{self, listen_and_serve},
     shutdown::Shutdown,
 };
 
@@ -40,6 +45,10 @@ fn init_tracing(options: &ObservabilityOptions) -> Option<OpenTelemetryGuard> {
         match options.otlp_endpoint.as_deref() {
             None | Some("") => (None, None),
             Some(endpoint) => {
+                // Record exporter errors for the readiness probe
+                if let Err(err) = synd_o11y::opentelemetry::init_error_handler() {
+                    eprintln!("Failed to set opentelemetry error handler: {err}");
+                }
                 let resource = synd_o11y::opentelemetry::resource(config::NAME, config::VERSION);
 
                 let trace_layer =
@@ -83,16 +92,42 @@ fn init_tracing(options: &ObservabilityOptions) -> Option<OpenTelemetryGuard> {
 
 async fn run(
     Args {
-        kvsd,
+        command: _,
+        config: _,
+        repository,
         bind,
         serve,
         tls,
+        feed,
+        search,
+        archive,
+        realtime,
+        quota,
+        admin,
+        oidc,
+        persisted_query,
         o11y,
     }: Args,
     shutdown: Shutdown,
     monitors: Monitors,
 ) -> anyhow::Result<()> {
-    let dep = Dependency::new(kvsd, tls, serve, monitors).await?;
+    // Tls is terminated by the reverse proxy in other modes
+    let tls = (bind.listen == ListenMode::Tls).then_some(tls);
+    let dep = Dependency::new(
+        repository,
+        tls,
+        serve,
+        feed,
+        search,
+        archive,
+        realtime,
+        quota,
+        admin,
+        oidc,
+        persisted_query,
+        monitors,
+    )
+    .await?;
 
     info!(
         version = config::VERSION,
@@ -103,7 +138,49 @@ async fn run(
         "Runinng...",
     );
 
-    listen_and_serve(dep, bind.into(), shutdown).await
+    listen_and_serve(dep, bind.try_into()?, shutdown).await
+}
+
+async fn migrate(
+    MigrateOptions {
+        config: _,
+        kvsd,
+        dry_run,
+        user_ids,
+    }: MigrateOptions,
+) -> anyhow::Result<()> {
+    let kvsd = Dependency::connect_kvsd(kvsd.try_into()?).await?;
+    let report = kvsd.migrate(user_ids, dry_run).await?;
+
+    println!("{report}");
+
+    if report.failed() > 0 {
+        anyhow::bail!("{} values could not be migrated", report.failed());
+    }
+    Ok(())
+}
+
+fn check_config(ConfigCheckOptions { config: path }: ConfigCheckOptions) -> anyhow::Result<()> {
+    let file = ConfigFile::load(path)?;
+    let matches = file
+        .apply(Args::command())?
+        .try_get_matches_from([config::NAME])?;
+    let Args {
+        repository,
+        bind,
+        tls,
+        ..
+    } = Args::from_arg_matches(&matches)?;
+
+    RepositoryConfig::try_from(repository)?;
+    if bind.listen == ListenMode::Tls {
+        tls.pem_files()?;
+    }
+    serve::BindOptions::try_from(bind)?;
+
+    println!("{}: ok", file.path().display());
+
+    Ok(())
 }
 
 fn init_file_descriptor_limit() {
@@ -146,14 +223,20 @@ fn init_runtime_monitor() -> Monitors {
 
 #[tokio::main]
 async fn main() {
-    let args = args::parse();
+    let mut args = args::parse();
     let _guard = init_tracing(&args.o11y);
     let shutdown = Shutdown::watch_signal();
     let monitors = init_runtime_monitor();
 
     init_file_descriptor_limit();
 
-    if let Err(err) = run(args, shutdown, monitors).await {
+    let result = match args.command.take() {
+        Some(Command::Migrate(options)) => migrate(options).await,
+        Some(Command::Config(ConfigCommand::Check(options))) => check_config(options),
+        None => run(args, shutdown, monitors).await,
+    };
+
+    if let Err(err) = result {
         if let Some(err) = err.downcast_ref::<ConnectKvsdFailed>() {
             error!("{err}: make sure kvsd is running");
         } else {
@@ -162,3 +245,38 @@ async fn main() {
         std::process::exit(1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(content: &str) -> anyhow::Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("synd.toml");
+        std::fs::write(&path, content).unwrap();
+
+        check_config(ConfigCheckOptions { config: path })
+    }
+
+    #[test]
+    fn check_repository_options() {
+        let kvsd = r#"
+            listen = "http"
+            kvsd-host = "localhost"
+            kvsd-port = 7379
+            kvsd-username = "synd"
+            kvsd-password = "secret"
+            "#;
+        assert!(check(kvsd).is_ok());
+        let err = check(r#"listen = "http""#).unwrap_err();
+        assert!(err.to_string().contains("--kvsd-host"), "{err}");
+
+        let sqlite = r#"
+            listen = "http"
+            repository = "sqlite"
+            "#;
+        let err = check(sqlite).unwrap_err();
+        assert!(err.to_string().contains("--sqlite-db"), "{err}");
+        assert!(check(&format!(r#"{sqlite} sqlite-db = "synd.db""#)).is_ok());
+    }
+}
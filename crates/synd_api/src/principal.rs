@@ -1,17 +1,91 @@
-use std::{
-    collections::hash_map::DefaultHasher,
-    hash::{Hash, Hasher},
-};
+use std::{fmt, hash::Hasher, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use siphasher::sip::SipHasher13;
 
 #[derive(Clone, Debug)]
 pub enum Principal {
     User(User),
+    /// User who is allowed to administer the service
+    Admin(User),
+    /// User authenticated with a personal access token.
+    /// Never regarded as admin even if the user is
+    Token {
+        user: User,
+        scopes: Vec<Scope>,
+    },
 }
 
 impl Principal {
     pub fn user_id(&self) -> Option<&str> {
         match self {
-            Principal::User(User { id, .. }) => Some(id.as_str()),
+            Principal::User(User { id, .. })
+            | Principal::Admin(User { id, .. })
+            | Principal::Token {
+                user: User { id, .. },
+                ..
+            } => Some(id.as_str()),
+        }
+    }
+
+    pub fn email(&self) -> Option<&str> {
+        match self {
+            Principal::User(user) | Principal::Admin(user) | Principal::Token { user, .. } => {
+                Some(user.email())
+            }
+        }
+    }
+
+    pub fn is_admin(&self) -> bool {
+        matches!(self, Principal::Admin(_))
+    }
+
+    pub fn is_token(&self) -> bool {
+        matches!(self, Principal::Token { .. })
+    }
+
+    /// Users who logged in interactively are granted all scopes
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        match self {
+            Principal::User(_) | Principal::Admin(_) => true,
+            Principal::Token { scopes, .. } => scopes.contains(&scope),
+        }
+    }
+}
+
+/// Operations allowed for a personal access token
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// Read subscriptions and entries
+    Read,
+    /// Modify subscriptions
+    Write,
+}
+
+impl Scope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Write => "write",
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Scope::Read),
+            "write" => Ok(Scope::Write),
+            _ => Err(format!("unknown scope: {s}")),
         }
     }
 }
@@ -19,25 +93,46 @@ impl Principal {
 #[derive(Clone, Debug)]
 pub struct User {
     id: String,
-    #[allow(dead_code)]
     email: String,
 }
 
 impl User {
+    pub fn new(id: impl Into<String>, email: impl Into<String>) -> Self {
+        User {
+            id: id.into(),
+            email: email.into(),
+        }
+    }
+
+    /// Construct user whose id is derived from the email
     pub fn from_email(email: impl Into<String>) -> Self {
-        let mut s = DefaultHasher::new();
         let email = email.into();
+        User {
+            id: Self::legacy_id(&email),
+            email,
+        }
+    }
 
-        email.hash(&mut s);
+    /// Derive user id from the email as it was before user records were introduced.
+    /// Ids were derived with `DefaultHasher`, which is not guaranteed to be stable across Rust
+    /// releases, so its current algorithm (SipHash-1-3 with zero keys and `str` hashing which
+    /// appends `0xff`) is pinned here
+    pub fn legacy_id(email: &str) -> String {
+        let mut s = SipHasher13::new();
+        s.write(email.as_bytes());
+        s.write_u8(0xff);
         let id = s.finish();
-        let id = format!("{id:016x}");
 
-        User { id, email }
+        format!("{id:016x}")
     }
 
     pub fn id(&self) -> &str {
         self.id.as_str()
     }
+
+    pub fn email(&self) -> &str {
+        self.email.as_str()
+    }
 }
 
 #[cfg(test)]
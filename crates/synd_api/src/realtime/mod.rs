@@ -0,0 +1,268 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use moka::sync::Cache;
+use synd_feed::{
+    feed::parser::{FetchFeed, FetchFeedResult},
+    types::{self, Feed},
+};
+use synd_o11y::metric;
+use tokio::sync::broadcast;
+
+mod refresher;
+pub use refresher::Refresher;
+
+/// Entries which were not included in the previous fetch of the feed
+#[derive(Debug)]
+pub struct NewEntries {
+    pub meta: types::FeedMeta,
+    pub entries: Vec<types::Entry>,
+}
+
+struct State {
+    /// Entry ids of each feed at the last fetch.
+    /// Feeds which have not been fetched for a while are evicted
+    seen: Cache<String, Arc<HashSet<String>>>,
+    /// Number of watchers of each feed
+    watched: HashMap<String, usize>,
+}
+
+/// Detect new entries from fetched feeds and notify them to watchers
+#[derive(Clone)]
+pub struct EntryBroadcaster {
+    tx: broadcast::Sender<Arc<NewEntries>>,
+    state: Arc<Mutex<State>>,
+}
+
+impl EntryBroadcaster {
+    const CHANNEL_CAPACITY: usize = 256;
+    const MAX_SEEN_FEEDS: u64 = 10_000;
+
+    /// Entry ids of feeds which are not fetched within `seen_ttl` are forgotten,
+    /// so that the next fetch of them is regarded as the first one
+    pub fn new(seen_ttl: Duration) -> Self {
+        let (tx, _) = broadcast::channel(Self::CHANNEL_CAPACITY);
+        let seen = Cache::builder()
+            .max_capacity(Self::MAX_SEEN_FEEDS)
+            .time_to_idle(seen_ttl)
+            .build();
+        Self {
+            tx,
+            state: Arc::new(Mutex::new(State {
+                seen,
+                watched: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Start watching given feeds.
+    /// Watched feeds are refreshed in the background until the returned guard is dropped
+    pub fn watch(&self, urls: Vec<String>) -> (WatchGuard, broadcast::Receiver<Arc<NewEntries>>) {
+        {
+            let mut state = self.state();
+            for url in &urls {
+                *state.watched.entry(url.clone()).or_default() += 1;
+            }
+        }
+        metric!(counter.realtime.watchers = 1);
+
+        let guard = WatchGuard {
+            urls,
+            state: Arc::clone(&self.state),
+        };
+        (guard, self.tx.subscribe())
+    }
+
+    /// Feeds which have at least one watcher
+    pub fn watched_urls(&self) -> Vec<String> {
+        self.state().watched.keys().cloned().collect()
+    }
+
+    /// Compare entries with the previous fetch and broadcast new entries.
+    /// Entries of a feed fetched for the first time are not regarded as new
+    pub fn observe(&self, feed: &Feed) {
+        let meta = feed.meta();
+        let ids = feed
+            .entries()
+            .map(|entry| entry.id_ref().to_string())
+            .collect::<HashSet<_>>();
+
+        let previous = {
+            let state = self.state();
+            let previous = state.seen.get(meta.url());
+            state.seen.insert(meta.url().to_owned(), Arc::new(ids));
+            previous
+        };
+        let Some(previous) = previous else {
+            return;
+        };
+
+        let entries = feed
+            .entries()
+            .filter(|entry| !previous.contains(&entry.id_ref().to_string()))
+            .cloned()
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return;
+        }
+
+        tracing::debug!(
+            url = meta.url(),
+            entries = entries.len(),
+            "New entries found"
+        );
+        metric!(monotonic_counter.realtime.new_entries = entries.len());
+
+        // Error means there is no watcher
+        self.tx
+            .send(Arc::new(NewEntries {
+                meta: meta.clone(),
+                entries,
+            }))
+            .ok();
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("broadcaster state poisoned")
+    }
+}
+
+/// Stop watching the feeds on drop
+pub struct WatchGuard {
+    urls: Vec<String>,
+    state: Arc<Mutex<State>>,
+}
+
+impl Drop for WatchGuard {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().expect("broadcaster state poisoned");
+        for url in &self.urls {
+            if let Some(watchers) = state.watched.get_mut(url) {
+                *watchers -= 1;
+                if *watchers == 0 {
+                    state.watched.remove(url);
+                }
+            }
+        }
+        metric!(counter.realtime.watchers = -1);
+    }
+}
+
+/// `FetchFeed` which broadcast new entries of fetched feeds
+#[derive(Clone)]
+pub struct BroadcastFeedService<S> {
+    service: S,
+    broadcaster: EntryBroadcaster,
+}
+
+impl<S> BroadcastFeedService<S> {
+    pub fn new(service: S, broadcaster: EntryBroadcaster) -> Self {
+        Self {
+            service,
+            broadcaster,
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FetchFeed for BroadcastFeedService<S>
+where
+    S: FetchFeed,
+{
+    async fn fetch_feed(&self, url: String) -> FetchFeedResult<Feed> {
+        let feed = self.service.fetch_feed(url).await?;
+        self.broadcaster.observe(&feed);
+        Ok(feed)
+    }
+
+    async fn fetch_feeds_parallel(&self, urls: &[String]) -> FetchFeedResult<Vec<Feed>> {
+        let feeds = self.service.fetch_feeds_parallel(urls).await?;
+        for feed in &feeds {
+            self.broadcaster.observe(feed);
+        }
+        Ok(feeds)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use synd_feed::feed::parser::FeedService;
+
+    use super::*;
+
+    /// Construct feed which has entries of given ids
+    pub(crate) fn feed(url: &str, ids: &[&str]) -> Feed {
+        let items = ids
+            .iter()
+            .map(|id| format!("<item><guid>{id}</guid><title>{id}</title></item>"))
+            .collect::<String>();
+        let rss = format!(
+            "<?xml version=\"1.0\"?><rss version=\"2.0\"><channel>\
+             <title>feed</title><link>https://example.com</link>{items}</channel></rss>"
+        );
+        FeedService::new("synd-test", 1024 * 1024)
+            .parse(url, rss.as_bytes())
+            .unwrap()
+    }
+
+    fn ids(new_entries: &NewEntries) -> Vec<&str> {
+        new_entries
+            .entries
+            .iter()
+            .map(|entry| entry.id_ref())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn broadcast_only_new_entries() {
+        let broadcaster = EntryBroadcaster::new(Duration::from_secs(60));
+        let url = "https://example.com/feed.xml";
+        let (_guard, mut rx) = broadcaster.watch(vec![url.into()]);
+
+        // First fetch is not regarded as new
+        broadcaster.observe(&feed(url, &["1", "2"]));
+        broadcaster.observe(&feed(url, &["1", "2"]));
+        assert!(rx.try_recv().is_err());
+
+        broadcaster.observe(&feed(url, &["3", "1", "2"]));
+        let new_entries = rx.try_recv().unwrap();
+        assert_eq!(new_entries.meta.url(), url);
+        assert_eq!(ids(&new_entries), vec!["3"]);
+    }
+
+    #[tokio::test]
+    async fn forget_feeds_not_fetched_recently() {
+        let broadcaster = EntryBroadcaster::new(Duration::from_millis(50));
+        let url = "https://example.com/feed.xml";
+        let (_guard, mut rx) = broadcaster.watch(vec![url.into()]);
+
+        broadcaster.observe(&feed(url, &["1"]));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        broadcaster.observe(&feed(url, &["2", "1"]));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn unwatch_on_guard_drop() {
+        let broadcaster = EntryBroadcaster::new(Duration::from_secs(60));
+        let a = "https://a.example.com/feed.xml".to_owned();
+        let b = "https://b.example.com/feed.xml".to_owned();
+
+        let (guard_a, _rx) = broadcaster.watch(vec![a.clone()]);
+        let (guard_ab, _rx) = broadcaster.watch(vec![a.clone(), b.clone()]);
+        let mut watched = broadcaster.watched_urls();
+        watched.sort();
+        assert_eq!(watched, vec![a.clone(), b]);
+
+        drop(guard_ab);
+        assert_eq!(broadcaster.watched_urls(), vec![a]);
+
+        drop(guard_a);
+        assert!(broadcaster.watched_urls().is_empty());
+    }
+}
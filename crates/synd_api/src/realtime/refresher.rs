@@ -0,0 +1,142 @@
+use std::{sync::Arc, time::Duration};
+
+use futures_util::{stream, StreamExt};
+use synd_feed::feed::cache::FetchCachedFeed;
+
+use crate::realtime::EntryBroadcaster;
+
+/// Periodically refresh watched feeds so that new entries are broadcasted
+pub struct Refresher {
+    fetch_feed: Arc<dyn FetchCachedFeed>,
+    broadcaster: EntryBroadcaster,
+    interval: Duration,
+}
+
+impl Refresher {
+    const CONCURRENCY: usize = 10;
+
+    pub fn new(
+        fetch_feed: Arc<dyn FetchCachedFeed>,
+        broadcaster: EntryBroadcaster,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            fetch_feed,
+            broadcaster,
+            interval,
+        }
+    }
+
+    /// Spawn refresh task
+    pub fn spawn(self) {
+        tokio::spawn(self.run());
+    }
+
+    async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let urls = self.broadcaster.watched_urls();
+            if urls.is_empty() {
+                continue;
+            }
+            tracing::debug!(feeds = urls.len(), "Refresh watched feeds");
+
+            let fetch_feed = &self.fetch_feed;
+            stream::iter(urls)
+                .for_each_concurrent(Self::CONCURRENCY, |url| async move {
+                    if let Err(err) = fetch_feed.refresh_feed(url.clone()).await {
+                        tracing::warn!(url, "Failed to refresh feed: {err}");
+                    }
+                })
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    use async_trait::async_trait;
+    use synd_feed::{
+        feed::{
+            cache::CacheLayer,
+            parser::{FetchFeed, FetchFeedResult},
+        },
+        types::Feed,
+    };
+
+    use crate::realtime::{tests::feed, BroadcastFeedService};
+
+    use super::*;
+
+    /// Return a new entry on every fetch
+    #[derive(Clone, Default)]
+    struct GrowingFeed {
+        fetched: Arc<AtomicUsize>,
+        urls: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl FetchFeed for GrowingFeed {
+        async fn fetch_feed(&self, url: String) -> FetchFeedResult<Feed> {
+            let fetched = self.fetched.fetch_add(1, Ordering::SeqCst);
+            self.urls.lock().unwrap().push(url.clone());
+            let ids = (0..=fetched).map(|i| i.to_string()).collect::<Vec<_>>();
+            Ok(feed(
+                &url,
+                &ids.iter().map(String::as_str).collect::<Vec<_>>(),
+            ))
+        }
+
+        async fn fetch_feeds_parallel(&self, urls: &[String]) -> FetchFeedResult<Vec<Feed>> {
+            let mut feeds = Vec::with_capacity(urls.len());
+            for url in urls {
+                feeds.push(self.fetch_feed(url.clone()).await?);
+            }
+            Ok(feeds)
+        }
+    }
+
+    #[tokio::test]
+    async fn refresh_watched_feeds() {
+        let broadcaster = EntryBroadcaster::new(Duration::from_secs(60));
+        let service = GrowingFeed::default();
+        let fetch_feed = Arc::new(CacheLayer::new(BroadcastFeedService::new(
+            service.clone(),
+            broadcaster.clone(),
+        )));
+        let url = "https://example.com/feed.xml";
+
+        Refresher::new(fetch_feed, broadcaster.clone(), Duration::from_millis(10)).spawn();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        // Nothing is fetched without watchers
+        assert_eq!(service.fetched.load(Ordering::SeqCst), 0);
+
+        let (guard, mut rx) = broadcaster.watch(vec![url.into()]);
+        let new_entries = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(new_entries.meta.url(), url);
+        assert!(service
+            .urls
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|fetched| fetched == url));
+
+        drop(guard);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let fetched = service.fetched.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(service.fetched.load(Ordering::SeqCst), fetched);
+    }
+}
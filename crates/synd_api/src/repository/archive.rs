@@ -0,0 +1,171 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use synd_feed::types::Time;
+
+use crate::repository::{subscription::RepositoryResult, types::ArchivedEntry};
+
+#[async_trait]
+pub trait EntryArchiveRepository: Send + Sync {
+    /// Fetch archived entries of the feed
+    async fn fetch_archived_entries(&self, feed_url: &str) -> RepositoryResult<Vec<ArchivedEntry>>;
+
+    /// Merge given entries into the archive of the feed and return the archived entries.
+    /// Entries which are older than `retain_since` are purged
+    async fn archive_entries(
+        &self,
+        feed_url: &str,
+        entries: Vec<ArchivedEntry>,
+        retain_since: Time,
+    ) -> RepositoryResult<Vec<ArchivedEntry>>;
+}
+
+#[async_trait]
+impl<T> EntryArchiveRepository for Arc<T>
+where
+    T: EntryArchiveRepository,
+{
+    async fn fetch_archived_entries(&self, feed_url: &str) -> RepositoryResult<Vec<ArchivedEntry>> {
+        T::fetch_archived_entries(self, feed_url).await
+    }
+
+    async fn archive_entries(
+        &self,
+        feed_url: &str,
+        entries: Vec<ArchivedEntry>,
+        retain_since: Time,
+    ) -> RepositoryResult<Vec<ArchivedEntry>> {
+        T::archive_entries(self, feed_url, entries, retain_since).await
+    }
+}
+
+/// Merge fresh entries into archived entries.
+/// Fresh entries overwrite archived ones except for the time at which they were archived.
+/// Expired entries are purged unless the feed still lists them.
+/// Return whether the archive was changed
+pub(crate) fn merge_entries(
+    archived: &mut Vec<ArchivedEntry>,
+    fresh: Vec<ArchivedEntry>,
+    retain_since: Time,
+) -> bool {
+    let mut changed = false;
+    let fresh_ids = fresh
+        .iter()
+        .map(|entry| entry.entry_id.clone())
+        .collect::<HashSet<_>>();
+    let mut positions = archived
+        .iter()
+        .enumerate()
+        .map(|(position, entry)| (entry.entry_id.clone(), position))
+        .collect::<HashMap<_, _>>();
+
+    for mut entry in fresh {
+        match positions.get(&entry.entry_id) {
+            Some(&position) => {
+                entry.archived_at = archived[position].archived_at;
+                if archived[position] != entry {
+                    archived[position] = entry;
+                    changed = true;
+                }
+            }
+            None => {
+                positions.insert(entry.entry_id.clone(), archived.len());
+                archived.push(entry);
+                changed = true;
+            }
+        }
+    }
+
+    let len = archived.len();
+    archived.retain(|entry| {
+        entry.retention_time() >= retain_since || fresh_ids.contains(&entry.entry_id)
+    });
+
+    changed || archived.len() != len
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+
+    fn time(s: &str) -> Time {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn entry(id: &str, title: &str, published: &str) -> ArchivedEntry {
+        ArchivedEntry {
+            feed_url: "https://example.com/feed.xml".into(),
+            entry_id: id.into(),
+            title: Some(title.into()),
+            summary: None,
+            content: None,
+            website_url: None,
+            published: Some(time(published)),
+            updated: None,
+            archived_at: time("2024-03-01T00:00:00Z"),
+        }
+    }
+
+    #[test]
+    fn merge_keeps_entries_which_left_the_feed() {
+        let mut archived = vec![entry("1", "old", "2024-01-01T00:00:00Z")];
+        let fresh = vec![entry("2", "new", "2024-02-01T00:00:00Z")];
+
+        assert!(merge_entries(
+            &mut archived,
+            fresh,
+            time("2023-01-01T00:00:00Z")
+        ));
+        assert_eq!(
+            archived
+                .iter()
+                .map(|e| e.entry_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["1", "2"]
+        );
+    }
+
+    #[test]
+    fn merge_updates_existing_entries() {
+        let mut archived = vec![entry("1", "old", "2024-01-01T00:00:00Z")];
+        let mut updated = entry("1", "updated", "2024-01-01T00:00:00Z");
+        updated.archived_at = time("2024-04-01T00:00:00Z");
+
+        assert!(merge_entries(
+            &mut archived,
+            vec![updated],
+            time("2023-01-01T00:00:00Z")
+        ));
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].title.as_deref(), Some("updated"));
+        assert_eq!(archived[0].archived_at, time("2024-03-01T00:00:00Z"));
+
+        let same = archived.clone();
+        assert!(!merge_entries(
+            &mut archived,
+            same,
+            time("2023-01-01T00:00:00Z")
+        ));
+    }
+
+    #[test]
+    fn merge_purges_expired_entries() {
+        let mut archived = vec![
+            entry("1", "expired", "2023-01-01T00:00:00Z"),
+            entry("2", "retained", "2024-01-01T00:00:00Z"),
+        ];
+
+        assert!(merge_entries(
+            &mut archived,
+            Vec::new(),
+            time("2023-06-01T00:00:00Z")
+        ));
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].entry_id, "2");
+    }
+}
@@ -1,99 +1,440 @@
-use std::{io::ErrorKind, time::Duration};
+use std::{
+    collections::{BTreeSet, HashSet},
+    time::{Duration, Instant},
+};
 
-use anyhow::Context;
 use async_trait::async_trait;
-use futures_util::TryFutureExt;
-use kvsd::{
-    client::{tcp::Client, Api},
-    Key, Value,
-};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::Utc;
+use kvsd::{client::Api, Key, Value};
 use serde::{Deserialize, Serialize};
+use synd_o11y::metric;
 use thiserror::Error;
-use tokio::sync::Mutex;
-use tokio::{net::TcpStream, sync::MutexGuard};
+
+use synd_feed::types::Time;
 
 use crate::repository::{
-    self, subscription::RepositoryResult, RepositoryError, SubscriptionRepository,
+    self,
+    archive::merge_entries,
+    migration::{self, Decoded, Migration, MigrationReport, MigrationStatus, Versioned},
+    subscription::RepositoryResult,
+    types::{AccessToken, ArchivedEntry, DeletionToken, Identity, UserRecord},
+    EntryArchiveRepository, RepositoryError, SubscriptionRepository, UserRepository,
 };
 
+mod lock;
+use lock::KeyLocks;
+mod pool;
+pub use pool::PoolConfig;
+use pool::{Connector, Pool, PooledClient};
+
 #[derive(Error, Debug)]
 #[error("connect kvsd failed")]
 pub struct ConnectKvsdFailed;
 
+/// Settings to connect kvsd
+pub struct KvsdConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+    pub pool: PoolConfig,
+}
+
 pub struct KvsdClient {
-    #[allow(dead_code)]
-    client: Mutex<Client<TcpStream>>,
+    pool: Pool,
+    /// `<host>:<port>` of kvsd
+    addr: String,
+    /// Serialize read-modify-write operations on the same key since kvsd does not support
+    /// transaction. Subscriptions are also locked across instances by leases
+    locks: KeyLocks,
 }
 
 impl KvsdClient {
-    pub fn new(client: Client<TcpStream>) -> Self {
-        Self {
-            client: Mutex::new(client),
-        }
-    }
+    /// Leases are regarded as released after this even if the owner did not release them
+    const LEASE_TTL: chrono::Duration = chrono::Duration::seconds(10);
+    /// Upper bound of the time from observing a lease free to writing the owner token
+    const LEASE_WRITE_WINDOW: Duration = Duration::from_millis(100);
+    const LEASE_RETRY_INTERVAL: Duration = Duration::from_millis(20);
+    const LEASE_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(15);
 
     pub async fn connect(
-        host: impl AsRef<str>,
+        host: impl Into<String>,
         port: u16,
         username: String,
         password: String,
-        timeout: Duration,
+        config: PoolConfig,
     ) -> anyhow::Result<Self> {
-        let handshake = async {
-            let mut retry = 0;
-            loop {
-                match kvsd::client::tcp::UnauthenticatedClient::insecure_from_addr(&host, port)
-                    .and_then(|client| client.authenticate(&username, &password))
-                    .await
-                    .map(Self::new)
-                {
-                    Ok(client) => break Ok(client),
-                    Err(kvsd::KvsdError::Io(io)) if io.kind() == ErrorKind::ConnectionRefused => {
-                        tracing::info!(retry, "Kvsd connection refused");
-                    }
-                    err => break err,
+        let host = host.into();
+        let addr = format!("{host}:{port}");
+        let connector = Connector {
+            host,
+            port,
+            username,
+            password,
+        };
+        let pool = Pool::new(connector, config);
+
+        pool.warm_up().await?;
+        tracing::info!("Kvsd handshake successfully completed");
+
+        Ok(Self {
+            pool,
+            addr,
+            locks: KeyLocks::default(),
+        })
+    }
+
+    /// Upgrade stored values to the current version.
+    /// Subscriptions of the users in the users index and given `user_ids`,
+    /// and archives of their subscribed feeds are visited.
+    /// If `dry_run` is true, nothing is written
+    pub async fn migrate(
+        &self,
+        user_ids: impl IntoIterator<Item = String>,
+        dry_run: bool,
+    ) -> RepositoryResult<MigrationReport> {
+        let mut report = MigrationReport::new(dry_run);
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            let mut users = self
+                .migrate_value::<Users>(
+                    &mut client,
+                    Self::users_key(),
+                    "users".into(),
+                    dry_run,
+                    &mut report,
+                )
+                .await?
+                .unwrap_or_default();
+            let unregistered = user_ids
+                .into_iter()
+                .filter(|user_id| !users.user_ids.contains(user_id))
+                .collect::<Vec<_>>();
+            users.user_ids.extend(unregistered.iter().cloned());
+
+            let mut feed_urls = BTreeSet::new();
+            for user_id in &users.user_ids {
+                let subscriptions = self
+                    .migrate_value::<Subscriptions>(
+                        &mut client,
+                        Self::feed_subscription_key(user_id),
+                        format!("user:{user_id}"),
+                        dry_run,
+                        &mut report,
+                    )
+                    .await?;
+                if let Some(subscriptions) = subscriptions {
+                    feed_urls.extend(subscriptions.subscriptions.into_iter().map(|s| s.url));
                 }
-                retry += 1;
-                tokio::time::sleep(Duration::from_millis(1000)).await;
             }
+
+            for feed_url in &feed_urls {
+                self.migrate_value::<EntryArchive>(
+                    &mut client,
+                    Self::entry_archive_key(feed_url),
+                    format!("feed:{feed_url}"),
+                    dry_run,
+                    &mut report,
+                )
+                .await?;
+            }
+
+            // Register users which were given explicitly so that the next migration can find them
+            if !dry_run && !unregistered.is_empty() {
+                let unregistered = unregistered.iter().map(String::as_str).collect::<Vec<_>>();
+                self.register_users(&mut client, &unregistered).await?;
+            }
+
+            Ok(report)
+        }
+        .await;
+        client.finish(result)
+    }
+
+    /// Rewrite the value in the current version if it is outdated.
+    /// Return the decoded value or None if the value does not exist or could not be decoded
+    async fn migrate_value<'a, T: Versioned>(
+        &self,
+        client: &mut PooledClient<'a>,
+        key: Key,
+        label: String,
+        dry_run: bool,
+        report: &mut MigrationReport,
+    ) -> RepositoryResult<Option<T>> {
+        let _lock = self.locks.lock([&key]).await;
+        let decoded = match Self::get_decoded::<T>(client, key.clone()).await {
+            Ok(Some(decoded)) => decoded,
+            Ok(None) => return Ok(None),
+            Err(RepositoryError::Migration(err)) => {
+                report.push(T::KIND, label, MigrationStatus::Failed(err.to_string()));
+                return Ok(None);
+            }
+            Err(err) => return Err(err),
         };
 
-        tokio::time::timeout(timeout, handshake)
-            .await
-            .map_err(anyhow::Error::from)
-            .context(ConnectKvsdFailed)?
-            .map_err(anyhow::Error::from)
-            .inspect(|_| tracing::info!("Kvsd handshake successfully completed"))
+        if !decoded.is_outdated() {
+            report.push(T::KIND, label, MigrationStatus::UpToDate);
+            return Ok(Some(decoded.value));
+        }
+
+        if !dry_run {
+            Self::set(client, key, &decoded.value).await?;
+        }
+        report.push(
+            T::KIND,
+            label,
+            MigrationStatus::Upgraded {
+                from: decoded.stored_version,
+                to: T::VERSION,
+            },
+        );
+
+        Ok(Some(decoded.value))
+    }
+
+    /// Apply `modify` to the user's subscriptions and write them only if they changed.
+    /// The subscriptions are locked by a lease so that writes from other instances are not lost
+    async fn modify_subscriptions<'a, R>(
+        &self,
+        client: &mut PooledClient<'a>,
+        user_id: &str,
+        modify: impl FnOnce(&mut Vec<repository::types::Subscription>) -> R + Send,
+    ) -> RepositoryResult<R> {
+        let key = Self::feed_subscription_key(user_id);
+        let lease_key = Self::subscription_lease_key(user_id);
+        let _lock = self.locks.lock([&key]).await;
+        let owner = Self::acquire_lease(client, lease_key.clone()).await?;
+
+        let result = async {
+            let current = Self::get::<Subscriptions>(client, key.clone())
+                .await?
+                .unwrap_or_default();
+            let mut subscriptions = current.subscriptions.clone();
+            let output = modify(&mut subscriptions);
+
+            if subscriptions != current.subscriptions {
+                let next = Subscriptions {
+                    revision: current.revision + 1,
+                    subscriptions,
+                };
+                Self::set(client, key, &next).await?;
+            }
+            Ok(output)
+        }
+        .await;
+
+        let released = Self::release_lease(client, lease_key, &owner).await;
+        result.and_then(|output| released.map(|()| output))
+    }
+
+    /// Acquire the lease shared by instances and return its owner token.
+    /// kvsd supports neither compare-and-swap nor locks, so the lease is acquired as in
+    /// Fischer's mutual exclusion. The token is written only if the lease was observed free
+    /// within `LEASE_WRITE_WINDOW`, then after waiting for the window, the lease is acquired
+    /// if the token was not overwritten by another instance which also observed it free
+    async fn acquire_lease<'a>(
+        client: &mut PooledClient<'a>,
+        key: Key,
+    ) -> RepositoryResult<String> {
+        let owner = format!("{:032x}", rand::random::<u128>());
+        let started = Instant::now();
+
+        loop {
+            if started.elapsed() > Self::LEASE_ACQUIRE_TIMEOUT {
+                metric!(monotonic_counter.kvsd.lease_timeout = 1);
+                return Err(RepositoryError::internal(anyhow::anyhow!(
+                    "kvsd lease {key} timed out"
+                )));
+            }
+
+            let observed = Instant::now();
+            let free = Self::get::<Lease>(client, key.clone())
+                .await?
+                .map_or(true, |lease| lease.expires_at <= Utc::now());
+            if free {
+                let lease = Lease {
+                    owner: owner.clone(),
+                    expires_at: Utc::now() + Self::LEASE_TTL,
+                };
+                Self::set(client, key.clone(), &lease).await?;
+
+                // If the write was late, other instances could have acquired the lease.
+                // The written token is left to expire so that they keep excluding others
+                if observed.elapsed() < Self::LEASE_WRITE_WINDOW {
+                    tokio::time::sleep(Self::LEASE_WRITE_WINDOW).await;
+                    if Self::get::<Lease>(client, key.clone())
+                        .await?
+                        .is_some_and(|lease| lease.owner == owner)
+                    {
+                        return Ok(owner);
+                    }
+                }
+            }
+
+            metric!(monotonic_counter.kvsd.lease_conflict = 1);
+            tokio::time::sleep(Self::LEASE_RETRY_INTERVAL).await;
+        }
     }
 
-    async fn get<'a, T>(
-        client: &mut MutexGuard<'a, Client<TcpStream>>,
+    /// Release the lease if it is still owned
+    async fn release_lease<'a>(
+        client: &mut PooledClient<'a>,
         key: Key,
-    ) -> RepositoryResult<Option<T>>
-    where
-        T: TryFrom<Value>,
-        T::Error: Into<RepositoryError>,
-    {
-        let Some(value) = client.get(key).await.map_err(RepositoryError::internal)? else {
+        owner: &str,
+    ) -> RepositoryResult<()> {
+        if Self::get::<Lease>(client, key.clone())
+            .await?
+            .is_some_and(|lease| lease.owner == owner)
+        {
+            Self::delete(client, key).await?;
+        }
+        Ok(())
+    }
+
+    async fn get<'a, T: Versioned>(
+        client: &mut PooledClient<'a>,
+        key: Key,
+    ) -> RepositoryResult<Option<T>> {
+        Ok(Self::get_decoded(client, key)
+            .await?
+            .map(|decoded| decoded.value))
+    }
+
+    /// Outdated values are upgraded on read and rewritten on next write
+    async fn get_decoded<'a, T: Versioned>(
+        client: &mut PooledClient<'a>,
+        key: Key,
+    ) -> RepositoryResult<Option<Decoded<T>>> {
+        let Some(value) = client.get(key).await? else {
             return Ok(None);
         };
-        Ok(Some(value.try_into().map_err(Into::into)?))
+        Ok(Some(migration::decode(&value)?))
     }
 
-    async fn set<'a, T>(
-        client: &mut MutexGuard<'a, Client<TcpStream>>,
+    async fn set<'a, T: Versioned>(
+        client: &mut PooledClient<'a>,
         key: Key,
-        value: T,
-    ) -> RepositoryResult<()>
-    where
-        T: TryInto<Value>,
-        T::Error: Into<RepositoryError>,
-    {
-        let value = value.try_into().map_err(Into::into)?;
+        value: &T,
+    ) -> RepositoryResult<()> {
+        let value = Value::new(migration::encode(value)?).map_err(RepositoryError::internal)?;
         client.set(key, value).await?;
         Ok(())
     }
 
+    async fn delete<'a>(client: &mut PooledClient<'a>, key: Key) -> RepositoryResult<()> {
+        client.delete(key).await?;
+        Ok(())
+    }
+
+    /// Add the users to the users index if not registered yet.
+    /// The index is locked only if it needs to be written since it is shared by all users
+    async fn register_users<'a>(
+        &self,
+        client: &mut PooledClient<'a>,
+        user_ids: &[&str],
+    ) -> RepositoryResult<()> {
+        let key = Self::users_key();
+        let registered = |users: &Users| {
+            user_ids
+                .iter()
+                .all(|user_id| users.user_ids.contains(*user_id))
+        };
+
+        let users = Self::get::<Users>(client, key.clone()).await?;
+        if users.as_ref().is_some_and(registered) {
+            return Ok(());
+        }
+
+        let _lock = self.locks.lock([&key]).await;
+        let mut users = Self::get::<Users>(client, key.clone())
+            .await?
+            .unwrap_or_default();
+        if !registered(&users) {
+            users
+                .user_ids
+                .extend(user_ids.iter().map(|user_id| (*user_id).to_owned()));
+            Self::set(client, key, &users).await?;
+        }
+        Ok(())
+    }
+
+    fn user_key(user_id: &str) -> Key {
+        let key = format!("{prefix}/user/{user_id}", prefix = Self::key_prefix());
+        Key::new(key).expect("Invalid key")
+    }
+
+    fn identity_key(identity: &Identity) -> Key {
+        let key = format!(
+            "{prefix}/identity/{provider}/{subject}",
+            prefix = Self::key_prefix(),
+            provider = identity.provider,
+            subject = URL_SAFE_NO_PAD.encode(&identity.subject),
+        );
+        Key::new(key).expect("Invalid key")
+    }
+
+    fn email_key(email: &str) -> Key {
+        let key = format!(
+            "{prefix}/email/{email}",
+            prefix = Self::key_prefix(),
+            email = URL_SAFE_NO_PAD.encode(email),
+        );
+        Key::new(key).expect("Invalid key")
+    }
+
+    /// Follow the link to the user
+    async fn get_linked_user<'a>(
+        client: &mut PooledClient<'a>,
+        link_key: Key,
+    ) -> RepositoryResult<Option<UserRecord>> {
+        let Some(UserLink { user_id }) = Self::get::<UserLink>(client, link_key).await? else {
+            return Ok(None);
+        };
+        Ok(Self::get::<StoredUser>(client, Self::user_key(&user_id))
+            .await?
+            .map(|StoredUser(user)| user))
+    }
+
+    fn access_tokens_key(user_id: &str) -> Key {
+        let key = format!(
+            "{prefix}/access_tokens/{user_id}",
+            prefix = Self::key_prefix()
+        );
+        Key::new(key).expect("Invalid key")
+    }
+
+    fn access_token_hash_key(hash: &str) -> Key {
+        let key = format!("{prefix}/access_token/{hash}", prefix = Self::key_prefix());
+        Key::new(key).expect("Invalid key")
+    }
+
+    fn deletion_token_key(user_id: &str) -> Key {
+        let key = format!(
+            "{prefix}/deletion_token/{user_id}",
+            prefix = Self::key_prefix()
+        );
+        Key::new(key).expect("Invalid key")
+    }
+
+    fn disabled_users_key() -> Key {
+        let key = format!("{prefix}/disabled_users", prefix = Self::key_prefix());
+        Key::new(key).expect("Invalid key")
+    }
+
+    fn users_key() -> Key {
+        let key = format!("{prefix}/users", prefix = Self::key_prefix());
+        Key::new(key).expect("Invalid key")
+    }
+
+    fn subscription_lease_key(user_id: &str) -> Key {
+        let key = format!(
+            "{prefix}/lease/subscription/{user_id}",
+            prefix = Self::key_prefix()
+        );
+        Key::new(key).expect("Invalid key")
+    }
+
     fn feed_subscription_key(user_id: &str) -> Key {
         let key = format!(
             "{prefix}/subscription/{user_id}",
@@ -102,6 +443,16 @@ impl KvsdClient {
         Key::new(key).expect("Invalid key")
     }
 
+    fn entry_archive_key(feed_url: &str) -> Key {
+        // Feed url could contain characters which are not allowed in the key
+        let key = format!(
+            "{prefix}/archive/{feed}",
+            prefix = Self::key_prefix(),
+            feed = URL_SAFE_NO_PAD.encode(feed_url),
+        );
+        Key::new(key).expect("Invalid key")
+    }
+
     fn key_prefix() -> &'static str {
         "/synd_api/v1"
     }
@@ -114,22 +465,22 @@ impl SubscriptionRepository for KvsdClient {
         &self,
         feed: repository::types::FeedSubscription,
     ) -> RepositoryResult<()> {
-        let key = Self::feed_subscription_key(&feed.user_id);
-
-        let mut client = self.client.lock().await;
-
-        let urls = if let Some(mut urls) =
-            Self::get::<SubscriptionUrls>(&mut client, key.clone()).await?
-        {
-            urls.urls.insert(0, feed.url);
-            urls
-        } else {
-            SubscriptionUrls {
-                urls: vec![feed.url],
-            }
-        };
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            self.modify_subscriptions(&mut client, &feed.user_id, |subscriptions| {
+                if subscriptions.iter().all(|s| s.url != feed.url) {
+                    subscriptions.insert(
+                        0,
+                        repository::types::Subscription::new(feed.url.clone(), Utc::now()),
+                    );
+                }
+            })
+            .await?;
 
-        Self::set(&mut client, key, urls).await
+            self.register_users(&mut client, &[&feed.user_id]).await
+        }
+        .await;
+        client.finish(result)
     }
 
     #[tracing::instrument(name = "repo::delete_feed_subscription", skip_all)]
@@ -137,49 +488,702 @@ impl SubscriptionRepository for KvsdClient {
         &self,
         feed: repository::types::FeedSubscription,
     ) -> RepositoryResult<()> {
-        let key = Self::feed_subscription_key(&feed.user_id);
-
-        let mut client = self.client.lock().await;
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            self.modify_subscriptions(&mut client, &feed.user_id, |subscriptions| {
+                subscriptions.retain(|subscription| subscription.url != feed.url);
+            })
+            .await
+        }
+        .await;
+        client.finish(result)
+    }
 
-        let Some(mut urls) = Self::get::<SubscriptionUrls>(&mut client, key.clone()).await? else {
-            return Ok(());
-        };
+    #[tracing::instrument(name = "repo::put_feed_subscriptions", skip_all)]
+    async fn put_feed_subscriptions(
+        &self,
+        user_id: &str,
+        urls: Vec<String>,
+    ) -> RepositoryResult<()> {
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            self.modify_subscriptions(&mut client, user_id, |subscriptions| {
+                let now = Utc::now();
+                for url in &urls {
+                    if subscriptions.iter().all(|s| &s.url != url) {
+                        subscriptions
+                            .insert(0, repository::types::Subscription::new(url.clone(), now));
+                    }
+                }
+            })
+            .await?;
 
-        urls.urls.retain(|url| url != &feed.url);
+            self.register_users(&mut client, &[user_id]).await
+        }
+        .await;
+        client.finish(result)
+    }
 
-        Self::set(&mut client, key, urls).await
+    #[tracing::instrument(name = "repo::delete_feed_subscriptions", skip_all)]
+    async fn delete_feed_subscriptions(
+        &self,
+        user_id: &str,
+        urls: Vec<String>,
+    ) -> RepositoryResult<()> {
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            self.modify_subscriptions(&mut client, user_id, |subscriptions| {
+                subscriptions.retain(|subscription| !urls.contains(&subscription.url));
+            })
+            .await
+        }
+        .await;
+        client.finish(result)
     }
 
-    #[tracing::instrument(name = "repo::fetch_subscribed_feed_urls", skip_all)]
-    async fn fetch_subscribed_feed_urls(&self, user_id: &str) -> RepositoryResult<Vec<String>> {
+    #[tracing::instrument(name = "repo::fetch_subscriptions", skip_all)]
+    async fn fetch_subscriptions(
+        &self,
+        user_id: &str,
+    ) -> RepositoryResult<Vec<repository::types::Subscription>> {
         let key = Self::feed_subscription_key(user_id);
 
-        let mut client = self.client.lock().await;
-        let Some(urls) = Self::get::<SubscriptionUrls>(&mut client, key).await? else {
-            return Ok(Vec::new());
-        };
-        Ok(urls.urls)
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            let Some(subscriptions) = Self::get::<Subscriptions>(&mut client, key).await? else {
+                return Ok(Vec::new());
+            };
+            Ok(subscriptions.subscriptions)
+        }
+        .await;
+        client.finish(result)
+    }
+
+    #[tracing::instrument(name = "repo::update_subscription", skip_all)]
+    async fn update_subscription(
+        &self,
+        user_id: &str,
+        url: &str,
+        update: repository::types::SubscriptionUpdate,
+    ) -> RepositoryResult<Option<repository::types::Subscription>> {
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            self.modify_subscriptions(&mut client, user_id, |subscriptions| {
+                let subscription = subscriptions
+                    .iter_mut()
+                    .find(|subscription| subscription.url == url)?;
+                update.clone().apply(subscription);
+                Some(subscription.clone())
+            })
+            .await
+        }
+        .await;
+        client.finish(result)
+    }
+
+    async fn ping(&self) -> RepositoryResult<()> {
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            client.ping().await?;
+            Ok(())
+        }
+        .await;
+        client.finish(result)
+    }
+
+    fn component_id(&self) -> String {
+        format!("kvsd:{}", self.addr)
     }
 }
 
-#[derive(Serialize, Deserialize)]
-struct SubscriptionUrls {
-    urls: Vec<String>,
+#[async_trait]
+impl EntryArchiveRepository for KvsdClient {
+    #[tracing::instrument(name = "repo::fetch_archived_entries", skip_all)]
+    async fn fetch_archived_entries(&self, feed_url: &str) -> RepositoryResult<Vec<ArchivedEntry>> {
+        let key = Self::entry_archive_key(feed_url);
+
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            let Some(archive) = Self::get::<EntryArchive>(&mut client, key).await? else {
+                return Ok(Vec::new());
+            };
+            Ok(archive.entries)
+        }
+        .await;
+        client.finish(result)
+    }
+
+    #[tracing::instrument(name = "repo::archive_entries", skip_all)]
+    async fn archive_entries(
+        &self,
+        feed_url: &str,
+        entries: Vec<ArchivedEntry>,
+        retain_since: Time,
+    ) -> RepositoryResult<Vec<ArchivedEntry>> {
+        let key = Self::entry_archive_key(feed_url);
+
+        let _lock = self.locks.lock([&key]).await;
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            let mut archive = Self::get::<EntryArchive>(&mut client, key.clone())
+                .await?
+                .unwrap_or_default();
+
+            if merge_entries(&mut archive.entries, entries, retain_since) {
+                Self::set(&mut client, key, &archive).await?;
+            }
+
+            Ok(archive.entries)
+        }
+        .await;
+        client.finish(result)
+    }
 }
 
-impl TryFrom<Value> for SubscriptionUrls {
-    type Error = RepositoryError;
+#[async_trait]
+impl UserRepository for KvsdClient {
+    #[tracing::instrument(name = "repo::fetch_user_ids", skip_all)]
+    async fn fetch_user_ids(&self) -> RepositoryResult<Vec<String>> {
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            let users = Self::get::<Users>(&mut client, Self::users_key())
+                .await?
+                .unwrap_or_default();
+
+            Ok(users.user_ids.into_iter().collect())
+        }
+        .await;
+        client.finish(result)
+    }
+
+    #[tracing::instrument(name = "repo::fetch_user", skip_all)]
+    async fn fetch_user(&self, user_id: &str) -> RepositoryResult<Option<UserRecord>> {
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            Ok(
+                Self::get::<StoredUser>(&mut client, Self::user_key(user_id))
+                    .await?
+                    .map(|StoredUser(user)| user),
+            )
+        }
+        .await;
+        client.finish(result)
+    }
+
+    #[tracing::instrument(name = "repo::find_user_by_identity", skip_all)]
+    async fn find_user_by_identity(
+        &self,
+        identity: &Identity,
+    ) -> RepositoryResult<Option<UserRecord>> {
+        let mut client = self.pool.get().await?;
+        let result = Self::get_linked_user(&mut client, Self::identity_key(identity)).await;
+        client.finish(result)
+    }
+
+    #[tracing::instrument(name = "repo::find_user_by_email", skip_all)]
+    async fn find_user_by_email(&self, email: &str) -> RepositoryResult<Option<UserRecord>> {
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            // The old link could remain if the email change was interrupted
+            Ok(Self::get_linked_user(&mut client, Self::email_key(email))
+                .await?
+                .filter(|user| user.email == email))
+        }
+        .await;
+        client.finish(result)
+    }
+
+    #[tracing::instrument(name = "repo::put_user", skip_all)]
+    async fn put_user(&self, user: &UserRecord) -> RepositoryResult<()> {
+        let user_key = Self::user_key(&user.id);
+
+        let lock = self.locks.lock([&user_key]).await;
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            // Unlink the previous email so that the address no longer leads to the user
+            if let Some(StoredUser(previous)) =
+                Self::get::<StoredUser>(&mut client, user_key.clone()).await?
+            {
+                let key = Self::email_key(&previous.email);
+                if previous.email != user.email
+                    && Self::get::<UserLink>(&mut client, key.clone())
+                        .await?
+                        .is_some_and(|link| link.user_id == user.id)
+                {
+                    Self::delete(&mut client, key).await?;
+                }
+            }
+
+            Self::set(&mut client, user_key, &StoredUser(user.clone())).await?;
+
+            let link = UserLink {
+                user_id: user.id.clone(),
+            };
+            for identity in &user.identities {
+                Self::set(&mut client, Self::identity_key(identity), &link).await?;
+            }
+            Self::set(&mut client, Self::email_key(&user.email), &link).await?;
+            drop(lock);
+
+            self.register_users(&mut client, &[&user.id]).await
+        }
+        .await;
+        client.finish(result)
+    }
+
+    #[tracing::instrument(name = "repo::put_access_token", skip_all)]
+    async fn put_access_token(&self, token: &AccessToken) -> RepositoryResult<()> {
+        let key = Self::access_tokens_key(&token.user_id);
+
+        let _lock = self.locks.lock([&key]).await;
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            let mut tokens = Self::get::<AccessTokens>(&mut client, key.clone())
+                .await?
+                .unwrap_or_default();
+            tokens.tokens.push(token.clone());
+            Self::set(&mut client, key, &tokens).await?;
+
+            let link = UserLink {
+                user_id: token.user_id.clone(),
+            };
+            Self::set(&mut client, Self::access_token_hash_key(&token.hash), &link).await
+        }
+        .await;
+        client.finish(result)
+    }
+
+    #[tracing::instrument(name = "repo::fetch_access_tokens", skip_all)]
+    async fn fetch_access_tokens(&self, user_id: &str) -> RepositoryResult<Vec<AccessToken>> {
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            Ok(
+                Self::get::<AccessTokens>(&mut client, Self::access_tokens_key(user_id))
+                    .await?
+                    .unwrap_or_default()
+                    .tokens,
+            )
+        }
+        .await;
+        client.finish(result)
+    }
 
-    fn try_from(value: Value) -> Result<Self, Self::Error> {
-        serde_json::from_slice(&value).map_err(RepositoryError::internal)
+    #[tracing::instrument(name = "repo::find_access_token", skip_all)]
+    async fn find_access_token(&self, hash: &str) -> RepositoryResult<Option<AccessToken>> {
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            let Some(UserLink { user_id }) =
+                Self::get::<UserLink>(&mut client, Self::access_token_hash_key(hash)).await?
+            else {
+                return Ok(None);
+            };
+
+            Ok(
+                Self::get::<AccessTokens>(&mut client, Self::access_tokens_key(&user_id))
+                    .await?
+                    .unwrap_or_default()
+                    .tokens
+                    .into_iter()
+                    .find(|token| token.hash == hash),
+            )
+        }
+        .await;
+        client.finish(result)
     }
+
+    #[tracing::instrument(name = "repo::delete_access_token", skip_all)]
+    async fn delete_access_token(&self, user_id: &str, token_id: &str) -> RepositoryResult<bool> {
+        let key = Self::access_tokens_key(user_id);
+
+        let _lock = self.locks.lock([&key]).await;
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            let mut tokens = Self::get::<AccessTokens>(&mut client, key.clone())
+                .await?
+                .unwrap_or_default();
+            let Some(position) = tokens.tokens.iter().position(|token| token.id == token_id) else {
+                return Ok(false);
+            };
+            let token = tokens.tokens.remove(position);
+
+            Self::set(&mut client, key, &tokens).await?;
+            Self::delete(&mut client, Self::access_token_hash_key(&token.hash)).await?;
+
+            Ok(true)
+        }
+        .await;
+        client.finish(result)
+    }
+
+    #[tracing::instrument(name = "repo::put_deletion_token", skip_all)]
+    async fn put_deletion_token(&self, token: &DeletionToken) -> RepositoryResult<()> {
+        let mut client = self.pool.get().await?;
+        let result = Self::set(
+            &mut client,
+            Self::deletion_token_key(&token.user_id),
+            &StoredDeletionToken(token.clone()),
+        )
+        .await;
+        client.finish(result)
+    }
+
+    #[tracing::instrument(name = "repo::fetch_deletion_token", skip_all)]
+    async fn fetch_deletion_token(&self, user_id: &str) -> RepositoryResult<Option<DeletionToken>> {
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            Ok(
+                Self::get::<StoredDeletionToken>(&mut client, Self::deletion_token_key(user_id))
+                    .await?
+                    .map(|StoredDeletionToken(token)| token),
+            )
+        }
+        .await;
+        client.finish(result)
+    }
+
+    #[tracing::instrument(name = "repo::delete_deletion_token", skip_all)]
+    async fn delete_deletion_token(&self, user_id: &str) -> RepositoryResult<()> {
+        let mut client = self.pool.get().await?;
+        let result = Self::delete(&mut client, Self::deletion_token_key(user_id)).await;
+        client.finish(result)
+    }
+
+    #[tracing::instrument(name = "repo::fetch_disabled_user_ids", skip_all)]
+    async fn fetch_disabled_user_ids(&self) -> RepositoryResult<HashSet<String>> {
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            let disabled = Self::get::<DisabledUsers>(&mut client, Self::disabled_users_key())
+                .await?
+                .unwrap_or_default();
+
+            Ok(disabled.user_ids.into_iter().collect())
+        }
+        .await;
+        client.finish(result)
+    }
+
+    #[tracing::instrument(name = "repo::set_user_disabled", skip_all)]
+    async fn set_user_disabled(&self, user_id: &str, disabled: bool) -> RepositoryResult<()> {
+        let key = Self::disabled_users_key();
+
+        let _lock = self.locks.lock([&key]).await;
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            let mut users = Self::get::<DisabledUsers>(&mut client, key.clone())
+                .await?
+                .unwrap_or_default();
+
+            let changed = if disabled {
+                users.user_ids.insert(user_id.to_owned())
+            } else {
+                users.user_ids.remove(user_id)
+            };
+            if changed {
+                Self::set(&mut client, key, &users).await?;
+            }
+            Ok(())
+        }
+        .await;
+        client.finish(result)
+    }
+
+    #[tracing::instrument(name = "repo::delete_user", skip_all)]
+    async fn delete_user(&self, user_id: &str) -> RepositoryResult<()> {
+        let _lock = self
+            .locks
+            .lock(&[
+                Self::feed_subscription_key(user_id),
+                Self::access_tokens_key(user_id),
+                Self::user_key(user_id),
+                Self::users_key(),
+            ])
+            .await;
+        let mut client = self.pool.get().await?;
+        let result: RepositoryResult<_> = async {
+            Self::delete(&mut client, Self::feed_subscription_key(user_id)).await?;
+            Self::delete(&mut client, Self::deletion_token_key(user_id)).await?;
+
+            if let Some(tokens) =
+                Self::get::<AccessTokens>(&mut client, Self::access_tokens_key(user_id)).await?
+            {
+                for token in &tokens.tokens {
+                    Self::delete(&mut client, Self::access_token_hash_key(&token.hash)).await?;
+                }
+                Self::delete(&mut client, Self::access_tokens_key(user_id)).await?;
+            }
+
+            if let Some(StoredUser(user)) =
+                Self::get::<StoredUser>(&mut client, Self::user_key(user_id)).await?
+            {
+                for identity in &user.identities {
+                    Self::delete(&mut client, Self::identity_key(identity)).await?;
+                }
+                Self::delete(&mut client, Self::email_key(&user.email)).await?;
+                Self::delete(&mut client, Self::user_key(user_id)).await?;
+            }
+
+            let key = Self::users_key();
+            let mut users = Self::get::<Users>(&mut client, key.clone())
+                .await?
+                .unwrap_or_default();
+            if users.user_ids.remove(user_id) {
+                Self::set(&mut client, key, &users).await?;
+            }
+            Ok(())
+        }
+        .await;
+        client.finish(result)
+    }
+}
+
+/// Stored value of archived entries of a feed
+#[derive(Serialize, Deserialize, Default)]
+struct EntryArchive {
+    entries: Vec<ArchivedEntry>,
+}
+
+impl Versioned for EntryArchive {
+    const KIND: &'static str = "archive";
+    const VERSION: u32 = 1;
+}
+
+/// Stored value of user's subscriptions
+#[derive(Serialize, Deserialize, Default)]
+struct Subscriptions {
+    /// Incremented on every write to detect concurrent writes
+    revision: u64,
+    subscriptions: Vec<repository::types::Subscription>,
 }
 
-impl TryFrom<SubscriptionUrls> for Value {
-    type Error = RepositoryError;
+impl Versioned for Subscriptions {
+    const KIND: &'static str = "subscriptions";
+    const VERSION: u32 = 3;
 
-    fn try_from(value: SubscriptionUrls) -> Result<Self, Self::Error> {
-        let value = serde_json::to_vec(&value).map_err(RepositoryError::internal)?;
-        Ok(Value::new(value).unwrap())
+    fn migrations() -> &'static [Migration] {
+        SUBSCRIPTIONS_MIGRATIONS
+    }
+}
+
+const SUBSCRIPTIONS_MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 1,
+        description: "introduce subscription metadata",
+        upgrade: |mut value| {
+            let urls = match value["urls"].take() {
+                serde_json::Value::Array(urls) => urls,
+                serde_json::Value::Null => Vec::new(),
+                other => return Err(format!("unexpected urls: {other}")),
+            };
+            let subscriptions = urls
+                .into_iter()
+                .map(|url| serde_json::json!({ "url": url }))
+                .collect::<Vec<_>>();
+            Ok(serde_json::json!({ "subscriptions": subscriptions }))
+        },
+    },
+    Migration {
+        from: 2,
+        description: "introduce revision and remove duplicated subscriptions",
+        upgrade: |mut value| {
+            let mut seen = BTreeSet::new();
+            if let Some(subscriptions) = value["subscriptions"].as_array_mut() {
+                subscriptions.retain(|subscription| seen.insert(subscription["url"].to_string()));
+            }
+            value["revision"] = 0.into();
+            Ok(value)
+        },
+    },
+];
+
+/// Stored value of the lease which excludes writes from other instances
+#[derive(Serialize, Deserialize)]
+struct Lease {
+    owner: String,
+    expires_at: Time,
+}
+
+impl Versioned for Lease {
+    const KIND: &'static str = "lease";
+    const VERSION: u32 = 1;
+}
+
+/// Stored value of user ids which have subscriptions.
+/// Used to enumerate keys on migration since kvsd does not support key scan
+#[derive(Serialize, Deserialize, Default)]
+struct Users {
+    user_ids: BTreeSet<String>,
+}
+
+impl Versioned for Users {
+    const KIND: &'static str = "users";
+    const VERSION: u32 = 1;
+}
+
+/// Stored value of the user record
+#[derive(Serialize, Deserialize)]
+struct StoredUser(UserRecord);
+
+impl Versioned for StoredUser {
+    const KIND: &'static str = "user";
+    const VERSION: u32 = 1;
+}
+
+/// Stored value of access tokens issued for a user
+#[derive(Serialize, Deserialize, Default)]
+struct AccessTokens {
+    tokens: Vec<AccessToken>,
+}
+
+impl Versioned for AccessTokens {
+    const KIND: &'static str = "access_tokens";
+    const VERSION: u32 = 1;
+}
+
+/// Stored value of the token to confirm account deletion
+#[derive(Serialize, Deserialize)]
+struct StoredDeletionToken(DeletionToken);
+
+impl Versioned for StoredDeletionToken {
+    const KIND: &'static str = "deletion_token";
+    const VERSION: u32 = 1;
+}
+
+/// Stored value of the link from an identity, an email or an access token hash to the user
+#[derive(Serialize, Deserialize)]
+struct UserLink {
+    user_id: String,
+}
+
+impl Versioned for UserLink {
+    const KIND: &'static str = "user_link";
+    const VERSION: u32 = 1;
+}
+
+/// Stored value of user ids which are disabled by admin
+#[derive(Serialize, Deserialize, Default)]
+struct DisabledUsers {
+    user_ids: BTreeSet<String>,
+}
+
+impl Versioned for DisabledUsers {
+    const KIND: &'static str = "disabled_users";
+    const VERSION: u32 = 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::pending, sync::Arc};
+
+    use tokio::net::TcpListener;
+
+    use crate::repository::subscription;
+
+    use super::*;
+
+    /// Run kvsd on an ephemeral port and return its root directory and port
+    pub(super) async fn run_kvsd() -> (tempfile::TempDir, u16) {
+        let root_dir = tempfile::TempDir::new().unwrap();
+        let mut config = ::kvsd::config::Config::default();
+        config.kvsd.users = vec![::kvsd::core::UserEntry {
+            username: "test".into(),
+            password: "test".into(),
+        }];
+        config.server.set_disable_tls(&mut Some(true));
+
+        let mut initializer = ::kvsd::config::Initializer::from_config(config);
+        initializer.set_root_dir(root_dir.path());
+        let listener = TcpListener::bind(("localhost", 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        initializer.set_listener(listener);
+        initializer.init_dir().await.unwrap();
+
+        tokio::spawn(initializer.run_kvsd(pending::<()>()));
+
+        (root_dir, port)
+    }
+
+    async fn connect(port: u16) -> Arc<KvsdClient> {
+        KvsdClient::connect(
+            "localhost",
+            port,
+            "test".into(),
+            "test".into(),
+            PoolConfig::default(),
+        )
+        .await
+        .map(Arc::new)
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn concurrent_subscribe() {
+        let (_root_dir, port) = run_kvsd().await;
+        subscription::tests::concurrent_subscribe(connect(port).await).await;
+    }
+
+    /// Writes through instances which share kvsd are not lost
+    #[tokio::test]
+    async fn concurrent_subscribe_from_instances() {
+        let (_root_dir, port) = run_kvsd().await;
+        let instances = [connect(port).await, connect(port).await];
+        let urls = (0..6)
+            .map(|i| format!("https://{i}.example.com/feed.xml"))
+            .collect::<Vec<_>>();
+
+        let tasks = urls
+            .iter()
+            .zip(instances.iter().cycle())
+            .map(|(url, repo)| {
+                let repo = Arc::clone(repo);
+                let feed = repository::types::FeedSubscription {
+                    user_id: "user".into(),
+                    url: url.clone(),
+                };
+                tokio::spawn(async move { repo.put_feed_subscription(feed).await })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        for repo in &instances {
+            let mut subscribed = repo.fetch_subscribed_feed_urls("user").await.unwrap();
+            subscribed.sort();
+            assert_eq!(subscribed, urls);
+        }
+    }
+
+    #[tokio::test]
+    async fn unlink_previous_email() {
+        let (_root_dir, port) = run_kvsd().await;
+        let repo = connect(port).await;
+        let mut user = UserRecord {
+            id: "user-1".into(),
+            email: "old@example.com".into(),
+            identities: vec![Identity::new("github", "1")],
+            created_at: Utc::now(),
+        };
+        repo.put_user(&user).await.unwrap();
+
+        user.email = "new@example.com".into();
+        repo.put_user(&user).await.unwrap();
+
+        assert!(repo
+            .find_user_by_email("old@example.com")
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            repo.find_user_by_email("new@example.com")
+                .await
+                .unwrap()
+                .map(|user| user.id),
+            Some(user.id.clone())
+        );
+        let mut client = repo.pool.get().await.unwrap();
+        let link =
+            KvsdClient::get::<UserLink>(&mut client, KvsdClient::email_key("old@example.com"))
+                .await;
+        assert!(client.finish(link).unwrap().is_none());
     }
 }
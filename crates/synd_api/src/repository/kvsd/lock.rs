@@ -0,0 +1,91 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{Arc, Mutex, Weak},
+};
+
+use kvsd::Key;
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// Locks of keys within the process.
+/// Read-modify-write operations on the same key are serialized while operations on other keys
+/// proceed concurrently
+#[derive(Default)]
+pub(super) struct KeyLocks {
+    locks: Mutex<HashMap<String, Weak<AsyncMutex<()>>>>,
+}
+
+/// Locks held until dropped
+pub(super) struct KeyGuards {
+    _guards: Vec<OwnedMutexGuard<()>>,
+}
+
+impl KeyLocks {
+    /// Lock given keys. Keys are locked in order so that operations on multiple keys do not
+    /// deadlock
+    pub(super) async fn lock<'k>(&self, keys: impl IntoIterator<Item = &'k Key>) -> KeyGuards {
+        let keys = keys
+            .into_iter()
+            .map(|key| key.as_str())
+            .collect::<BTreeSet<_>>();
+
+        let mut guards = Vec::with_capacity(keys.len());
+        for key in keys {
+            guards.push(self.get(key).lock_owned().await);
+        }
+        KeyGuards { _guards: guards }
+    }
+
+    fn get(&self, key: &str) -> Arc<AsyncMutex<()>> {
+        let mut locks = self.locks.lock().expect("kvsd key locks poisoned");
+        // Locks which are neither held nor waited are no longer needed
+        locks.retain(|_, lock| lock.strong_count() > 0);
+
+        if let Some(lock) = locks.get(key).and_then(Weak::upgrade) {
+            return lock;
+        }
+        let lock = Arc::new(AsyncMutex::new(()));
+        locks.insert(key.to_owned(), Arc::downgrade(&lock));
+        lock
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn key(key: &str) -> Key {
+        Key::new(key.to_owned()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn lock_same_key_exclusively() {
+        let locks = KeyLocks::default();
+        let (a, b) = (key("a"), key("b"));
+
+        let guard = locks.lock([&a, &b]).await;
+        let timeout = Duration::from_millis(50);
+        assert!(tokio::time::timeout(timeout, locks.lock([&b]))
+            .await
+            .is_err());
+        assert!(tokio::time::timeout(timeout, locks.lock([&key("c")]))
+            .await
+            .is_ok());
+
+        drop(guard);
+        assert!(tokio::time::timeout(timeout, locks.lock([&b, &a]))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn remove_unused_locks() {
+        let locks = KeyLocks::default();
+
+        drop(locks.lock([&key("a")]).await);
+        let _guard = locks.lock([&key("b")]).await;
+
+        assert_eq!(locks.locks.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,452 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use futures_util::TryFutureExt;
+use kvsd::{
+    client::{
+        tcp::{Client, UnauthenticatedClient},
+        Api,
+    },
+    KvsdError,
+};
+use synd_o11y::metric;
+use tokio::{
+    net::TcpStream,
+    sync::{Semaphore, SemaphorePermit},
+};
+
+use crate::repository::{kvsd::ConnectKvsdFailed, subscription::RepositoryResult, RepositoryError};
+
+pub struct PoolConfig {
+    size: usize,
+    connect_timeout: Duration,
+    acquire_timeout: Duration,
+    health_check_interval: Duration,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            size: 8,
+            connect_timeout: Duration::from_secs(10),
+            acquire_timeout: Duration::from_secs(5),
+            health_check_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+impl PoolConfig {
+    /// Maximum number of connections
+    #[must_use]
+    pub fn with_size(self, size: usize) -> Self {
+        Self { size, ..self }
+    }
+
+    /// Timeout of establishing a connection including retries
+    #[must_use]
+    pub fn with_connect_timeout(self, connect_timeout: Duration) -> Self {
+        Self {
+            connect_timeout,
+            ..self
+        }
+    }
+
+    /// Timeout of waiting for an available connection
+    #[must_use]
+    pub fn with_acquire_timeout(self, acquire_timeout: Duration) -> Self {
+        Self {
+            acquire_timeout,
+            ..self
+        }
+    }
+
+    /// Connections which have been idle longer than this are pinged before use
+    #[must_use]
+    pub fn with_health_check_interval(self, health_check_interval: Duration) -> Self {
+        Self {
+            health_check_interval,
+            ..self
+        }
+    }
+}
+
+pub(super) struct Connector {
+    pub(super) host: String,
+    pub(super) port: u16,
+    pub(super) username: String,
+    pub(super) password: String,
+}
+
+impl Connector {
+    const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+    const MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+    /// Connect and authenticate. io errors are retried with exponential backoff until timeout
+    async fn connect(&self, timeout: Duration) -> anyhow::Result<Client<TcpStream>> {
+        let handshake = async {
+            let mut retry = 0;
+            let mut backoff = Self::INITIAL_BACKOFF;
+            loop {
+                match UnauthenticatedClient::insecure_from_addr(&self.host, self.port)
+                    .and_then(|client| client.authenticate(&self.username, &self.password))
+                    .await
+                {
+                    Ok(client) => break Ok(client),
+                    Err(KvsdError::Io(io)) => {
+                        tracing::info!(retry, ?backoff, "Kvsd connection failed: {io}");
+                    }
+                    Err(err) => break Err(err),
+                }
+                metric!(monotonic_counter.kvsd.pool.connect_retry = 1);
+
+                tokio::time::sleep(backoff).await;
+                retry += 1;
+                backoff = (backoff * 2).min(Self::MAX_BACKOFF);
+            }
+        };
+
+        tokio::time::timeout(timeout, handshake)
+            .await
+            .map_err(anyhow::Error::from)
+            .context(ConnectKvsdFailed)?
+            .map_err(anyhow::Error::from)
+    }
+}
+
+struct IdleClient {
+    client: Client<TcpStream>,
+    last_used: Instant,
+}
+
+/// Pool of authenticated connections.
+/// Connections are established lazily up to the pool size
+pub(super) struct Pool {
+    connector: Connector,
+    config: PoolConfig,
+    idle: Mutex<Vec<IdleClient>>,
+    permits: Semaphore,
+}
+
+impl Pool {
+    pub(super) fn new(connector: Connector, config: PoolConfig) -> Self {
+        Self {
+            permits: Semaphore::new(config.size),
+            idle: Mutex::new(Vec::with_capacity(config.size)),
+            connector,
+            config,
+        }
+    }
+
+    /// Establish a connection to make sure kvsd is available
+    pub(super) async fn warm_up(&self) -> anyhow::Result<()> {
+        let client = self.connector.connect(self.config.connect_timeout).await?;
+        metric!(counter.kvsd.pool.connections = 1);
+
+        self.push_idle(client);
+        Ok(())
+    }
+
+    /// Get a connection. If there is no idle connection, connect new one
+    pub(super) async fn get(&self) -> RepositoryResult<PooledClient<'_>> {
+        let started = Instant::now();
+        let permit = tokio::time::timeout(self.config.acquire_timeout, self.permits.acquire())
+            .await
+            .map_err(|_| {
+                metric!(monotonic_counter.kvsd.pool.acquire_timeout = 1);
+                RepositoryError::internal(anyhow::anyhow!("kvsd connection pool timed out"))
+            })?
+            .expect("kvsd connection pool closed");
+        metric!(histogram.kvsd.pool.acquire_duration = started.elapsed().as_secs_f64());
+
+        while let Some(IdleClient {
+            mut client,
+            last_used,
+        }) = self.pop_idle()
+        {
+            if last_used.elapsed() < self.config.health_check_interval
+                || client.ping().await.is_ok()
+            {
+                return Ok(PooledClient::new(self, client, permit));
+            }
+            tracing::info!("Discard unhealthy kvsd connection");
+            metric!(counter.kvsd.pool.connections = -1);
+        }
+
+        let client = self
+            .connector
+            .connect(self.config.connect_timeout)
+            .await
+            .map_err(RepositoryError::internal)?;
+        metric!(counter.kvsd.pool.connections = 1);
+
+        Ok(PooledClient::new(self, client, permit))
+    }
+
+    fn pop_idle(&self) -> Option<IdleClient> {
+        self.idle.lock().expect("kvsd pool poisoned").pop()
+    }
+
+    fn push_idle(&self, client: Client<TcpStream>) {
+        self.idle
+            .lock()
+            .expect("kvsd pool poisoned")
+            .push(IdleClient {
+                client,
+                last_used: Instant::now(),
+            });
+    }
+}
+
+/// Connection borrowed from the pool.
+/// Returned to the pool on drop only if the operations on it are finished.
+/// Otherwise, such as the request future is cancelled, the response could be left in the
+/// connection, so it is closed
+pub(super) struct PooledClient<'a> {
+    pool: &'a Pool,
+    client: Option<Client<TcpStream>>,
+    completed: bool,
+    _permit: SemaphorePermit<'a>,
+}
+
+impl<'a> PooledClient<'a> {
+    fn new(pool: &'a Pool, client: Client<TcpStream>, permit: SemaphorePermit<'a>) -> Self {
+        metric!(counter.kvsd.pool.in_use = 1);
+        Self {
+            pool,
+            client: Some(client),
+            completed: false,
+            _permit: permit,
+        }
+    }
+
+    /// Release the connection with the result of the operations on it.
+    /// If the operations failed in kvsd, the connection could be broken, so it is discarded
+    pub(super) fn finish<T>(mut self, result: RepositoryResult<T>) -> RepositoryResult<T> {
+        self.completed = match &result {
+            Err(RepositoryError::Internal(err)) => !err.is::<KvsdError>(),
+            _ => true,
+        };
+        result
+    }
+}
+
+impl<'a> Deref for PooledClient<'a> {
+    type Target = Client<TcpStream>;
+
+    fn deref(&self) -> &Self::Target {
+        self.client.as_ref().expect("kvsd connection discarded")
+    }
+}
+
+impl<'a> DerefMut for PooledClient<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.client.as_mut().expect("kvsd connection discarded")
+    }
+}
+
+impl<'a> Drop for PooledClient<'a> {
+    fn drop(&mut self) {
+        metric!(counter.kvsd.pool.in_use = -1);
+        let Some(client) = self.client.take() else {
+            return;
+        };
+        if self.completed {
+            self.pool.push_idle(client);
+        } else {
+            tracing::info!("Discard kvsd connection");
+            metric!(counter.kvsd.pool.connections = -1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::{net::TcpListener, task::JoinHandle};
+
+    use crate::repository::kvsd::tests::run_kvsd;
+
+    use super::*;
+
+    /// Proxy connections to kvsd so that the connections can be closed from the test
+    #[derive(Clone, Default)]
+    struct Proxy {
+        connections: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    }
+
+    impl Proxy {
+        fn spawn(listener: TcpListener, upstream: u16) -> Self {
+            let proxy = Proxy::default();
+            let connections = Arc::clone(&proxy.connections);
+            tokio::spawn(async move {
+                loop {
+                    let (mut inbound, _) = listener.accept().await.unwrap();
+                    connections.lock().unwrap().push(tokio::spawn(async move {
+                        let mut outbound =
+                            TcpStream::connect(("localhost", upstream)).await.unwrap();
+                        let _ = tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await;
+                    }));
+                }
+            });
+            proxy
+        }
+
+        fn accepted(&self) -> usize {
+            self.connections.lock().unwrap().len()
+        }
+
+        fn close_all(&self) {
+            for connection in self.connections.lock().unwrap().iter() {
+                connection.abort();
+            }
+        }
+    }
+
+    fn pool(port: u16, config: PoolConfig) -> Pool {
+        let connector = Connector {
+            host: "localhost".into(),
+            port,
+            username: "test".into(),
+            password: "test".into(),
+        };
+        Pool::new(connector, config)
+    }
+
+    #[tokio::test]
+    async fn size_bounds_connections_in_use() {
+        let (_root_dir, port) = run_kvsd().await;
+        let pool = pool(
+            port,
+            PoolConfig::default()
+                .with_size(1)
+                .with_acquire_timeout(Duration::from_millis(100)),
+        );
+
+        let client = pool.get().await.unwrap();
+        assert!(pool.get().await.is_err());
+
+        drop(client);
+        let mut client = pool.get().await.unwrap();
+        assert!(client.ping().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn reuse_finished_connection() {
+        let (_root_dir, port) = run_kvsd().await;
+        let listener = TcpListener::bind(("localhost", 0)).await.unwrap();
+        let proxy_port = listener.local_addr().unwrap().port();
+        let proxy = Proxy::spawn(listener, port);
+        let pool = pool(proxy_port, PoolConfig::default());
+
+        for _ in 0..3 {
+            let mut client = pool.get().await.unwrap();
+            let result = client.ping().await.map_err(RepositoryError::from);
+            assert!(client.finish(result).is_ok());
+        }
+        assert_eq!(proxy.accepted(), 1);
+    }
+
+    #[tokio::test]
+    async fn discard_connection_dropped_mid_request() {
+        let (_root_dir, port) = run_kvsd().await;
+        let listener = TcpListener::bind(("localhost", 0)).await.unwrap();
+        let proxy_port = listener.local_addr().unwrap().port();
+        let proxy = Proxy::spawn(listener, port);
+        let pool = pool(proxy_port, PoolConfig::default());
+
+        // Cancel the request after it is sent, leaving the response in the connection
+        let mut client = pool.get().await.unwrap();
+        let _ = tokio::time::timeout(Duration::ZERO, client.ping()).await;
+        drop(client);
+
+        let mut client = pool.get().await.unwrap();
+        assert!(client.ping().await.is_ok());
+        assert_eq!(proxy.accepted(), 2);
+    }
+
+    #[tokio::test]
+    async fn evict_unhealthy_idle_connection() {
+        let (_root_dir, port) = run_kvsd().await;
+        let listener = TcpListener::bind(("localhost", 0)).await.unwrap();
+        let proxy_port = listener.local_addr().unwrap().port();
+        let proxy = Proxy::spawn(listener, port);
+        let pool = pool(
+            proxy_port,
+            PoolConfig::default().with_health_check_interval(Duration::ZERO),
+        );
+
+        pool.warm_up().await.unwrap();
+        proxy.close_all();
+        // Wait for the connection to be closed
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let mut client = pool.get().await.unwrap();
+        assert!(client.ping().await.is_ok());
+        assert_eq!(proxy.accepted(), 2);
+    }
+
+    #[tokio::test]
+    async fn discard_failed_connection() {
+        let (_root_dir, port) = run_kvsd().await;
+        let listener = TcpListener::bind(("localhost", 0)).await.unwrap();
+        let proxy_port = listener.local_addr().unwrap().port();
+        let proxy = Proxy::spawn(listener, port);
+        let pool = pool(proxy_port, PoolConfig::default());
+
+        let mut client = pool.get().await.unwrap();
+        proxy.close_all();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let result = client.ping().await.map_err(RepositoryError::from);
+        assert!(client.finish(result).is_err());
+
+        // Broken connection is not reused even if the health check is skipped
+        let mut client = pool.get().await.unwrap();
+        assert!(client.ping().await.is_ok());
+        assert_eq!(proxy.accepted(), 2);
+    }
+
+    #[tokio::test]
+    async fn reconnect_with_backoff() {
+        let (_root_dir, port) = run_kvsd().await;
+        // Reserve a port which kvsd is not listening yet
+        let proxy_port = TcpListener::bind(("localhost", 0))
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let delay = Duration::from_millis(300);
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let listener = TcpListener::bind(("localhost", proxy_port)).await.unwrap();
+            Proxy::spawn(listener, port);
+        });
+
+        let connector = pool(proxy_port, PoolConfig::default()).connector;
+        let started = Instant::now();
+        assert!(connector.connect(Duration::from_secs(5)).await.is_ok());
+        assert!(started.elapsed() >= delay);
+    }
+
+    #[tokio::test]
+    async fn connect_timeout() {
+        let port = TcpListener::bind(("localhost", 0))
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let connector = pool(port, PoolConfig::default()).connector;
+        let err = connector
+            .connect(Duration::from_millis(300))
+            .await
+            .unwrap_err();
+        assert!(err.is::<ConnectKvsdFailed>());
+    }
+}
@@ -1,14 +1,28 @@
-use std::sync::RwLock;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::RwLock,
+};
 
 use async_trait::async_trait;
+use chrono::Utc;
+use synd_feed::types::Time;
 
 use crate::repository::{
     self,
+    archive::merge_entries,
     subscription::{RepositoryResult, SubscriptionRepository},
+    types::{AccessToken, ArchivedEntry, DeletionToken, Identity, UserRecord},
+    EntryArchiveRepository, UserRepository,
 };
 
 pub struct MemoryRepository {
-    feeds: RwLock<Vec<repository::types::FeedSubscription>>,
+    feeds: RwLock<Vec<repository::types::Subscription>>,
+    archive: RwLock<HashMap<String, Vec<ArchivedEntry>>>,
+    users: RwLock<HashSet<String>>,
+    records: RwLock<HashMap<String, UserRecord>>,
+    access_tokens: RwLock<Vec<AccessToken>>,
+    deletion_tokens: RwLock<HashMap<String, DeletionToken>>,
+    disabled_users: RwLock<HashSet<String>>,
 }
 
 const TEST_DATA: &[&str] = &[
@@ -39,12 +53,15 @@ impl MemoryRepository {
             feeds: RwLock::new(
                 TEST_DATA
                     .iter()
-                    .map(|feed| repository::types::FeedSubscription {
-                        user_id: "me".into(),
-                        url: (*feed).to_string(),
-                    })
+                    .map(|feed| repository::types::Subscription::from_url(*feed))
                     .collect(),
             ),
+            archive: RwLock::new(HashMap::new()),
+            users: RwLock::new(HashSet::new()),
+            records: RwLock::new(HashMap::new()),
+            access_tokens: RwLock::new(Vec::new()),
+            deletion_tokens: RwLock::new(HashMap::new()),
+            disabled_users: RwLock::new(HashSet::new()),
         }
     }
 }
@@ -55,7 +72,11 @@ impl SubscriptionRepository for MemoryRepository {
         &self,
         feed: repository::types::FeedSubscription,
     ) -> RepositoryResult<()> {
-        self.feeds.write().unwrap().push(feed);
+        let mut feeds = self.feeds.write().unwrap();
+        if feeds.iter().all(|sub| sub.url != feed.url) {
+            feeds.push(repository::types::Subscription::new(feed.url, Utc::now()));
+        }
+        self.users.write().unwrap().insert(feed.user_id);
         Ok(())
     }
 
@@ -71,13 +92,192 @@ impl SubscriptionRepository for MemoryRepository {
         Ok(())
     }
 
-    async fn fetch_subscribed_feed_urls(&self, _user_id: &str) -> RepositoryResult<Vec<String>> {
+    async fn fetch_subscriptions(
+        &self,
+        _user_id: &str,
+    ) -> RepositoryResult<Vec<repository::types::Subscription>> {
+        Ok(self.feeds.read().unwrap().clone())
+    }
+
+    async fn update_subscription(
+        &self,
+        _user_id: &str,
+        url: &str,
+        update: repository::types::SubscriptionUpdate,
+    ) -> RepositoryResult<Option<repository::types::Subscription>> {
+        let mut feeds = self.feeds.write().unwrap();
+        let Some(subscription) = feeds.iter_mut().find(|sub| sub.url == url) else {
+            return Ok(None);
+        };
+        update.apply(subscription);
+        Ok(Some(subscription.clone()))
+    }
+
+    async fn ping(&self) -> RepositoryResult<()> {
+        Ok(())
+    }
+
+    fn component_id(&self) -> String {
+        "memory".into()
+    }
+}
+
+#[async_trait]
+impl EntryArchiveRepository for MemoryRepository {
+    async fn fetch_archived_entries(&self, feed_url: &str) -> RepositoryResult<Vec<ArchivedEntry>> {
+        Ok(self
+            .archive
+            .read()
+            .unwrap()
+            .get(feed_url)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn archive_entries(
+        &self,
+        feed_url: &str,
+        entries: Vec<ArchivedEntry>,
+        retain_since: Time,
+    ) -> RepositoryResult<Vec<ArchivedEntry>> {
+        let mut archive = self.archive.write().unwrap();
+        let archived = archive.entry(feed_url.to_owned()).or_default();
+        merge_entries(archived, entries, retain_since);
+        Ok(archived.clone())
+    }
+}
+
+#[async_trait]
+impl UserRepository for MemoryRepository {
+    async fn fetch_user_ids(&self) -> RepositoryResult<Vec<String>> {
+        let mut user_ids = self.users.read().unwrap().clone();
+        user_ids.extend(self.records.read().unwrap().keys().cloned());
+        Ok(user_ids.into_iter().collect())
+    }
+
+    async fn fetch_user(&self, user_id: &str) -> RepositoryResult<Option<UserRecord>> {
+        Ok(self.records.read().unwrap().get(user_id).cloned())
+    }
+
+    async fn find_user_by_identity(
+        &self,
+        identity: &Identity,
+    ) -> RepositoryResult<Option<UserRecord>> {
         Ok(self
-            .feeds
+            .records
+            .read()
+            .unwrap()
+            .values()
+            .find(|user| user.identities.contains(identity))
+            .cloned())
+    }
+
+    async fn find_user_by_email(&self, email: &str) -> RepositoryResult<Option<UserRecord>> {
+        Ok(self
+            .records
+            .read()
+            .unwrap()
+            .values()
+            .find(|user| user.email == email)
+            .cloned())
+    }
+
+    async fn put_user(&self, user: &UserRecord) -> RepositoryResult<()> {
+        self.records
+            .write()
+            .unwrap()
+            .insert(user.id.clone(), user.clone());
+        Ok(())
+    }
+
+    async fn put_access_token(&self, token: &AccessToken) -> RepositoryResult<()> {
+        self.access_tokens.write().unwrap().push(token.clone());
+        Ok(())
+    }
+
+    async fn fetch_access_tokens(&self, user_id: &str) -> RepositoryResult<Vec<AccessToken>> {
+        Ok(self
+            .access_tokens
             .read()
             .unwrap()
             .iter()
-            .map(|feed| feed.url.clone())
+            .filter(|token| token.user_id == user_id)
+            .cloned()
             .collect())
     }
+
+    async fn find_access_token(&self, hash: &str) -> RepositoryResult<Option<AccessToken>> {
+        Ok(self
+            .access_tokens
+            .read()
+            .unwrap()
+            .iter()
+            .find(|token| token.hash == hash)
+            .cloned())
+    }
+
+    async fn delete_access_token(&self, user_id: &str, token_id: &str) -> RepositoryResult<bool> {
+        let mut tokens = self.access_tokens.write().unwrap();
+        let len = tokens.len();
+        tokens.retain(|token| !(token.user_id == user_id && token.id == token_id));
+        Ok(tokens.len() < len)
+    }
+
+    async fn put_deletion_token(&self, token: &DeletionToken) -> RepositoryResult<()> {
+        self.deletion_tokens
+            .write()
+            .unwrap()
+            .insert(token.user_id.clone(), token.clone());
+        Ok(())
+    }
+
+    async fn fetch_deletion_token(&self, user_id: &str) -> RepositoryResult<Option<DeletionToken>> {
+        Ok(self.deletion_tokens.read().unwrap().get(user_id).cloned())
+    }
+
+    async fn delete_deletion_token(&self, user_id: &str) -> RepositoryResult<()> {
+        self.deletion_tokens.write().unwrap().remove(user_id);
+        Ok(())
+    }
+
+    async fn fetch_disabled_user_ids(&self) -> RepositoryResult<HashSet<String>> {
+        Ok(self.disabled_users.read().unwrap().clone())
+    }
+
+    async fn set_user_disabled(&self, user_id: &str, disabled: bool) -> RepositoryResult<()> {
+        let mut disabled_users = self.disabled_users.write().unwrap();
+        if disabled {
+            disabled_users.insert(user_id.to_owned());
+        } else {
+            disabled_users.remove(user_id);
+        }
+        Ok(())
+    }
+
+    async fn delete_user(&self, user_id: &str) -> RepositoryResult<()> {
+        // Subscriptions are shared by all users
+        self.feeds.write().unwrap().clear();
+        self.users.write().unwrap().remove(user_id);
+        self.records.write().unwrap().remove(user_id);
+        self.access_tokens
+            .write()
+            .unwrap()
+            .retain(|token| token.user_id != user_id);
+        self.deletion_tokens.write().unwrap().remove(user_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::repository::subscription;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn concurrent_subscribe() {
+        subscription::tests::concurrent_subscribe(Arc::new(MemoryRepository::new())).await;
+    }
 }
@@ -0,0 +1,284 @@
+//! Versioning of stored values.
+//!
+//! Every stored value is a json object which has `version` field.
+//! Values which were stored before versioning was introduced lack the field and are regarded as version 1.
+//! When reading a value which is older than the current version,
+//! the registered upgrade steps are applied in order.
+
+use std::fmt;
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+const VERSION_FIELD: &str = "version";
+
+/// Version of values stored before versioning was introduced
+pub const INITIAL_VERSION: u32 = 1;
+
+/// Upgrade step from `from` version to the next version
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    /// Return error message if the value could not be upgraded
+    pub upgrade: fn(Value) -> Result<Value, String>,
+}
+
+/// Value which is stored with version
+pub trait Versioned: Serialize + DeserializeOwned {
+    /// Kind of the value used in reports
+    const KIND: &'static str;
+    /// Current version
+    const VERSION: u32;
+
+    /// Upgrade steps which are applied to older values
+    fn migrations() -> &'static [Migration] {
+        &[]
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    #[error("malformed value: {0}")]
+    Malformed(#[from] serde_json::Error),
+    #[error("{kind} version {version} is newer than supported version {supported}")]
+    UnsupportedVersion {
+        kind: &'static str,
+        version: u32,
+        supported: u32,
+    },
+    #[error("{kind} upgrade step from version {from} not found")]
+    MissingStep { kind: &'static str, from: u32 },
+    #[error("upgrade {kind} from version {from}: {message}")]
+    Upgrade {
+        kind: &'static str,
+        from: u32,
+        message: String,
+    },
+}
+
+/// Decoded value with the version at which it was stored
+pub struct Decoded<T> {
+    pub value: T,
+    pub stored_version: u32,
+}
+
+impl<T: Versioned> Decoded<T> {
+    /// Whether the stored value need to be rewritten in the current version
+    pub fn is_outdated(&self) -> bool {
+        self.stored_version < T::VERSION
+    }
+}
+
+/// Return the version of stored value
+pub fn stored_version(value: &Value) -> u32 {
+    value
+        .get(VERSION_FIELD)
+        .and_then(Value::as_u64)
+        .and_then(|version| u32::try_from(version).ok())
+        .unwrap_or(INITIAL_VERSION)
+}
+
+/// Decode stored bytes into the current version of `T`
+pub fn decode<T: Versioned>(bytes: &[u8]) -> Result<Decoded<T>, MigrationError> {
+    let mut value: Value = serde_json::from_slice(bytes)?;
+    let stored_version = stored_version(&value);
+
+    if stored_version > T::VERSION {
+        return Err(MigrationError::UnsupportedVersion {
+            kind: T::KIND,
+            version: stored_version,
+            supported: T::VERSION,
+        });
+    }
+
+    for from in stored_version..T::VERSION {
+        let step = T::migrations()
+            .iter()
+            .find(|step| step.from == from)
+            .ok_or(MigrationError::MissingStep {
+                kind: T::KIND,
+                from,
+            })?;
+
+        value = (step.upgrade)(value).map_err(|message| MigrationError::Upgrade {
+            kind: T::KIND,
+            from,
+            message,
+        })?;
+        set_version(&mut value, from + 1);
+    }
+
+    Ok(Decoded {
+        value: serde_json::from_value(value)?,
+        stored_version,
+    })
+}
+
+/// Encode value with the current version
+pub fn encode<T: Versioned>(value: &T) -> Result<Vec<u8>, MigrationError> {
+    let mut value = serde_json::to_value(value)?;
+    set_version(&mut value, T::VERSION);
+    Ok(serde_json::to_vec(&value)?)
+}
+
+fn set_version(value: &mut Value, version: u32) {
+    if let Value::Object(object) = value {
+        object.insert(VERSION_FIELD.to_owned(), version.into());
+    }
+}
+
+/// Result of migration of a stored value
+#[derive(Debug)]
+pub enum MigrationStatus {
+    /// Already in the current version
+    UpToDate,
+    /// Upgraded. not written if dry run
+    Upgraded {
+        from: u32,
+        to: u32,
+    },
+    Failed(String),
+}
+
+#[derive(Debug)]
+pub struct MigrationRecord {
+    pub kind: &'static str,
+    pub key: String,
+    pub status: MigrationStatus,
+}
+
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    pub dry_run: bool,
+    pub records: Vec<MigrationRecord>,
+}
+
+impl MigrationReport {
+    pub fn new(dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            records: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, kind: &'static str, key: impl Into<String>, status: MigrationStatus) {
+        self.records.push(MigrationRecord {
+            kind,
+            key: key.into(),
+            status,
+        });
+    }
+
+    pub fn upgraded(&self) -> usize {
+        self.records
+            .iter()
+            .filter(|record| matches!(record.status, MigrationStatus::Upgraded { .. }))
+            .count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.records
+            .iter()
+            .filter(|record| matches!(record.status, MigrationStatus::Failed(_)))
+            .count()
+    }
+}
+
+impl fmt::Display for MigrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for MigrationRecord { kind, key, status } in &self.records {
+            match status {
+                MigrationStatus::UpToDate => writeln!(f, "up-to-date {kind} {key}")?,
+                MigrationStatus::Upgraded { from, to } => {
+                    writeln!(f, "upgraded   {kind} {key} v{from} -> v{to}")?;
+                }
+                MigrationStatus::Failed(err) => writeln!(f, "failed     {kind} {key}: {err}")?,
+            }
+        }
+        write!(
+            f,
+            "{total} values checked, {upgraded} upgraded, {failed} failed{dry_run}",
+            total = self.records.len(),
+            upgraded = self.upgraded(),
+            failed = self.failed(),
+            dry_run = if self.dry_run {
+                " (dry run, nothing written)"
+            } else {
+                ""
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Names {
+        names: Vec<String>,
+    }
+
+    const MIGRATIONS: &[Migration] = &[
+        Migration {
+            from: 1,
+            description: "rename list to items",
+            upgrade: |mut value| {
+                let list = value["list"].take();
+                Ok(json!({ "items": list }))
+            },
+        },
+        Migration {
+            from: 2,
+            description: "rename items to names",
+            upgrade: |mut value| {
+                let items = value["items"].take();
+                Ok(json!({ "names": items }))
+            },
+        },
+    ];
+
+    impl Versioned for Names {
+        const KIND: &'static str = "names";
+        const VERSION: u32 = 3;
+
+        fn migrations() -> &'static [Migration] {
+            MIGRATIONS
+        }
+    }
+
+    #[test]
+    fn decode_applies_upgrade_steps_in_order() {
+        let decoded = decode::<Names>(br#"{"list":["a"]}"#).unwrap();
+        assert_eq!(decoded.stored_version, 1);
+        assert!(decoded.is_outdated());
+        assert_eq!(decoded.value.names, vec!["a".to_owned()]);
+
+        let decoded = decode::<Names>(br#"{"version":2,"items":["b"]}"#).unwrap();
+        assert_eq!(decoded.stored_version, 2);
+        assert_eq!(decoded.value.names, vec!["b".to_owned()]);
+    }
+
+    #[test]
+    fn encode_writes_current_version() {
+        let bytes = encode(&Names {
+            names: vec!["a".into()],
+        })
+        .unwrap();
+        let decoded = decode::<Names>(&bytes).unwrap();
+        assert_eq!(decoded.stored_version, 3);
+        assert!(!decoded.is_outdated());
+    }
+
+    #[test]
+    fn decode_rejects_newer_version() {
+        assert!(matches!(
+            decode::<Names>(br#"{"version":4,"names":[]}"#),
+            Err(MigrationError::UnsupportedVersion { version: 4, .. })
+        ));
+    }
+}
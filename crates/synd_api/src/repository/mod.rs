@@ -1,15 +1,34 @@
+use std::path::PathBuf;
+
 mod subscription;
 use ::kvsd::KvsdError;
-pub use subscription::SubscriptionRepository;
+pub use subscription::{RepositoryResult, SubscriptionRepository};
+
+mod archive;
+pub(crate) use archive::merge_entries;
+pub use archive::EntryArchiveRepository;
+
+mod user;
+pub use user::UserRepository;
 
 pub mod kvsd;
 pub mod memory;
+pub mod migration;
+pub mod sqlite;
 pub mod types;
 
+/// Backend of the repositories with the settings required to connect
+pub enum RepositoryConfig {
+    Kvsd(kvsd::KvsdConfig),
+    Sqlite { path: PathBuf },
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum RepositoryError {
     #[error("internal error: {0}")]
     Internal(#[from] anyhow::Error),
+    #[error(transparent)]
+    Migration(#[from] migration::MigrationError),
 }
 
 impl RepositoryError {
@@ -23,3 +42,9 @@ impl From<KvsdError> for RepositoryError {
         RepositoryError::Internal(value.into())
     }
 }
+
+impl From<sqlx::Error> for RepositoryError {
+    fn from(value: sqlx::Error) -> Self {
+        RepositoryError::Internal(value.into())
+    }
+}
@@ -0,0 +1,856 @@
+use std::{collections::HashSet, path::Path, str::FromStr};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+    FromRow, SqlitePool,
+};
+use synd_feed::types::Time;
+
+use crate::{
+    principal::Scope,
+    repository::{
+        self,
+        archive::merge_entries,
+        subscription::RepositoryResult,
+        types::{AccessToken, ArchivedEntry, DeletionToken, Identity, Subscription, UserRecord},
+        EntryArchiveRepository, RepositoryError, SubscriptionRepository, UserRepository,
+    },
+};
+
+/// Repository backed by a single SQLite database file
+pub struct SqliteRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteRepository {
+    /// Open the database file and apply pending migrations.
+    /// If the file does not exist, create it
+    pub async fn connect(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal);
+
+        Self::with_options(options, 8)
+            .await
+            .with_context(|| format!("sqlite: {}", path.display()))
+    }
+
+    /// Construct repository which is not persisted
+    pub async fn in_memory() -> anyhow::Result<Self> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")?;
+        // In-memory database is per connection
+        Self::with_options(options, 1).await
+    }
+
+    async fn with_options(
+        options: SqliteConnectOptions,
+        max_connections: u32,
+    ) -> anyhow::Result<Self> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(max_connections)
+            .connect_with(options)
+            .await?;
+
+        Self::migrate(&pool).await?;
+
+        Ok(Self { pool })
+    }
+
+    async fn migrate(pool: &SqlitePool) -> anyhow::Result<()> {
+        sqlx::migrate!("./migrations/sqlite")
+            .run(pool)
+            .await
+            .context("sqlite migration")?;
+
+        tracing::info!("Sqlite migration successfully completed");
+
+        Ok(())
+    }
+}
+
+#[derive(FromRow)]
+struct SubscriptionRow {
+    url: String,
+    title: Option<String>,
+    folder: Option<String>,
+    tags: String,
+    created_at: Option<Time>,
+    paused: bool,
+}
+
+impl TryFrom<SubscriptionRow> for Subscription {
+    type Error = RepositoryError;
+
+    fn try_from(row: SubscriptionRow) -> Result<Self, Self::Error> {
+        Ok(Subscription {
+            url: row.url,
+            title: row.title,
+            folder: row.folder,
+            tags: serde_json::from_str(&row.tags).map_err(RepositoryError::internal)?,
+            created_at: row.created_at,
+            paused: row.paused,
+        })
+    }
+}
+
+#[derive(FromRow)]
+struct UserRow {
+    id: String,
+    email: String,
+    created_at: Time,
+}
+
+#[derive(FromRow)]
+struct IdentityRow {
+    provider: String,
+    subject: String,
+}
+
+#[derive(FromRow)]
+struct AccessTokenRow {
+    id: String,
+    user_id: String,
+    name: String,
+    /// Comma separated scopes
+    scopes: String,
+    hash: String,
+    created_at: Time,
+    expires_at: Option<Time>,
+}
+
+#[derive(FromRow)]
+struct DeletionTokenRow {
+    user_id: String,
+    hash: String,
+    expires_at: Time,
+}
+
+impl From<DeletionTokenRow> for DeletionToken {
+    fn from(row: DeletionTokenRow) -> Self {
+        DeletionToken {
+            user_id: row.user_id,
+            hash: row.hash,
+            expires_at: row.expires_at,
+        }
+    }
+}
+
+impl TryFrom<AccessTokenRow> for AccessToken {
+    type Error = RepositoryError;
+
+    fn try_from(row: AccessTokenRow) -> Result<Self, Self::Error> {
+        let scopes = row
+            .scopes
+            .split(',')
+            .filter(|scope| !scope.is_empty())
+            .map(Scope::from_str)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| RepositoryError::internal(anyhow::anyhow!(err)))?;
+
+        Ok(AccessToken {
+            id: row.id,
+            user_id: row.user_id,
+            name: row.name,
+            scopes,
+            hash: row.hash,
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+        })
+    }
+}
+
+#[derive(FromRow)]
+struct ArchivedEntryRow {
+    feed_url: String,
+    entry_id: String,
+    title: Option<String>,
+    summary: Option<String>,
+    content: Option<String>,
+    website_url: Option<String>,
+    published: Option<Time>,
+    updated: Option<Time>,
+    archived_at: Time,
+}
+
+impl From<ArchivedEntryRow> for ArchivedEntry {
+    fn from(row: ArchivedEntryRow) -> Self {
+        ArchivedEntry {
+            feed_url: row.feed_url,
+            entry_id: row.entry_id,
+            title: row.title,
+            summary: row.summary,
+            content: row.content,
+            website_url: row.website_url,
+            published: row.published,
+            updated: row.updated,
+            archived_at: row.archived_at,
+        }
+    }
+}
+
+#[async_trait]
+impl SubscriptionRepository for SqliteRepository {
+    #[tracing::instrument(name = "repo::put_feed_subscription", skip_all)]
+    async fn put_feed_subscription(
+        &self,
+        feed: repository::types::FeedSubscription,
+    ) -> RepositoryResult<()> {
+        sqlx::query(
+            "INSERT INTO subscriptions (user_id, url, created_at) VALUES (?, ?, ?)
+             ON CONFLICT (user_id, url) DO NOTHING",
+        )
+        .bind(&feed.user_id)
+        .bind(&feed.url)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repo::delete_feed_subscription", skip_all)]
+    async fn delete_feed_subscription(
+        &self,
+        feed: repository::types::FeedSubscription,
+    ) -> RepositoryResult<()> {
+        sqlx::query("DELETE FROM subscriptions WHERE user_id = ? AND url = ?")
+            .bind(&feed.user_id)
+            .bind(&feed.url)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repo::put_feed_subscriptions", skip_all)]
+    async fn put_feed_subscriptions(
+        &self,
+        user_id: &str,
+        urls: Vec<String>,
+    ) -> RepositoryResult<()> {
+        let mut tx = self.pool.begin().await?;
+        let now = Utc::now();
+
+        for url in &urls {
+            sqlx::query(
+                "INSERT INTO subscriptions (user_id, url, created_at) VALUES (?, ?, ?)
+                 ON CONFLICT (user_id, url) DO NOTHING",
+            )
+            .bind(user_id)
+            .bind(url)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repo::delete_feed_subscriptions", skip_all)]
+    async fn delete_feed_subscriptions(
+        &self,
+        user_id: &str,
+        urls: Vec<String>,
+    ) -> RepositoryResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for url in &urls {
+            sqlx::query("DELETE FROM subscriptions WHERE user_id = ? AND url = ?")
+                .bind(user_id)
+                .bind(url)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repo::fetch_subscriptions", skip_all)]
+    async fn fetch_subscriptions(&self, user_id: &str) -> RepositoryResult<Vec<Subscription>> {
+        // Newest subscription first
+        sqlx::query_as::<_, SubscriptionRow>(
+            "SELECT url, title, folder, tags, created_at, paused FROM subscriptions
+             WHERE user_id = ? ORDER BY rowid DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(Subscription::try_from)
+        .collect()
+    }
+
+    #[tracing::instrument(name = "repo::update_subscription", skip_all)]
+    async fn update_subscription(
+        &self,
+        user_id: &str,
+        url: &str,
+        update: repository::types::SubscriptionUpdate,
+    ) -> RepositoryResult<Option<Subscription>> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(row) = sqlx::query_as::<_, SubscriptionRow>(
+            "SELECT url, title, folder, tags, created_at, paused FROM subscriptions
+             WHERE user_id = ? AND url = ?",
+        )
+        .bind(user_id)
+        .bind(url)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut subscription = Subscription::try_from(row)?;
+        update.apply(&mut subscription);
+
+        sqlx::query(
+            "UPDATE subscriptions SET title = ?, folder = ?, tags = ?, paused = ?
+             WHERE user_id = ? AND url = ?",
+        )
+        .bind(&subscription.title)
+        .bind(&subscription.folder)
+        .bind(serde_json::to_string(&subscription.tags).map_err(RepositoryError::internal)?)
+        .bind(subscription.paused)
+        .bind(user_id)
+        .bind(url)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(subscription))
+    }
+
+    async fn ping(&self) -> RepositoryResult<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    fn component_id(&self) -> String {
+        "sqlite".into()
+    }
+}
+
+#[async_trait]
+impl EntryArchiveRepository for SqliteRepository {
+    #[tracing::instrument(name = "repo::fetch_archived_entries", skip_all)]
+    async fn fetch_archived_entries(&self, feed_url: &str) -> RepositoryResult<Vec<ArchivedEntry>> {
+        Ok(sqlx::query_as::<_, ArchivedEntryRow>(
+            "SELECT feed_url, entry_id, title, summary, content, website_url, published, updated,
+                    archived_at
+             FROM archived_entries WHERE feed_url = ? ORDER BY rowid",
+        )
+        .bind(feed_url)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+    }
+
+    #[tracing::instrument(name = "repo::archive_entries", skip_all)]
+    async fn archive_entries(
+        &self,
+        feed_url: &str,
+        entries: Vec<ArchivedEntry>,
+        retain_since: Time,
+    ) -> RepositoryResult<Vec<ArchivedEntry>> {
+        let mut tx = self.pool.begin().await?;
+
+        let mut archived = sqlx::query_as::<_, ArchivedEntryRow>(
+            "SELECT feed_url, entry_id, title, summary, content, website_url, published, updated,
+                    archived_at
+             FROM archived_entries WHERE feed_url = ? ORDER BY rowid",
+        )
+        .bind(feed_url)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(ArchivedEntry::from)
+        .collect::<Vec<_>>();
+
+        if !merge_entries(&mut archived, entries, retain_since) {
+            return Ok(archived);
+        }
+
+        sqlx::query("DELETE FROM archived_entries WHERE feed_url = ?")
+            .bind(feed_url)
+            .execute(&mut *tx)
+            .await?;
+
+        for entry in &archived {
+            sqlx::query(
+                "INSERT INTO archived_entries
+                 (feed_url, entry_id, title, summary, content, website_url, published, updated,
+                  archived_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&entry.feed_url)
+            .bind(&entry.entry_id)
+            .bind(&entry.title)
+            .bind(&entry.summary)
+            .bind(&entry.content)
+            .bind(&entry.website_url)
+            .bind(entry.published)
+            .bind(entry.updated)
+            .bind(entry.archived_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(archived)
+    }
+}
+
+#[async_trait]
+impl UserRepository for SqliteRepository {
+    #[tracing::instrument(name = "repo::fetch_user_ids", skip_all)]
+    async fn fetch_user_ids(&self) -> RepositoryResult<Vec<String>> {
+        Ok(sqlx::query_scalar(
+            "SELECT user_id FROM subscriptions UNION SELECT id FROM users ORDER BY 1",
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    #[tracing::instrument(name = "repo::fetch_user", skip_all)]
+    async fn fetch_user(&self, user_id: &str) -> RepositoryResult<Option<UserRecord>> {
+        let Some(UserRow {
+            id,
+            email,
+            created_at,
+        }) = sqlx::query_as::<_, UserRow>("SELECT id, email, created_at FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let identities = sqlx::query_as::<_, IdentityRow>(
+            "SELECT provider, subject FROM identities WHERE user_id = ? ORDER BY rowid",
+        )
+        .bind(&id)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| Identity::new(row.provider, row.subject))
+        .collect();
+
+        Ok(Some(UserRecord {
+            id,
+            email,
+            identities,
+            created_at,
+        }))
+    }
+
+    #[tracing::instrument(name = "repo::find_user_by_identity", skip_all)]
+    async fn find_user_by_identity(
+        &self,
+        identity: &Identity,
+    ) -> RepositoryResult<Option<UserRecord>> {
+        let user_id: Option<String> =
+            sqlx::query_scalar("SELECT user_id FROM identities WHERE provider = ? AND subject = ?")
+                .bind(&identity.provider)
+                .bind(&identity.subject)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        match user_id {
+            Some(user_id) => self.fetch_user(&user_id).await,
+            None => Ok(None),
+        }
+    }
+
+    #[tracing::instrument(name = "repo::find_user_by_email", skip_all)]
+    async fn find_user_by_email(&self, email: &str) -> RepositoryResult<Option<UserRecord>> {
+        let user_id: Option<String> =
+            sqlx::query_scalar("SELECT id FROM users WHERE email = ? ORDER BY rowid LIMIT 1")
+                .bind(email)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        match user_id {
+            Some(user_id) => self.fetch_user(&user_id).await,
+            None => Ok(None),
+        }
+    }
+
+    #[tracing::instrument(name = "repo::put_user", skip_all)]
+    async fn put_user(&self, user: &UserRecord) -> RepositoryResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO users (id, email, created_at) VALUES (?, ?, ?)
+             ON CONFLICT (id) DO UPDATE SET email = excluded.email",
+        )
+        .bind(&user.id)
+        .bind(&user.email)
+        .bind(user.created_at)
+        .execute(&mut *tx)
+        .await?;
+
+        for identity in &user.identities {
+            sqlx::query(
+                "INSERT INTO identities (provider, subject, user_id) VALUES (?, ?, ?)
+                 ON CONFLICT (provider, subject) DO UPDATE SET user_id = excluded.user_id",
+            )
+            .bind(&identity.provider)
+            .bind(&identity.subject)
+            .bind(&user.id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repo::put_access_token", skip_all)]
+    async fn put_access_token(&self, token: &AccessToken) -> RepositoryResult<()> {
+        let scopes = token
+            .scopes
+            .iter()
+            .map(|scope| scope.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        sqlx::query(
+            "INSERT INTO access_tokens (id, user_id, name, scopes, hash, created_at, expires_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&token.id)
+        .bind(&token.user_id)
+        .bind(&token.name)
+        .bind(scopes)
+        .bind(&token.hash)
+        .bind(token.created_at)
+        .bind(token.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repo::fetch_access_tokens", skip_all)]
+    async fn fetch_access_tokens(&self, user_id: &str) -> RepositoryResult<Vec<AccessToken>> {
+        sqlx::query_as::<_, AccessTokenRow>(
+            "SELECT id, user_id, name, scopes, hash, created_at, expires_at
+             FROM access_tokens WHERE user_id = ? ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(AccessToken::try_from)
+        .collect()
+    }
+
+    #[tracing::instrument(name = "repo::find_access_token", skip_all)]
+    async fn find_access_token(&self, hash: &str) -> RepositoryResult<Option<AccessToken>> {
+        sqlx::query_as::<_, AccessTokenRow>(
+            "SELECT id, user_id, name, scopes, hash, created_at, expires_at
+             FROM access_tokens WHERE hash = ?",
+        )
+        .bind(hash)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(AccessToken::try_from)
+        .transpose()
+    }
+
+    #[tracing::instrument(name = "repo::delete_access_token", skip_all)]
+    async fn delete_access_token(&self, user_id: &str, token_id: &str) -> RepositoryResult<bool> {
+        let result = sqlx::query("DELETE FROM access_tokens WHERE user_id = ? AND id = ?")
+            .bind(user_id)
+            .bind(token_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(name = "repo::put_deletion_token", skip_all)]
+    async fn put_deletion_token(&self, token: &DeletionToken) -> RepositoryResult<()> {
+        sqlx::query(
+            "INSERT INTO deletion_tokens (user_id, hash, expires_at) VALUES (?, ?, ?)
+             ON CONFLICT (user_id)
+             DO UPDATE SET hash = excluded.hash, expires_at = excluded.expires_at",
+        )
+        .bind(&token.user_id)
+        .bind(&token.hash)
+        .bind(token.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repo::fetch_deletion_token", skip_all)]
+    async fn fetch_deletion_token(&self, user_id: &str) -> RepositoryResult<Option<DeletionToken>> {
+        Ok(sqlx::query_as::<_, DeletionTokenRow>(
+            "SELECT user_id, hash, expires_at FROM deletion_tokens WHERE user_id = ?",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(Into::into))
+    }
+
+    #[tracing::instrument(name = "repo::delete_deletion_token", skip_all)]
+    async fn delete_deletion_token(&self, user_id: &str) -> RepositoryResult<()> {
+        sqlx::query("DELETE FROM deletion_tokens WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repo::fetch_disabled_user_ids", skip_all)]
+    async fn fetch_disabled_user_ids(&self) -> RepositoryResult<HashSet<String>> {
+        Ok(
+            sqlx::query_scalar::<_, String>("SELECT user_id FROM disabled_users")
+                .fetch_all(&self.pool)
+                .await?
+                .into_iter()
+                .collect(),
+        )
+    }
+
+    #[tracing::instrument(name = "repo::set_user_disabled", skip_all)]
+    async fn set_user_disabled(&self, user_id: &str, disabled: bool) -> RepositoryResult<()> {
+        if disabled {
+            sqlx::query(
+                "INSERT INTO disabled_users (user_id, disabled_at) VALUES (?, ?)
+                 ON CONFLICT (user_id) DO NOTHING",
+            )
+            .bind(user_id)
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+        } else {
+            sqlx::query("DELETE FROM disabled_users WHERE user_id = ?")
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repo::is_user_disabled", skip_all)]
+    async fn is_user_disabled(&self, user_id: &str) -> RepositoryResult<bool> {
+        let disabled: Option<String> =
+            sqlx::query_scalar("SELECT user_id FROM disabled_users WHERE user_id = ?")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(disabled.is_some())
+    }
+
+    #[tracing::instrument(name = "repo::delete_user", skip_all)]
+    async fn delete_user(&self, user_id: &str) -> RepositoryResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for query in [
+            "DELETE FROM subscriptions WHERE user_id = ?",
+            "DELETE FROM identities WHERE user_id = ?",
+            "DELETE FROM access_tokens WHERE user_id = ?",
+            "DELETE FROM deletion_tokens WHERE user_id = ?",
+            "DELETE FROM users WHERE id = ?",
+        ] {
+            sqlx::query(query).bind(user_id).execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repository::{
+        subscription,
+        types::{
+            AccessToken, DeletionToken, FeedSubscription, Identity, SubscriptionUpdate, UserRecord,
+        },
+    };
+
+    use super::*;
+
+    fn subscription(url: &str) -> FeedSubscription {
+        FeedSubscription {
+            user_id: "user".into(),
+            url: url.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn subscription_lifecycle() {
+        let repo = SqliteRepository::in_memory().await.unwrap();
+
+        repo.put_feed_subscription(subscription("https://a.example.com/feed.xml"))
+            .await
+            .unwrap();
+        repo.put_feed_subscription(subscription("https://b.example.com/feed.xml"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repo.fetch_subscribed_feed_urls("user").await.unwrap(),
+            vec![
+                "https://b.example.com/feed.xml".to_owned(),
+                "https://a.example.com/feed.xml".to_owned(),
+            ]
+        );
+
+        let updated = repo
+            .update_subscription(
+                "user",
+                "https://a.example.com/feed.xml",
+                SubscriptionUpdate {
+                    folder: Some(Some("rust".into())),
+                    tags: Some(vec!["b".into(), "a".into()]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.folder.as_deref(), Some("rust"));
+        assert_eq!(updated.tags, vec!["a".to_owned(), "b".to_owned()]);
+
+        repo.delete_feed_subscription(subscription("https://a.example.com/feed.xml"))
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.fetch_subscribed_feed_urls("user").await.unwrap(),
+            vec!["https://b.example.com/feed.xml".to_owned()]
+        );
+        assert!(repo
+            .update_subscription(
+                "user",
+                "https://a.example.com/feed.xml",
+                SubscriptionUpdate::default()
+            )
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn concurrent_subscribe() {
+        let repo = SqliteRepository::in_memory().await.unwrap();
+        subscription::tests::concurrent_subscribe(std::sync::Arc::new(repo)).await;
+    }
+
+    #[tokio::test]
+    async fn disable_user() {
+        let repo = SqliteRepository::in_memory().await.unwrap();
+        repo.put_feed_subscription(subscription("https://a.example.com/feed.xml"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repo.fetch_user_ids().await.unwrap(),
+            vec!["user".to_owned()]
+        );
+        assert!(!repo.is_user_disabled("user").await.unwrap());
+
+        repo.set_user_disabled("user", true).await.unwrap();
+        repo.set_user_disabled("user", true).await.unwrap();
+        assert!(repo.is_user_disabled("user").await.unwrap());
+
+        repo.set_user_disabled("user", false).await.unwrap();
+        assert!(repo.fetch_disabled_user_ids().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn link_identities() {
+        let repo = SqliteRepository::in_memory().await.unwrap();
+        let github = Identity::new("github", "1");
+        let google = Identity::new("google", "2");
+        let mut user = UserRecord {
+            id: "user".into(),
+            email: "user@example.com".into(),
+            identities: vec![github.clone()],
+            created_at: Utc::now(),
+        };
+        repo.put_user(&user).await.unwrap();
+        assert!(repo.find_user_by_identity(&google).await.unwrap().is_none());
+
+        user.identities.push(google.clone());
+        repo.put_user(&user).await.unwrap();
+
+        let found = repo.find_user_by_identity(&google).await.unwrap().unwrap();
+        assert_eq!(found.id, "user");
+        assert_eq!(found.identities.len(), 2);
+        let found = repo.find_user_by_email("user@example.com").await.unwrap();
+        assert_eq!(found.map(|user| user.id).as_deref(), Some("user"));
+
+        repo.delete_user("user").await.unwrap();
+        assert!(repo.find_user_by_identity(&github).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn access_tokens() {
+        let repo = SqliteRepository::in_memory().await.unwrap();
+        let token = AccessToken {
+            id: "token".into(),
+            user_id: "user".into(),
+            name: "ci".into(),
+            scopes: vec![Scope::Read, Scope::Write],
+            hash: AccessToken::hash("synd_pat_secret"),
+            created_at: Utc::now(),
+            expires_at: None,
+        };
+        repo.put_access_token(&token).await.unwrap();
+
+        let found = repo
+            .find_access_token(&AccessToken::hash("synd_pat_secret"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.scopes, token.scopes);
+        assert_eq!(repo.fetch_access_tokens("user").await.unwrap().len(), 1);
+
+        assert!(!repo.delete_access_token("other", "token").await.unwrap());
+        assert!(repo.delete_access_token("user", "token").await.unwrap());
+        assert!(repo.find_access_token(&token.hash).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn deletion_tokens() {
+        let repo = SqliteRepository::in_memory().await.unwrap();
+        let expires_at = Utc::now();
+        repo.put_deletion_token(&DeletionToken::new("user", "first", expires_at))
+            .await
+            .unwrap();
+        repo.put_deletion_token(&DeletionToken::new("user", "second", expires_at))
+            .await
+            .unwrap();
+
+        let token = repo.fetch_deletion_token("user").await.unwrap().unwrap();
+        assert_eq!(token.hash, AccessToken::hash("second"));
+
+        repo.delete_user("user").await.unwrap();
+        assert!(repo.fetch_deletion_token("user").await.unwrap().is_none());
+    }
+}
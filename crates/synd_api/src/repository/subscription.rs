@@ -10,17 +10,79 @@ pub type RepositoryResult<T> = std::result::Result<T, RepositoryError>;
 
 #[async_trait]
 pub trait SubscriptionRepository: Send + Sync {
+    /// Subscribe the feed. Subscribing an already subscribed feed does nothing
     async fn put_feed_subscription(
         &self,
         feed: repository::types::FeedSubscription,
     ) -> RepositoryResult<()>;
 
+    /// Unsubscribe the feed. Unsubscribing a feed which is not subscribed does nothing
     async fn delete_feed_subscription(
         &self,
         feed: repository::types::FeedSubscription,
     ) -> RepositoryResult<()>;
 
-    async fn fetch_subscribed_feed_urls(&self, _user_id: &str) -> RepositoryResult<Vec<String>>;
+    /// Subscribe multiple feeds in one write if the backend supports it
+    async fn put_feed_subscriptions(
+        &self,
+        user_id: &str,
+        urls: Vec<String>,
+    ) -> RepositoryResult<()> {
+        for url in urls {
+            self.put_feed_subscription(repository::types::FeedSubscription {
+                user_id: user_id.to_owned(),
+                url,
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Unsubscribe multiple feeds in one write if the backend supports it
+    async fn delete_feed_subscriptions(
+        &self,
+        user_id: &str,
+        urls: Vec<String>,
+    ) -> RepositoryResult<()> {
+        for url in urls {
+            self.delete_feed_subscription(repository::types::FeedSubscription {
+                user_id: user_id.to_owned(),
+                url,
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn fetch_subscribed_feed_urls(&self, user_id: &str) -> RepositoryResult<Vec<String>> {
+        Ok(self
+            .fetch_subscriptions(user_id)
+            .await?
+            .into_iter()
+            .map(|subscription| subscription.url)
+            .collect())
+    }
+
+    /// Fetch user's subscriptions with metadata
+    async fn fetch_subscriptions(
+        &self,
+        user_id: &str,
+    ) -> RepositoryResult<Vec<repository::types::Subscription>>;
+
+    /// Update subscription metadata.
+    /// Return None if given url is not subscribed
+    async fn update_subscription(
+        &self,
+        user_id: &str,
+        url: &str,
+        update: repository::types::SubscriptionUpdate,
+    ) -> RepositoryResult<Option<repository::types::Subscription>>;
+
+    /// Check that the backend is reachable
+    async fn ping(&self) -> RepositoryResult<()>;
+
+    /// Identify the backend in health checks such as `kvsd:<host>:<port>`
+    fn component_id(&self) -> String;
 }
 
 #[async_trait]
@@ -32,17 +94,113 @@ where
         &self,
         feed: repository::types::FeedSubscription,
     ) -> RepositoryResult<()> {
-        self.put_feed_subscription(feed).await
+        T::put_feed_subscription(self, feed).await
     }
 
     async fn delete_feed_subscription(
         &self,
         feed: repository::types::FeedSubscription,
     ) -> RepositoryResult<()> {
-        self.delete_feed_subscription(feed).await
+        T::delete_feed_subscription(self, feed).await
+    }
+
+    async fn put_feed_subscriptions(
+        &self,
+        user_id: &str,
+        urls: Vec<String>,
+    ) -> RepositoryResult<()> {
+        T::put_feed_subscriptions(self, user_id, urls).await
+    }
+
+    async fn delete_feed_subscriptions(
+        &self,
+        user_id: &str,
+        urls: Vec<String>,
+    ) -> RepositoryResult<()> {
+        T::delete_feed_subscriptions(self, user_id, urls).await
     }
 
     async fn fetch_subscribed_feed_urls(&self, user_id: &str) -> RepositoryResult<Vec<String>> {
-        self.fetch_subscribed_feed_urls(user_id).await
+        T::fetch_subscribed_feed_urls(self, user_id).await
+    }
+
+    async fn fetch_subscriptions(
+        &self,
+        user_id: &str,
+    ) -> RepositoryResult<Vec<repository::types::Subscription>> {
+        T::fetch_subscriptions(self, user_id).await
+    }
+
+    async fn update_subscription(
+        &self,
+        user_id: &str,
+        url: &str,
+        update: repository::types::SubscriptionUpdate,
+    ) -> RepositoryResult<Option<repository::types::Subscription>> {
+        T::update_subscription(self, user_id, url, update).await
+    }
+
+    async fn ping(&self) -> RepositoryResult<()> {
+        T::ping(self).await
+    }
+
+    fn component_id(&self) -> String {
+        T::component_id(self)
+    }
+}
+
+/// Tests shared among the implementations
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::repository::types::FeedSubscription;
+
+    use super::*;
+
+    /// Concurrent writes to the same user's subscriptions through one repository are not lost,
+    /// and subscribing or unsubscribing the same feed twice is idempotent
+    pub(crate) async fn concurrent_subscribe(repo: Arc<dyn SubscriptionRepository>) {
+        let urls = [
+            "https://a.example.com/feed.xml",
+            "https://b.example.com/feed.xml",
+        ];
+        let subscription = |url: &str| FeedSubscription {
+            user_id: "user".into(),
+            url: url.into(),
+        };
+
+        let tasks = urls
+            .iter()
+            .cycle()
+            .take(10)
+            .map(|url| {
+                let (repo, feed) = (Arc::clone(&repo), subscription(url));
+                tokio::spawn(async move { repo.put_feed_subscription(feed).await })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        // Repositories could have other subscriptions such as test data
+        let count = |subscribed: &[String], url: &str| {
+            subscribed
+                .iter()
+                .filter(|subscribed| *subscribed == url)
+                .count()
+        };
+
+        let subscribed = repo.fetch_subscribed_feed_urls("user").await.unwrap();
+        assert_eq!(count(&subscribed, urls[0]), 1);
+        assert_eq!(count(&subscribed, urls[1]), 1);
+
+        repo.delete_feed_subscription(subscription(urls[0]))
+            .await
+            .unwrap();
+        repo.delete_feed_subscription(subscription(urls[0]))
+            .await
+            .unwrap();
+        let subscribed = repo.fetch_subscribed_feed_urls("user").await.unwrap();
+        assert_eq!(count(&subscribed, urls[0]), 0);
+        assert_eq!(count(&subscribed, urls[1]), 1);
     }
 }
@@ -1,4 +1,8 @@
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use synd_feed::types::{self, Time};
+
+use crate::principal::Scope;
 
 #[derive(Debug, Clone)]
 pub struct Feed {
@@ -11,3 +15,223 @@ pub struct FeedSubscription {
     pub user_id: String,
     pub url: String,
 }
+
+/// User's subscription to a feed with user defined metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Subscription {
+    pub url: String,
+    /// User defined title which overrides the feed title
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// None if the subscription was created before metadata was recorded
+    #[serde(default)]
+    pub created_at: Option<Time>,
+    /// Paused subscriptions are excluded from entries
+    #[serde(default)]
+    pub paused: bool,
+}
+
+impl Subscription {
+    pub fn new(url: impl Into<String>, created_at: Time) -> Self {
+        Self {
+            created_at: Some(created_at),
+            ..Self::from_url(url)
+        }
+    }
+
+    /// Construct subscription which has no metadata
+    pub fn from_url(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            title: None,
+            folder: None,
+            tags: Vec::new(),
+            created_at: None,
+            paused: false,
+        }
+    }
+}
+
+/// Partial update of `Subscription`.
+/// `None` means the field is left unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionUpdate {
+    pub title: Option<Option<String>>,
+    pub folder: Option<Option<String>>,
+    pub tags: Option<Vec<String>>,
+    pub paused: Option<bool>,
+}
+
+impl SubscriptionUpdate {
+    pub fn apply(self, subscription: &mut Subscription) {
+        let SubscriptionUpdate {
+            title,
+            folder,
+            tags,
+            paused,
+        } = self;
+
+        if let Some(title) = title {
+            subscription.title = title;
+        }
+        if let Some(folder) = folder {
+            subscription.folder = folder;
+        }
+        if let Some(mut tags) = tags {
+            tags.sort_unstable();
+            tags.dedup();
+            subscription.tags = tags;
+        }
+        if let Some(paused) = paused {
+            subscription.paused = paused;
+        }
+    }
+}
+
+/// Conditions to narrow down subscriptions
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionFilter {
+    pub folder: Option<String>,
+    pub tag: Option<String>,
+}
+
+impl SubscriptionFilter {
+    pub fn matches(&self, subscription: &Subscription) -> bool {
+        let folder = self
+            .folder
+            .as_ref()
+            .map_or(true, |folder| subscription.folder.as_ref() == Some(folder));
+        let tag = self
+            .tag
+            .as_ref()
+            .map_or(true, |tag| subscription.tags.contains(tag));
+
+        folder && tag
+    }
+}
+
+/// Entry persisted in the archive so that it can be served
+/// even after the upstream feed stops listing it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedEntry {
+    pub feed_url: types::FeedUrl,
+    pub entry_id: String,
+    pub title: Option<String>,
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    pub website_url: Option<String>,
+    pub published: Option<Time>,
+    pub updated: Option<Time>,
+    /// The time at which the entry was first archived
+    pub archived_at: Time,
+}
+
+impl ArchivedEntry {
+    pub fn new(
+        feed_url: impl Into<types::FeedUrl>,
+        meta: &types::FeedMeta,
+        entry: &types::Entry,
+        archived_at: Time,
+    ) -> Self {
+        Self {
+            feed_url: feed_url.into(),
+            entry_id: entry.id_ref().to_string(),
+            title: entry.title().map(ToOwned::to_owned),
+            summary: entry.summary().map(ToOwned::to_owned),
+            content: entry.content().map(ToOwned::to_owned),
+            website_url: entry.website_url(meta.r#type()).map(ToOwned::to_owned),
+            published: entry.published(),
+            updated: entry.updated(),
+            archived_at,
+        }
+    }
+
+    /// Published time if exists, otherwise the time at which the entry was archived
+    pub fn retention_time(&self) -> Time {
+        self.published.or(self.updated).unwrap_or(self.archived_at)
+    }
+}
+
+/// Identity of the user at an authentication provider
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Identity {
+    /// Authentication provider such as `github` or `google`
+    pub provider: String,
+    /// Identifier of the user which is stable within the provider
+    pub subject: String,
+}
+
+impl Identity {
+    pub fn new(provider: impl Into<String>, subject: impl Into<String>) -> Self {
+        Self {
+            provider: provider.into(),
+            subject: subject.into(),
+        }
+    }
+}
+
+/// Stored user. The id is assigned on the first login and never derived again
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserRecord {
+    pub id: String,
+    pub email: String,
+    /// Provider identities linked to the user
+    pub identities: Vec<Identity>,
+    pub created_at: Time,
+}
+
+/// Personal access token. Only the hash of the token is stored
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessToken {
+    pub id: String,
+    pub user_id: String,
+    /// Name given by the user to identify the token
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    /// Hex encoded sha256 of the token
+    pub hash: String,
+    pub created_at: Time,
+    pub expires_at: Option<Time>,
+}
+
+impl AccessToken {
+    /// Prefix of issued tokens to make them easy to identify
+    pub const PREFIX: &'static str = "synd_pat_";
+
+    /// Return the hash which is stored instead of the token
+    pub fn hash(token: &str) -> String {
+        format!("{:x}", Sha256::digest(token.as_bytes()))
+    }
+
+    pub fn is_expired(&self, now: Time) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// Token to confirm account deletion. Only the hash of the token is stored
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeletionToken {
+    pub user_id: String,
+    /// Hex encoded sha256 of the token
+    pub hash: String,
+    pub expires_at: Time,
+}
+
+impl DeletionToken {
+    pub fn new(user_id: impl Into<String>, token: &str, expires_at: Time) -> Self {
+        Self {
+            user_id: user_id.into(),
+            hash: AccessToken::hash(token),
+            expires_at,
+        }
+    }
+
+    pub fn is_expired(&self, now: Time) -> bool {
+        self.expires_at <= now
+    }
+}
@@ -0,0 +1,135 @@
+use std::{collections::HashSet, sync::Arc};
+
+use async_trait::async_trait;
+
+use crate::repository::{
+    subscription::RepositoryResult,
+    types::{AccessToken, DeletionToken, Identity, UserRecord},
+};
+
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    /// Fetch ids of the users who have records or subscribed feeds
+    async fn fetch_user_ids(&self) -> RepositoryResult<Vec<String>>;
+
+    /// Fetch the user with its identities
+    async fn fetch_user(&self, user_id: &str) -> RepositoryResult<Option<UserRecord>>;
+
+    /// Find the user to which the identity is linked
+    async fn find_user_by_identity(
+        &self,
+        identity: &Identity,
+    ) -> RepositoryResult<Option<UserRecord>>;
+
+    /// Find the user who has the email
+    async fn find_user_by_email(&self, email: &str) -> RepositoryResult<Option<UserRecord>>;
+
+    /// Create or update the user. Identities of the user are linked to it
+    async fn put_user(&self, user: &UserRecord) -> RepositoryResult<()>;
+
+    async fn put_access_token(&self, token: &AccessToken) -> RepositoryResult<()>;
+
+    /// Fetch access tokens issued for the user
+    async fn fetch_access_tokens(&self, user_id: &str) -> RepositoryResult<Vec<AccessToken>>;
+
+    /// Find the access token by the hash of the token
+    async fn find_access_token(&self, hash: &str) -> RepositoryResult<Option<AccessToken>>;
+
+    /// Delete the access token. Return false if the user does not have the token
+    async fn delete_access_token(&self, user_id: &str, token_id: &str) -> RepositoryResult<bool>;
+
+    /// Store the token to confirm account deletion. Previously stored token of the user is replaced
+    async fn put_deletion_token(&self, token: &DeletionToken) -> RepositoryResult<()>;
+
+    async fn fetch_deletion_token(&self, user_id: &str) -> RepositoryResult<Option<DeletionToken>>;
+
+    async fn delete_deletion_token(&self, user_id: &str) -> RepositoryResult<()>;
+
+    /// Fetch ids of the users who are disabled
+    async fn fetch_disabled_user_ids(&self) -> RepositoryResult<HashSet<String>>;
+
+    /// Disable or enable the user. Disabled users are not authorized to any operations
+    async fn set_user_disabled(&self, user_id: &str, disabled: bool) -> RepositoryResult<()>;
+
+    async fn is_user_disabled(&self, user_id: &str) -> RepositoryResult<bool> {
+        Ok(self.fetch_disabled_user_ids().await?.contains(user_id))
+    }
+
+    /// Delete the user record, subscriptions, access tokens, deletion token
+    /// and any other data stored for the user.
+    /// Disabled status is retained so that disabled users cannot evade it by deleting account
+    async fn delete_user(&self, user_id: &str) -> RepositoryResult<()>;
+}
+
+#[async_trait]
+impl<T> UserRepository for Arc<T>
+where
+    T: UserRepository,
+{
+    async fn fetch_user_ids(&self) -> RepositoryResult<Vec<String>> {
+        T::fetch_user_ids(self).await
+    }
+
+    async fn fetch_user(&self, user_id: &str) -> RepositoryResult<Option<UserRecord>> {
+        T::fetch_user(self, user_id).await
+    }
+
+    async fn find_user_by_identity(
+        &self,
+        identity: &Identity,
+    ) -> RepositoryResult<Option<UserRecord>> {
+        T::find_user_by_identity(self, identity).await
+    }
+
+    async fn find_user_by_email(&self, email: &str) -> RepositoryResult<Option<UserRecord>> {
+        T::find_user_by_email(self, email).await
+    }
+
+    async fn put_user(&self, user: &UserRecord) -> RepositoryResult<()> {
+        T::put_user(self, user).await
+    }
+
+    async fn put_access_token(&self, token: &AccessToken) -> RepositoryResult<()> {
+        T::put_access_token(self, token).await
+    }
+
+    async fn fetch_access_tokens(&self, user_id: &str) -> RepositoryResult<Vec<AccessToken>> {
+        T::fetch_access_tokens(self, user_id).await
+    }
+
+    async fn find_access_token(&self, hash: &str) -> RepositoryResult<Option<AccessToken>> {
+        T::find_access_token(self, hash).await
+    }
+
+    async fn delete_access_token(&self, user_id: &str, token_id: &str) -> RepositoryResult<bool> {
+        T::delete_access_token(self, user_id, token_id).await
+    }
+
+    async fn put_deletion_token(&self, token: &DeletionToken) -> RepositoryResult<()> {
+        T::put_deletion_token(self, token).await
+    }
+
+    async fn fetch_deletion_token(&self, user_id: &str) -> RepositoryResult<Option<DeletionToken>> {
+        T::fetch_deletion_token(self, user_id).await
+    }
+
+    async fn delete_deletion_token(&self, user_id: &str) -> RepositoryResult<()> {
+        T::delete_deletion_token(self, user_id).await
+    }
+
+    async fn fetch_disabled_user_ids(&self) -> RepositoryResult<HashSet<String>> {
+        T::fetch_disabled_user_ids(self).await
+    }
+
+    async fn set_user_disabled(&self, user_id: &str, disabled: bool) -> RepositoryResult<()> {
+        T::set_user_disabled(self, user_id, disabled).await
+    }
+
+    async fn is_user_disabled(&self, user_id: &str) -> RepositoryResult<bool> {
+        T::is_user_disabled(self, user_id).await
+    }
+
+    async fn delete_user(&self, user_id: &str) -> RepositoryResult<()> {
+        T::delete_user(self, user_id).await
+    }
+}
@@ -0,0 +1,103 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use synd_feed::{
+    feed::parser::{FetchFeed, FetchFeedResult},
+    types::Feed,
+};
+use synd_o11y::metric;
+use tokio::sync::mpsc;
+
+use crate::search::SearchIndex;
+
+/// Handle to request indexing of fetched feeds in the background
+#[derive(Clone)]
+pub struct Indexer {
+    tx: mpsc::Sender<(String, Feed)>,
+}
+
+impl Indexer {
+    const QUEUE_SIZE: usize = 1024;
+    const MAX_BATCH_SIZE: usize = 64;
+
+    /// Spawn indexing task
+    pub fn spawn(index: Arc<SearchIndex>) -> Self {
+        let (tx, rx) = mpsc::channel(Self::QUEUE_SIZE);
+
+        tokio::spawn(Self::run(index, rx));
+
+        Self { tx }
+    }
+
+    /// Enqueue the feed requested with the url to be indexed.
+    /// If the queue is full, the feed is discarded because it will be indexed on next fetch
+    pub fn enqueue(&self, url: String, feed: Feed) {
+        if let Err(err) = self.tx.try_send((url, feed)) {
+            tracing::warn!("Failed to enqueue feed to index: {err}");
+        }
+    }
+
+    async fn run(index: Arc<SearchIndex>, mut rx: mpsc::Receiver<(String, Feed)>) {
+        while let Some(feed) = rx.recv().await {
+            let mut batch = vec![feed];
+            while batch.len() < Self::MAX_BATCH_SIZE {
+                match rx.try_recv() {
+                    Ok(feed) => batch.push(feed),
+                    Err(_) => break,
+                }
+            }
+
+            let index = Arc::clone(&index);
+            let result = tokio::task::spawn_blocking(move || {
+                index.index_feeds(batch.iter().map(|(url, feed)| (url.as_str(), feed)))
+            })
+            .await;
+
+            match result {
+                Ok(Ok(entries)) => {
+                    tracing::debug!(entries, "Indexed entries");
+                    metric!(monotonic_counter.search.indexed_entries = entries);
+                }
+                Ok(Err(err)) => tracing::error!("Failed to index feeds: {err}"),
+                Err(err) => tracing::error!("Index task panicked: {err}"),
+            }
+
+            // Avoid committing too frequently
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+}
+
+/// `FetchFeed` which index fetched feeds
+#[derive(Clone)]
+pub struct IndexFeedService<S> {
+    service: S,
+    indexer: Indexer,
+}
+
+impl<S> IndexFeedService<S> {
+    pub fn new(service: S, indexer: Indexer) -> Self {
+        Self { service, indexer }
+    }
+}
+
+#[async_trait]
+impl<S> FetchFeed for IndexFeedService<S>
+where
+    S: FetchFeed,
+{
+    async fn fetch_feed(&self, url: String) -> FetchFeedResult<Feed> {
+        let feed = self.service.fetch_feed(url.clone()).await?;
+        self.indexer.enqueue(url, feed.clone());
+        Ok(feed)
+    }
+
+    async fn fetch_feeds_parallel(&self, urls: &[String]) -> FetchFeedResult<Vec<Feed>> {
+        let feeds = self.service.fetch_feeds_parallel(urls).await?;
+        // Feeds are returned in the order of urls
+        for (url, feed) in urls.iter().zip(&feeds) {
+            self.indexer.enqueue(url.clone(), feed.clone());
+        }
+        Ok(feeds)
+    }
+}
@@ -0,0 +1,358 @@
+use std::{path::Path, sync::Mutex};
+
+use synd_feed::types::{self, Time};
+use tantivy::{
+    collector::TopDocs,
+    directory::MmapDirectory,
+    doc,
+    query::{BooleanQuery, Occur, Query, QueryParser, QueryParserError, TermQuery},
+    schema::{Field, IndexRecordOption, Schema, STORED, STRING, TEXT},
+    DateTime, Document, Index, IndexReader, IndexWriter, ReloadPolicy, SnippetGenerator,
+    TantivyError, Term,
+};
+use thiserror::Error;
+
+mod indexer;
+pub use indexer::{IndexFeedService, Indexer};
+
+#[derive(Error, Debug)]
+pub enum SearchError {
+    #[error("invalid search query: {0}")]
+    InvalidQuery(#[from] QueryParserError),
+    #[error("search index error: {0}")]
+    Index(#[from] TantivyError),
+    #[error("failed to open search index: {0}")]
+    Open(#[from] tantivy::directory::error::OpenDirectoryError),
+    #[error("search task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+pub type SearchResult<T> = std::result::Result<T, SearchError>;
+
+/// Entry which matched the search query
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub score: f32,
+    pub feed_url: types::FeedUrl,
+    pub entry_id: String,
+    pub title: Option<String>,
+    pub summary: Option<String>,
+    pub website_url: Option<String>,
+    pub published: Option<Time>,
+    /// Html fragment of the title which emphasizes the matched terms with `<b>`
+    pub title_snippet: String,
+    /// Html fragment of the summary or content which emphasizes the matched terms with `<b>`
+    pub content_snippet: String,
+}
+
+#[derive(Clone, Copy)]
+struct Fields {
+    feed_url: Field,
+    entry_id: Field,
+    title: Field,
+    summary: Field,
+    content: Field,
+    website_url: Field,
+    published: Field,
+}
+
+impl Fields {
+    fn schema() -> (Schema, Self) {
+        let mut builder = Schema::builder();
+        let fields = Fields {
+            feed_url: builder.add_text_field("feed_url", STRING | STORED),
+            entry_id: builder.add_text_field("entry_id", STORED),
+            title: builder.add_text_field("title", TEXT | STORED),
+            summary: builder.add_text_field("summary", TEXT | STORED),
+            content: builder.add_text_field("content", TEXT | STORED),
+            website_url: builder.add_text_field("website_url", STORED),
+            published: builder.add_date_field("published", STORED),
+        };
+        (builder.build(), fields)
+    }
+}
+
+/// Full-text index of entries.
+/// Documents are shared among users and narrowed down by user's subscriptions on search
+pub struct SearchIndex {
+    index: Index,
+    reader: IndexReader,
+    writer: Mutex<IndexWriter>,
+    fields: Fields,
+}
+
+impl SearchIndex {
+    const WRITER_MEMORY_BUDGET_BYTES: usize = 50 * 1024 * 1024;
+
+    /// Open the index in given directory. If it does not exist, create new one
+    pub fn open(dir: impl AsRef<Path>) -> SearchResult<Self> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)
+            .map_err(|err| TantivyError::SystemError(format!("{}: {err}", dir.display())))?;
+
+        let (schema, fields) = Fields::schema();
+        let index = Index::open_or_create(MmapDirectory::open(dir)?, schema)?;
+
+        Self::with_index(index, fields)
+    }
+
+    /// Construct index which is not persisted
+    pub fn in_memory() -> SearchResult<Self> {
+        let (schema, fields) = Fields::schema();
+        let index = Index::create_in_ram(schema);
+
+        Self::with_index(index, fields)
+    }
+
+    fn with_index(index: Index, fields: Fields) -> SearchResult<Self> {
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::OnCommit)
+            .try_into()?;
+        let writer = index.writer(Self::WRITER_MEMORY_BUDGET_BYTES)?;
+
+        Ok(Self {
+            index,
+            reader,
+            writer: Mutex::new(writer),
+            fields,
+        })
+    }
+
+    /// Replace the documents of given feeds with their current entries.
+    /// Documents are keyed by the url with which the feed was requested
+    /// so that they match the urls of subscriptions.
+    /// This is a blocking operation
+    pub fn index_feeds<'a>(
+        &self,
+        feeds: impl IntoIterator<Item = (&'a str, &'a types::Feed)>,
+    ) -> SearchResult<usize> {
+        let f = self.fields;
+        let mut writer = self.writer.lock().expect("search index writer poisoned");
+        let mut indexed = 0;
+
+        for (feed_url, feed) in feeds {
+            let meta = feed.meta();
+            writer.delete_term(Term::from_field_text(f.feed_url, feed_url));
+
+            for entry in feed.entries() {
+                let entry_id = entry.id_ref().to_string();
+                let mut document = doc!(
+                    f.feed_url => feed_url,
+                    f.entry_id => entry_id,
+                    f.title => entry.title().unwrap_or_default(),
+                    f.summary => entry.summary().unwrap_or_default(),
+                    f.content => entry.content().unwrap_or_default(),
+                );
+                if let Some(website_url) = entry.website_url(meta.r#type()) {
+                    document.add_text(f.website_url, website_url);
+                }
+                if let Some(published) = entry.published().or(entry.updated()) {
+                    document.add_date(
+                        f.published,
+                        DateTime::from_timestamp_secs(published.timestamp()),
+                    );
+                }
+                writer.add_document(document)?;
+                indexed += 1;
+            }
+        }
+
+        writer.commit()?;
+
+        Ok(indexed)
+    }
+
+    /// Search entries of given feeds ordered by relevance.
+    /// This is a blocking operation
+    pub fn search(
+        &self,
+        query: &str,
+        feed_urls: &[String],
+        offset: usize,
+        limit: usize,
+    ) -> SearchResult<Vec<SearchHit>> {
+        if feed_urls.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let f = self.fields;
+        let parser = QueryParser::for_index(&self.index, vec![f.title, f.summary, f.content]);
+        let text_query = parser.parse_query(query)?;
+
+        let feeds_query = BooleanQuery::new(
+            feed_urls
+                .iter()
+                .map(|url| {
+                    let term = Term::from_field_text(f.feed_url, url);
+                    let query: Box<dyn Query> =
+                        Box::new(TermQuery::new(term, IndexRecordOption::Basic));
+                    (Occur::Should, query)
+                })
+                .collect(),
+        );
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, text_query.box_clone()),
+            (Occur::Must, Box::new(feeds_query)),
+        ]);
+
+        let searcher = self.reader.searcher();
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit).and_offset(offset))?;
+
+        let title_snippet = SnippetGenerator::create(&searcher, &*text_query, f.title)?;
+        let mut summary_snippet = SnippetGenerator::create(&searcher, &*text_query, f.summary)?;
+        summary_snippet.set_max_num_chars(200);
+        let mut content_snippet = SnippetGenerator::create(&searcher, &*text_query, f.content)?;
+        content_snippet.set_max_num_chars(200);
+
+        let mut hits = Vec::with_capacity(top_docs.len());
+        for (score, address) in top_docs {
+            let document: Document = searcher.doc(address)?;
+            let text = |field: Field| {
+                document
+                    .get_first(field)
+                    .and_then(|value| value.as_text())
+                    .filter(|text| !text.is_empty())
+                    .map(ToOwned::to_owned)
+            };
+
+            // Prefer summary snippet, fallback to content if the summary does not match
+            let snippet = summary_snippet.snippet_from_doc(&document);
+            let snippet = if snippet.highlighted().is_empty() {
+                content_snippet.snippet_from_doc(&document)
+            } else {
+                snippet
+            };
+
+            hits.push(SearchHit {
+                score,
+                feed_url: text(f.feed_url).unwrap_or_default(),
+                entry_id: text(f.entry_id).unwrap_or_default(),
+                title: text(f.title),
+                summary: text(f.summary),
+                website_url: text(f.website_url),
+                published: document
+                    .get_first(f.published)
+                    .and_then(|value| value.as_date())
+                    .and_then(|date| {
+                        chrono::DateTime::from_timestamp(date.into_timestamp_secs(), 0)
+                    }),
+                title_snippet: title_snippet.snippet_from_doc(&document).to_html(),
+                content_snippet: snippet.to_html(),
+            });
+        }
+
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use synd_feed::feed::parser::FeedService;
+
+    use super::*;
+
+    fn feed(url: &str, titles: &[&str]) -> types::Feed {
+        let items = titles
+            .iter()
+            .enumerate()
+            .map(|(i, title)| {
+                format!(
+                    "<item><guid>{url}/{i}</guid><title>{title}</title>\
+                     <description>about {title}</description></item>"
+                )
+            })
+            .collect::<String>();
+        let rss = format!(
+            "<?xml version=\"1.0\"?><rss version=\"2.0\"><channel>\
+             <title>feed</title><link>https://example.com</link>{items}</channel></rss>"
+        );
+        FeedService::new("synd-test", 1024 * 1024)
+            .parse(url, rss.as_bytes())
+            .unwrap()
+    }
+
+    fn open() -> (tempfile::TempDir, SearchIndex) {
+        let dir = tempfile::tempdir().unwrap();
+        let index = SearchIndex::open(dir.path().join("index")).unwrap();
+        (dir, index)
+    }
+
+    #[test]
+    fn index_and_search_by_requested_url() {
+        let (_dir, index) = open();
+        let url = "https://a.example.com/feed.xml";
+        let feed = feed(url, &["Rust release", "Go release", "Cooking"]);
+
+        assert_eq!(index.index_feeds([(url, &feed)]).unwrap(), 3);
+
+        let hits = index.search("release", &[url.into()], 0, 10).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|hit| hit.feed_url == url));
+        assert!(hits.iter().all(|hit| hit.title_snippet.contains("<b>")));
+
+        // Entries of feeds which are not given are excluded
+        let other = "https://b.example.com/feed.xml".to_owned();
+        assert!(index.search("release", &[other], 0, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reindex_replaces_documents() {
+        let (_dir, index) = open();
+        let url = "https://a.example.com/feed.xml";
+
+        index
+            .index_feeds([(url, &feed(url, &["Rust release"]))])
+            .unwrap();
+        index
+            .index_feeds([(url, &feed(url, &["Rust conference"]))])
+            .unwrap();
+
+        assert!(index
+            .search("release", &[url.into()], 0, 10)
+            .unwrap()
+            .is_empty());
+        assert_eq!(index.search("rust", &[url.into()], 0, 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn query_syntax() {
+        let (_dir, index) = open();
+        let url = "https://a.example.com/feed.xml";
+        index
+            .index_feeds([(url, &feed(url, &["Rust release", "Rust conference"]))])
+            .unwrap();
+
+        let hits = index
+            .search("rust AND -conference", &[url.into()], 0, 10)
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].title.as_deref(), Some("Rust release"));
+
+        assert!(matches!(
+            index.search("title:(rust", &[url.into()], 0, 10),
+            Err(SearchError::InvalidQuery(_))
+        ));
+    }
+
+    #[test]
+    fn paginate_by_offset() {
+        let (_dir, index) = open();
+        let url = "https://a.example.com/feed.xml";
+        let titles = (0..5).map(|i| format!("rust {i}")).collect::<Vec<_>>();
+        let titles = titles.iter().map(String::as_str).collect::<Vec<_>>();
+        index.index_feeds([(url, &feed(url, &titles))]).unwrap();
+
+        let urls = [url.to_owned()];
+        let mut ids = Vec::new();
+        for offset in [0, 2, 4] {
+            let hits = index.search("rust", &urls, offset, 2).unwrap();
+            ids.extend(hits.into_iter().map(|hit| hit.entry_id));
+        }
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 5);
+
+        assert!(index.search("rust", &urls, 5, 2).unwrap().is_empty());
+    }
+}
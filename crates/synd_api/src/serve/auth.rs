@@ -1,25 +1,51 @@
-use std::time::Duration;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 
+use chrono::Utc;
 use futures_util::future::BoxFuture;
 use moka::future::Cache;
-use synd_auth::jwt::google::JwtService as GoogleJwtService;
+use synd_auth::jwt::{google::JwtService as GoogleJwtService, oidc::OidcJwtService};
 use tracing::warn;
 
 use crate::{
     client::github::GithubClient,
     principal::{Principal, User},
+    repository::{
+        types::{AccessToken, Identity, UserRecord},
+        UserRepository,
+    },
     serve::layer::authenticate::Authenticate,
 };
 
+/// User who is authorized to administrative operations.
+/// Admins are identified by stable ids instead of emails which the providers could reassign
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Admin {
+    /// Identity at the authentication provider
+    Identity(Identity),
+    /// User id of synd-api
+    User(String),
+}
+
 #[derive(Clone)]
 pub struct Authenticator {
     github: GithubClient,
     google: GoogleJwtService,
+    /// Configured OpenID Connect providers keyed by the authorization scheme
+    oidc: Arc<HashMap<String, OidcJwtService>>,
     cache: Cache<String, Principal>,
+    users: Arc<dyn UserRepository>,
+    /// Identities of the users who are authenticated as admin
+    admin_identities: Arc<HashSet<Identity>>,
+    /// User ids of the users who are authenticated as admin
+    admin_user_ids: Arc<HashSet<String>>,
 }
 
 impl Authenticator {
-    pub fn new() -> anyhow::Result<Self> {
+    pub fn new(users: Arc<dyn UserRepository>) -> anyhow::Result<Self> {
         let cache = Cache::builder()
             .max_capacity(1024 * 1024)
             .time_to_live(Duration::from_secs(60 * 60))
@@ -28,7 +54,127 @@ impl Authenticator {
         Ok(Self {
             github: GithubClient::new()?,
             google: GoogleJwtService::default(),
+            oidc: Arc::new(HashMap::new()),
             cache,
+            users,
+            admin_identities: Arc::new(HashSet::new()),
+            admin_user_ids: Arc::new(HashSet::new()),
+        })
+    }
+
+    #[must_use]
+    pub fn with_admins(self, admins: impl IntoIterator<Item = Admin>) -> Self {
+        let mut admin_identities = HashSet::new();
+        let mut admin_user_ids = HashSet::new();
+        for admin in admins {
+            match admin {
+                Admin::Identity(identity) => admin_identities.insert(identity),
+                Admin::User(user_id) => admin_user_ids.insert(user_id),
+            };
+        }
+        Self {
+            admin_identities: Arc::new(admin_identities),
+            admin_user_ids: Arc::new(admin_user_ids),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn with_oidc_providers(
+        self,
+        providers: impl IntoIterator<Item = (String, OidcJwtService)>,
+    ) -> Self {
+        Self {
+            oidc: Arc::new(providers.into_iter().collect()),
+            ..self
+        }
+    }
+
+    /// Resolve the user to which the provider identity belongs.
+    /// If the identity is not linked yet, it is linked to the user who has the same email
+    /// or a new user is created. New users adopt the id derived from the email
+    /// so that the subscriptions stored before user records were introduced are retained.
+    /// Since the email decides the user, identities whose email is not verified are rejected
+    async fn resolve(
+        &self,
+        identity: Identity,
+        email: String,
+        email_verified: bool,
+    ) -> Result<Principal, ()> {
+        if !email_verified {
+            warn!(provider = identity.provider, "Email is not verified");
+            return Err(());
+        }
+        let is_admin = self.admin_identities.contains(&identity);
+        let record = match self.resolve_record(identity, &email).await {
+            Ok(record) => record,
+            Err(err) => {
+                warn!("Failed to resolve user: {err}");
+                return Err(());
+            }
+        };
+        let is_admin = is_admin || self.admin_user_ids.contains(&record.id);
+        let user = User::new(record.id, email);
+
+        if is_admin {
+            Ok(Principal::Admin(user))
+        } else {
+            Ok(Principal::User(user))
+        }
+    }
+
+    async fn resolve_record(&self, identity: Identity, email: &str) -> anyhow::Result<UserRecord> {
+        if let Some(mut record) = self.users.find_user_by_identity(&identity).await? {
+            if record.email != email {
+                record.email = email.to_owned();
+                self.users.put_user(&record).await?;
+            }
+            return Ok(record);
+        }
+
+        let record = match self.users.find_user_by_email(email).await? {
+            Some(mut record) => {
+                tracing::info!(
+                    user_id = record.id,
+                    provider = identity.provider,
+                    "Link identity"
+                );
+                record.identities.push(identity);
+                record
+            }
+            None => UserRecord {
+                id: User::legacy_id(email),
+                email: email.to_owned(),
+                identities: vec![identity],
+                created_at: Utc::now(),
+            },
+        };
+        self.users.put_user(&record).await?;
+
+        Ok(record)
+    }
+
+    /// Access tokens are not cached so that revoked tokens are rejected immediately
+    async fn authenticate_access_token(&self, token: &str) -> anyhow::Result<Principal> {
+        let access_token = self
+            .users
+            .find_access_token(&AccessToken::hash(token))
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("unknown access token"))?;
+
+        if access_token.is_expired(Utc::now()) {
+            anyhow::bail!("access token expired");
+        }
+
+        let record = self
+            .users
+            .fetch_user(&access_token.user_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("access token user not found"))?;
+
+        Ok(Principal::Token {
+            user: User::new(record.id, record.email),
+            scopes: access_token.scopes,
         })
     }
 
@@ -53,8 +199,10 @@ impl Authenticator {
                 }
 
                 match self.github.authenticate(access_token).await {
-                    Ok(email) => {
-                        let principal = Principal::User(User::from_email(email));
+                    Ok(user) => {
+                        let identity = Identity::new("github", user.id.to_string());
+                        // Github only allows verified emails to be the public email
+                        let principal = self.resolve(identity, user.email, true).await?;
 
                         self.cache.insert(token.to_owned(), principal.clone()).await;
 
@@ -74,11 +222,10 @@ impl Authenticator {
 
                 match self.google.decode_id_token(id_token).await {
                     Ok(claims) => {
-                        if !claims.email_verified {
-                            warn!("Google jwt claims email is not verified");
-                            return Err(());
-                        }
-                        let principal = Principal::User(User::from_email(claims.email));
+                        let identity = Identity::new("google", claims.sub);
+                        let principal = self
+                            .resolve(identity, claims.email, claims.email_verified)
+                            .await?;
 
                         self.cache
                             .insert(id_token.to_owned(), principal.clone())
@@ -87,15 +234,42 @@ impl Authenticator {
                         Ok(principal)
                     }
                     Err(err) => {
-                        // Id a lot of intentional invalid id tokens are sent
-                        // google's api limit will be exceeded.
-                        // To prevent this, it is necessary to cache the currently valid kids
-                        // and discard jwt headers with other kids.
+                        // Unknown kids are rejected by the key cache without calling google
                         warn!("Failed to authenticate google: {err}");
                         Err(())
                     }
                 }
             }
+            (Some("token"), Some(access_token)) => self
+                .authenticate_access_token(access_token)
+                .await
+                .map_err(|err| warn!("Failed to authenticate access token: {err}")),
+            (Some(scheme), Some(id_token)) => {
+                let Some(oidc) = self.oidc.get(scheme) else {
+                    return Err(());
+                };
+                if let Some(principal) = self.cache.get(token).await {
+                    tracing::info!("Principal cache hit");
+                    return Ok(principal);
+                }
+
+                match oidc.decode_id_token(id_token).await {
+                    Ok(claims) => {
+                        let identity = Identity::new(scheme, claims.subject);
+                        let principal = self
+                            .resolve(identity, claims.email, claims.email_verified)
+                            .await?;
+
+                        self.cache.insert(token.to_owned(), principal.clone()).await;
+
+                        Ok(principal)
+                    }
+                    Err(err) => {
+                        warn!(provider = scheme, "Failed to authenticate oidc: {err}");
+                        Err(())
+                    }
+                }
+            }
             _ => Err(()),
         }
     }
@@ -114,3 +288,73 @@ impl Authenticate for Authenticator {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::repository::memory::MemoryRepository;
+
+    use super::*;
+
+    fn authenticator(admins: Vec<Admin>) -> Authenticator {
+        Authenticator::new(Arc::new(MemoryRepository::new()))
+            .unwrap()
+            .with_admins(admins)
+    }
+
+    #[tokio::test]
+    async fn admin_is_identified_by_identity() {
+        let admin = Identity::new("github", "1");
+        let authenticator = authenticator(vec![Admin::Identity(admin.clone())]);
+
+        let principal = authenticator
+            .resolve(admin, "admin@example.com".into(), true)
+            .await
+            .unwrap();
+        assert!(principal.is_admin());
+
+        // Same email at another provider is not admin
+        let principal = authenticator
+            .resolve(Identity::new("oidc", "1"), "admin@example.com".into(), true)
+            .await
+            .unwrap();
+        assert!(!principal.is_admin());
+    }
+
+    #[tokio::test]
+    async fn admin_is_identified_by_user_id() {
+        let identity = Identity::new("github", "1");
+        let user_id = User::legacy_id("admin@example.com");
+        let authenticator = authenticator(vec![Admin::User(user_id)]);
+
+        let principal = authenticator
+            .resolve(identity, "admin@example.com".into(), true)
+            .await
+            .unwrap();
+        assert!(principal.is_admin());
+    }
+
+    #[tokio::test]
+    async fn unverified_email_is_not_linked() {
+        let users = Arc::new(MemoryRepository::new());
+        let authenticator = Authenticator::new(users.clone()).unwrap();
+        let github = Identity::new("github", "1");
+        let user = authenticator
+            .resolve(github.clone(), "user@example.com".into(), true)
+            .await
+            .unwrap();
+
+        let oidc = Identity::new("oidc", "attacker");
+        assert!(authenticator
+            .resolve(oidc.clone(), "user@example.com".into(), false)
+            .await
+            .is_err());
+
+        assert!(users.find_user_by_identity(&oidc).await.unwrap().is_none());
+        let record = users
+            .fetch_user(user.user_id().unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.identities, vec![github]);
+    }
+}
@@ -0,0 +1,240 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::{ConnectInfo, Request},
+    http::{header::FORWARDED, HeaderMap},
+};
+use tower::{Layer, Service};
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// Address of the client which sent the request.
+/// If the request is forwarded by trusted proxies, the address is taken from the forwarded headers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+/// Ip address range in CIDR notation. A single address is also accepted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpNetwork {
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr = IpAddr::from_str(addr.trim())
+            .map_err(|err| format!("invalid ip address `{addr}`: {err}"))?
+            .to_canonical();
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= max)
+                .ok_or_else(|| format!("invalid prefix length `{prefix}`"))?,
+            None => max,
+        };
+
+        Ok(Self { addr, prefix })
+    }
+}
+
+/// Proxies whose forwarded headers are trusted
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Vec<IpNetwork>);
+
+impl TrustedProxies {
+    pub fn new(networks: impl IntoIterator<Item = IpNetwork>) -> Self {
+        Self(networks.into_iter().collect())
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        self.0.iter().any(|network| network.contains(ip))
+    }
+
+    /// Resolve the client address. `peer` is None if the connection is accepted on
+    /// unix domain socket, in which case the peer is always regarded as a trusted proxy
+    fn resolve(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        if let Some(peer) = peer {
+            if !self.contains(peer) {
+                return Some(peer);
+            }
+        }
+
+        // Walk from the nearest hop and return the first address which is not a trusted proxy
+        let chain = forwarded_for(headers);
+        chain
+            .iter()
+            .rev()
+            .find(|ip| !self.contains(**ip))
+            .or(chain.first())
+            .copied()
+            .or(peer)
+    }
+}
+
+/// Addresses in `Forwarded` header or `X-Forwarded-For` if `Forwarded` is not present.
+/// The first one is the originating client
+fn forwarded_for(headers: &HeaderMap) -> Vec<IpAddr> {
+    let values = |name| {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+    };
+
+    if headers.contains_key(FORWARDED) {
+        // https://datatracker.ietf.org/doc/html/rfc7239#section-4
+        values(FORWARDED.as_str())
+            .filter_map(|element| {
+                element.split(';').find_map(|pair| {
+                    let (key, value) = pair.trim().split_once('=')?;
+                    key.eq_ignore_ascii_case("for").then(|| parse_node(value))?
+                })
+            })
+            .collect()
+    } else {
+        values(X_FORWARDED_FOR).filter_map(parse_node).collect()
+    }
+}
+
+/// Parse node such as `192.0.2.43`, `"192.0.2.43:47011"` or `"[2001:db8:cafe::17]:4711"`
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if let Some(v6) = node.strip_prefix('[') {
+        return v6.split_once(']')?.0.parse().ok();
+    }
+    node.parse()
+        .ok()
+        .or_else(|| node.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+#[derive(Clone)]
+pub struct ClientIpLayer {
+    trusted_proxies: Arc<TrustedProxies>,
+}
+
+impl ClientIpLayer {
+    pub fn new(trusted_proxies: TrustedProxies) -> Self {
+        Self {
+            trusted_proxies: Arc::new(trusted_proxies),
+        }
+    }
+}
+
+impl<S> Layer<S> for ClientIpLayer {
+    type Service = ClientIpService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ClientIpService {
+            inner,
+            trusted_proxies: Arc::clone(&self.trusted_proxies),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ClientIpService<S> {
+    inner: S,
+    trusted_proxies: Arc<TrustedProxies>,
+}
+
+impl<S> Service<Request> for ClientIpService<S>
+where
+    S: Service<Request>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
+        let peer = req
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+
+        if let Some(ip) = self.trusted_proxies.resolve(peer, req.headers()) {
+            req.extensions_mut().insert(ClientIp(ip));
+        }
+
+        self.inner.call(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn resolve_client_ip() {
+        let trusted = TrustedProxies::new(["10.0.0.0/8".parse().unwrap()]);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            X_FORWARDED_FOR,
+            HeaderValue::from_static("203.0.113.1, 198.51.100.7, 10.0.0.2"),
+        );
+
+        // Untrusted peer can not spoof the address
+        assert_eq!(
+            trusted.resolve(Some(ip("192.0.2.1")), &headers),
+            Some(ip("192.0.2.1"))
+        );
+        // The nearest untrusted hop is the client
+        assert_eq!(
+            trusted.resolve(Some(ip("10.0.0.1")), &headers),
+            Some(ip("198.51.100.7"))
+        );
+
+        headers.insert(
+            FORWARDED,
+            HeaderValue::from_static(r#"for="[2001:db8:cafe::17]:4711";proto=https"#),
+        );
+        assert_eq!(
+            trusted.resolve(None, &headers),
+            Some(ip("2001:db8:cafe::17"))
+        );
+    }
+}
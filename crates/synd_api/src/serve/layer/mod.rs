@@ -1,3 +1,5 @@
 pub mod authenticate;
+pub mod client_ip;
+pub mod rate_limit;
 pub mod request_metrics;
 pub mod trace;
@@ -0,0 +1,280 @@
+use std::{
+    convert::Infallible,
+    future::{ready, Ready},
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::Request,
+    http::{header::RETRY_AFTER, StatusCode},
+    response::{IntoResponse, Response},
+};
+use futures_util::future::Either;
+use moka::sync::Cache;
+use synd_o11y::metric;
+use tower::{Layer, Service};
+
+use crate::{principal::Principal, serve::layer::client_ip::ClientIp};
+
+/// Token bucket parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Number of tokens refilled per minute. 0 disables the limit
+    pub requests_per_minute: u32,
+    /// Maximum number of tokens in the bucket
+    pub burst: u32,
+}
+
+impl RateLimit {
+    pub fn is_enabled(&self) -> bool {
+        self.requests_per_minute > 0
+    }
+
+    fn tokens_per_sec(&self) -> f64 {
+        f64::from(self.requests_per_minute) / 60.
+    }
+}
+
+/// What the requests are limited by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitKey {
+    /// Address of the client. Applied before authentication
+    ClientIp,
+    /// Authenticated principal. Applied after authentication
+    Principal,
+}
+
+impl RateLimitKey {
+    fn as_str(self) -> &'static str {
+        match self {
+            RateLimitKey::ClientIp => "client_ip",
+            RateLimitKey::Principal => "principal",
+        }
+    }
+
+    fn extract(self, req: &Request) -> Option<String> {
+        match self {
+            RateLimitKey::ClientIp => req
+                .extensions()
+                .get::<ClientIp>()
+                .map(|ClientIp(ip)| ip.to_string()),
+            RateLimitKey::Principal => req
+                .extensions()
+                .get::<Principal>()
+                .and_then(Principal::user_id)
+                .map(ToOwned::to_owned),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    fn full(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(limit.burst),
+            updated_at: now,
+        }
+    }
+
+    /// Take a token or return the duration until a token is available
+    fn acquire(&mut self, limit: RateLimit, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.tokens_per_sec()).min(f64::from(limit.burst));
+        self.updated_at = now;
+
+        if self.tokens >= 1. {
+            self.tokens -= 1.;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1. - self.tokens) / limit.tokens_per_sec(),
+            ))
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    key: RateLimitKey,
+    limit: RateLimit,
+    buckets: Cache<String, Arc<Mutex<Bucket>>>,
+}
+
+impl RateLimitLayer {
+    pub fn new(key: RateLimitKey, limit: RateLimit) -> Self {
+        // Idle buckets are refilled anyway, so they can be dropped
+        let idle = Duration::from_secs_f64(f64::from(limit.burst.max(1)) / limit.tokens_per_sec());
+        let buckets = Cache::builder()
+            .max_capacity(100_000)
+            .time_to_idle(idle.max(Duration::from_secs(60)))
+            .build();
+
+        Self {
+            key,
+            limit,
+            buckets,
+        }
+    }
+
+    fn acquire(&self, key: String) -> Result<(), Duration> {
+        let now = Instant::now();
+        let bucket = self
+            .buckets
+            .get_with(key, || Arc::new(Mutex::new(Bucket::full(self.limit, now))));
+        let mut bucket = bucket.lock().unwrap();
+        bucket.acquire(self.limit, now)
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    layer: RateLimitLayer,
+}
+
+impl<S> Service<Request> for RateLimitService<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible>,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Either<Ready<Result<Response, Infallible>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let Some(key) = self.layer.key.extract(&req) else {
+            return Either::Right(self.inner.call(req));
+        };
+
+        match self.layer.acquire(key) {
+            Ok(()) => Either::Right(self.inner.call(req)),
+            Err(retry_after) => {
+                metric!(
+                    monotonic_counter.http.server.rate_limited = 1,
+                    key = self.layer.key.as_str()
+                );
+                let retry_after = retry_after.as_secs_f64().ceil().max(1.).to_string();
+
+                Either::Left(ready(Ok((
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [(RETRY_AFTER, retry_after)],
+                )
+                    .into_response())))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use tower::{service_fn, ServiceExt};
+
+    use crate::principal::User;
+
+    use super::*;
+
+    fn service(
+        key: RateLimitKey,
+    ) -> impl Service<Request, Response = Response, Error = Infallible> {
+        let limit = RateLimit {
+            requests_per_minute: 1,
+            burst: 1,
+        };
+        RateLimitLayer::new(key, limit).layer(service_fn(|_: Request| async {
+            Ok::<_, Infallible>(StatusCode::OK.into_response())
+        }))
+    }
+
+    async fn send<S, T>(service: &mut S, extension: Option<T>) -> Response
+    where
+        S: Service<Request, Response = Response, Error = Infallible>,
+        T: Clone + Send + Sync + 'static,
+    {
+        let mut req = Request::new(Body::empty());
+        if let Some(extension) = extension {
+            req.extensions_mut().insert(extension);
+        }
+        service.ready().await.unwrap().call(req).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn limit_requests_per_client_ip() {
+        let mut service = service(RateLimitKey::ClientIp);
+        let ip = |ip: &str| Some(ClientIp(ip.parse().unwrap()));
+
+        assert_eq!(
+            send(&mut service, ip("192.0.2.1")).await.status(),
+            StatusCode::OK
+        );
+        let res = send(&mut service, ip("192.0.2.1")).await;
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(res.headers()[RETRY_AFTER], "60");
+
+        // Each client ip has its own bucket
+        assert_eq!(
+            send(&mut service, ip("192.0.2.2")).await.status(),
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn limit_requests_per_principal() {
+        let mut service = service(RateLimitKey::Principal);
+        let user = |id: &str| Some(Principal::User(User::new(id, "user@example.com")));
+
+        assert_eq!(send(&mut service, user("a")).await.status(), StatusCode::OK);
+        let res = send(&mut service, user("a")).await;
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(res.headers().contains_key(RETRY_AFTER));
+
+        // Users with the same email are limited separately
+        assert_eq!(send(&mut service, user("b")).await.status(), StatusCode::OK);
+        // Unauthenticated requests are left to the client ip limit
+        for _ in 0..2 {
+            assert_eq!(
+                send(&mut service, None::<Principal>).await.status(),
+                StatusCode::OK
+            );
+        }
+    }
+
+    #[test]
+    fn bucket_refill() {
+        let limit = RateLimit {
+            requests_per_minute: 60,
+            burst: 2,
+        };
+        let now = Instant::now();
+        let mut bucket = Bucket::full(limit, now);
+
+        assert!(bucket.acquire(limit, now).is_ok());
+        assert!(bucket.acquire(limit, now).is_ok());
+        assert_eq!(bucket.acquire(limit, now), Err(Duration::from_secs(1)));
+
+        let now = now + Duration::from_millis(1500);
+        assert!(bucket.acquire(limit, now).is_ok());
+        assert_eq!(bucket.acquire(limit, now), Err(Duration::from_millis(500)));
+    }
+}
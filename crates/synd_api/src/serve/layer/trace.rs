@@ -1,6 +1,8 @@
 use tower_http::trace::HttpMakeClassifier;
 use tracing::Level;
 
+use crate::serve::layer::client_ip::ClientIp;
+
 #[derive(Clone)]
 pub struct MakeSpan;
 
@@ -14,12 +16,18 @@ impl<B> tower_http::trace::MakeSpan<B> for MakeSpan {
             .get(synd_o11y::REQUEST_ID_KEY)
             .map_or("?".into(), |v| v.as_str());
 
+        let client_ip = request
+            .extensions()
+            .get::<ClientIp>()
+            .map_or_else(|| "?".to_owned(), |ClientIp(ip)| ip.to_string());
+
         let span = tracing::span!(
             Level::INFO,
             "http",
             method = %request.method(),
             uri = %request.uri(),
             %request_id,
+            %client_ip,
         );
 
         span.set_parent(cx);
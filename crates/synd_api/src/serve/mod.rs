@@ -1,5 +1,11 @@
-use std::{net::IpAddr, time::Duration};
+use std::{
+    net::{IpAddr, SocketAddr},
+    os::unix::fs::FileTypeExt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
+use anyhow::Context as _;
 use axum::{
     error_handling::HandleErrorLayer,
     http::{header::AUTHORIZATION, StatusCode},
@@ -7,9 +13,17 @@ use axum::{
     routing::{get, post},
     BoxError, Extension, Router,
 };
-use tokio::net::TcpListener;
+use axum_server::tls_rustls::RustlsConfig;
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::{conn::auto, graceful::GracefulShutdown},
+    service::TowerToHyperService,
+};
+use tokio::net::{TcpListener, UnixListener};
 use tokio_metrics::TaskMonitor;
-use tower::{limit::ConcurrencyLimitLayer, timeout::TimeoutLayer, ServiceBuilder};
+use tower::{
+    limit::ConcurrencyLimitLayer, timeout::TimeoutLayer, util::option_layer, ServiceBuilder,
+};
 use tower_http::{
     cors::CorsLayer, limit::RequestBodyLimitLayer, sensitive_headers::SetSensitiveHeadersLayer,
 };
@@ -17,43 +31,88 @@ use tracing::info;
 
 use crate::{
     dependency::Dependency,
-    gql::{self, SyndSchema},
-    serve::layer::{authenticate, request_metrics::RequestMetricsLayer, trace},
-    shutdown::Shutdown,
+    gql::{self, loader::MakeLoaders, SyndSchema},
+    serve::{
+        layer::{
+            authenticate,
+            client_ip::{ClientIpLayer, TrustedProxies},
+            rate_limit::{RateLimit, RateLimitKey, RateLimitLayer},
+            request_metrics::RequestMetricsLayer,
+            trace,
+        },
+        probe::Readiness,
+    },
+    shutdown::{Shutdown, GRACEFUL_SHUTDOWN_TIMEOUT},
 };
 
 pub mod auth;
 mod probe;
 
 pub mod layer;
+pub mod tls;
 
-pub struct BindOptions {
-    pub port: u16,
-    pub addr: IpAddr,
+pub enum BindOptions {
+    /// Serve https if tls is configured, otherwise plain http
+    Tcp {
+        addr: IpAddr,
+        port: u16,
+    },
+    Unix {
+        path: PathBuf,
+    },
 }
 
 pub struct ServeOptions {
     pub timeout: Duration,
     pub body_limit_bytes: usize,
     pub concurrency_limit: usize,
+    pub client_ip_rate_limit: RateLimit,
+    pub principal_rate_limit: RateLimit,
+    pub trusted_proxies: TrustedProxies,
 }
 
 #[derive(Clone)]
 pub struct Context {
     pub gql_monitor: TaskMonitor,
     pub schema: SyndSchema,
+    pub make_loaders: MakeLoaders,
 }
 
-/// Bind tcp listener and serve.
+/// Bind listener and serve.
 pub async fn listen_and_serve(
     dep: Dependency,
     bind: BindOptions,
     shutdown: Shutdown,
 ) -> anyhow::Result<()> {
-    info!(addr = %bind.addr, port = bind.port, "Listening...");
-    let listener = TcpListener::bind((bind.addr, bind.port)).await?;
+    match bind {
+        BindOptions::Tcp { addr, port } => {
+            info!(%addr, port, tls = dep.tls_config.is_some(), "Listening...");
+            let listener = TcpListener::bind((addr, port)).await?;
 
-    serve(listener, dep, shutdown).await
+            serve(listener, dep, shutdown).await
+        }
+        BindOptions::Unix { path } => {
+            remove_stale_socket(&path)?;
+            info!(path = %path.display(), "Listening...");
+            let listener = UnixListener::bind(&path)?;
+
+            let result = serve_unix(listener, dep, shutdown).await;
+            std::fs::remove_file(&path).ok();
+            result
+        }
+    }
+}
+
+/// Remove the socket left by the previous process.
+/// Other kinds of files are never removed
+fn remove_stale_socket(path: &Path) -> anyhow::Result<()> {
+    match path.metadata() {
+        Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path)
+            .with_context(|| format!("remove unix socket {}", path.display())),
+        Ok(_) => anyhow::bail!("{} exists and is not a socket", path.display()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err).with_context(|| format!("inspect {}", path.display())),
+    }
 }
 
 /// Start api server
@@ -62,6 +121,82 @@ pub async fn serve(
     dep: Dependency,
     shutdown: Shutdown,
 ) -> anyhow::Result<()> {
+    let (service, tls_config) = router(dep);
+    let service = service.into_make_service_with_connect_info::<SocketAddr>();
+
+    match tls_config {
+        Some(tls_config) => {
+            axum_server::from_tcp_rustls(listener.into_std()?, tls_config)
+                .handle(shutdown.into_handle())
+                .serve(service)
+                .await?;
+        }
+        None => {
+            axum_server::from_tcp(listener.into_std()?)
+                .handle(shutdown.into_handle())
+                .serve(service)
+                .await?;
+        }
+    }
+
+    tracing::info!("Shutdown complete");
+
+    Ok(())
+}
+
+/// Start api server on unix domain socket.
+/// Client addresses are resolved only from the forwarded headers
+pub async fn serve_unix(
+    listener: UnixListener,
+    dep: Dependency,
+    shutdown: Shutdown,
+) -> anyhow::Result<()> {
+    let (service, _) = router(dep);
+    let builder = auto::Builder::new(TokioExecutor::new());
+    let graceful = GracefulShutdown::new();
+    let shutdown = shutdown.notify();
+    tokio::pin!(shutdown);
+
+    loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(err) => {
+                    tracing::warn!("Failed to accept unix socket connection: {err}");
+                    continue;
+                }
+            },
+            () = &mut shutdown => break,
+        };
+
+        let service = TowerToHyperService::new(service.clone());
+        let conn = graceful.watch(
+            builder
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .into_owned(),
+        );
+        tokio::spawn(async move {
+            if let Err(err) = conn.await {
+                tracing::debug!("Failed to serve unix socket connection: {err}");
+            }
+        });
+    }
+
+    drop(listener);
+    // Let in-flight requests complete, then close remaining connections
+    if tokio::time::timeout(GRACEFUL_SHUTDOWN_TIMEOUT, graceful.shutdown())
+        .await
+        .is_err()
+    {
+        tracing::warn!("Timed out waiting for unix socket connections to close");
+    }
+
+    tracing::info!("Shutdown complete");
+
+    Ok(())
+}
+
+fn router(dep: Dependency) -> (Router, Option<RustlsConfig>) {
     let Dependency {
         authenticator,
         runtime,
@@ -71,19 +206,34 @@ pub async fn serve(
                 timeout: request_timeout,
                 body_limit_bytes: request_body_limit_bytes,
                 concurrency_limit,
+                client_ip_rate_limit,
+                principal_rate_limit,
+                trusted_proxies,
             },
         monitors,
+        persisted_queries,
     } = dep;
 
+    let readiness = Readiness::new(runtime.make_usecase());
     let cx = Context {
         gql_monitor: monitors.gql,
-        schema: gql::schema_builder().data(runtime).finish(),
+        make_loaders: MakeLoaders::new(runtime.make_usecase()),
+        schema: gql::schema_builder(persisted_queries)
+            .data(runtime)
+            .finish(),
     };
 
     let service = Router::new()
         .route("/graphql", post(gql::handler::graphql))
+        .route("/graphql/ws", get(gql::handler::graphql_ws))
         .layer(Extension(cx))
+        .layer(option_layer(principal_rate_limit.is_enabled().then(|| {
+            RateLimitLayer::new(RateLimitKey::Principal, principal_rate_limit)
+        })))
         .layer(authenticate::AuthenticateLayer::new(authenticator))
+        .layer(option_layer(client_ip_rate_limit.is_enabled().then(|| {
+            RateLimitLayer::new(RateLimitKey::ClientIp, client_ip_rate_limit)
+        })))
         .route("/graphql", get(gql::handler::graphiql))
         .layer(
             ServiceBuilder::new()
@@ -98,17 +248,16 @@ pub async fn serve(
                 .layer(CorsLayer::new()),
         )
         .route("/health", get(probe::healthcheck))
+        .route("/health/live", get(probe::liveness))
+        .route(
+            "/health/ready",
+            get(probe::readiness).layer(Extension(readiness)),
+        )
         .layer(RequestMetricsLayer::new())
+        .layer(ClientIpLayer::new(trusted_proxies))
         .fallback(not_found);
 
-    axum_server::from_tcp_rustls(listener.into_std()?, tls_config)
-        .handle(shutdown.into_handle())
-        .serve(service.into_make_service())
-        .await?;
-
-    tracing::info!("Shutdown complete");
-
-    Ok(())
+    (service, tls_config)
 }
 
 async fn handle_middleware_error(err: BoxError) -> (StatusCode, String) {
@@ -128,3 +277,25 @@ async fn handle_middleware_error(err: BoxError) -> (StatusCode, String) {
 async fn not_found() -> impl IntoResponse {
     StatusCode::NOT_FOUND
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn remove_only_stale_socket() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let socket = dir.path().join("synd.sock");
+        drop(UnixListener::bind(&socket).unwrap());
+        remove_stale_socket(&socket).unwrap();
+        assert!(!socket.exists());
+        // Nothing to remove
+        remove_stale_socket(&socket).unwrap();
+
+        let file = dir.path().join("synd.txt");
+        std::fs::write(&file, "keep").unwrap();
+        assert!(remove_stale_socket(&file).is_err());
+        assert!(file.exists());
+    }
+}
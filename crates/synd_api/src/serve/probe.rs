@@ -1,11 +1,20 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
 use axum::{
     http::{header, StatusCode},
     response::IntoResponse,
-    Json,
+    Extension, Json,
+};
+use synd_feed::feed::cache::FetchCachedFeed;
+use synd_o11y::{
+    health_check::{Check, Health, Status},
+    opentelemetry::exporter_status,
 };
-use synd_o11y::health_check::Health;
 
-use crate::config;
+use crate::{config, repository::SubscriptionRepository, usecase::MakeUsecase};
 
 pub async fn healthcheck() -> impl IntoResponse {
     (
@@ -18,3 +27,283 @@ pub async fn healthcheck() -> impl IntoResponse {
         ),
     )
 }
+
+/// Liveness probe. Dependencies are not checked so that their outage does not restart the process
+pub async fn liveness() -> impl IntoResponse {
+    response(
+        Health::pass()
+            .with_version(config::VERSION)
+            .with_description("liveness of synd-api"),
+    )
+}
+
+/// Dependencies checked by the readiness probe
+#[derive(Clone)]
+pub struct Readiness {
+    repository: Arc<dyn SubscriptionRepository>,
+    fetch_feed: Arc<dyn FetchCachedFeed>,
+}
+
+impl Readiness {
+    const REPOSITORY_TIMEOUT: Duration = Duration::from_secs(3);
+    /// Exporter errors older than this are regarded as recovered
+    const EXPORTER_ERROR_WINDOW: Duration = Duration::from_secs(60 * 5);
+
+    pub fn new(make: &MakeUsecase) -> Self {
+        Self {
+            repository: Arc::clone(&make.subscription_repo),
+            fetch_feed: Arc::clone(&make.fetch_feed),
+        }
+    }
+
+    async fn check(&self) -> Health {
+        let mut health = Health::pass()
+            .with_version(config::VERSION)
+            .with_description("readiness of synd-api")
+            .with_check("repository:connectivity", self.check_repository().await);
+
+        for (key, check) in self.check_feed_cache() {
+            health = health.with_check(key, check);
+        }
+        if let Some(check) = Self::check_exporter() {
+            health = health.with_check("otlp_exporter:errors", check);
+        }
+        health
+    }
+
+    async fn check_repository(&self) -> Check {
+        let check =
+            match tokio::time::timeout(Self::REPOSITORY_TIMEOUT, self.repository.ping()).await {
+                Ok(Ok(())) => Check::pass(),
+                Ok(Err(err)) => Check::fail(err.to_string()),
+                Err(_) => Check::fail(format!(
+                    "no response in {}s",
+                    Self::REPOSITORY_TIMEOUT.as_secs()
+                )),
+            };
+        check
+            .with_component_id(self.repository.component_id())
+            .with_component_type("datastore")
+    }
+
+    /// The cache evicts feeds when it is full, so its state is only reported
+    fn check_feed_cache(&self) -> Vec<(&'static str, Check)> {
+        let stats = self.fetch_feed.cache_stats();
+        let mut checks = vec![
+            (
+                "feed_cache:entries",
+                Check::pass()
+                    .with_component_type("component")
+                    .with_observed_value(stats.entry_count, "entries"),
+            ),
+            (
+                "feed_cache:size",
+                Check::pass()
+                    .with_component_type("component")
+                    .with_observed_value(stats.weighted_size, "bytes"),
+            ),
+        ];
+        if let Some(max) = stats.max_capacity.filter(|max| *max > 0) {
+            #[allow(clippy::cast_precision_loss)]
+            let utilization = stats.weighted_size as f64 / max as f64 * 100.;
+            checks.push((
+                "feed_cache:utilization",
+                Check::pass()
+                    .with_component_type("component")
+                    .with_observed_value(utilization, "percent"),
+            ));
+        }
+        checks
+    }
+
+    /// Exporter errors do not affect serving requests, so they are reported as warn
+    fn check_exporter() -> Option<Check> {
+        let status = exporter_status()?;
+        let recent = status.last_error.filter(|(at, _)| {
+            SystemTime::now()
+                .duration_since(*at)
+                .is_ok_and(|elapsed| elapsed < Self::EXPORTER_ERROR_WINDOW)
+        });
+
+        let check = match recent {
+            Some((_, err)) => Check::warn(err),
+            None => Check::pass(),
+        };
+        Some(check.with_observed_value(status.errors, "errors"))
+    }
+}
+
+/// Readiness probe. Respond with 503 if any dependency fails
+pub async fn readiness(Extension(readiness): Extension<Readiness>) -> impl IntoResponse {
+    response(readiness.check().await)
+}
+
+fn response(health: Health) -> impl IntoResponse {
+    let status = match health.status {
+        Status::Pass | Status::Warn => StatusCode::OK,
+        Status::Fail => StatusCode::SERVICE_UNAVAILABLE,
+    };
+
+    (
+        status,
+        [(header::CONTENT_TYPE, Health::CONTENT_TYPE)],
+        Json(health),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future;
+
+    use async_trait::async_trait;
+    use synd_feed::{
+        feed::{cache::CacheStats, parser::FetchFeedResult},
+        types,
+    };
+
+    use crate::repository::{self, RepositoryError, RepositoryResult};
+
+    use super::*;
+
+    enum Ping {
+        Ok,
+        Err,
+        Hang,
+    }
+
+    struct StubRepository(Ping);
+
+    #[async_trait]
+    impl SubscriptionRepository for StubRepository {
+        async fn put_feed_subscription(
+            &self,
+            _feed: repository::types::FeedSubscription,
+        ) -> RepositoryResult<()> {
+            unimplemented!()
+        }
+
+        async fn delete_feed_subscription(
+            &self,
+            _feed: repository::types::FeedSubscription,
+        ) -> RepositoryResult<()> {
+            unimplemented!()
+        }
+
+        async fn fetch_subscriptions(
+            &self,
+            _user_id: &str,
+        ) -> RepositoryResult<Vec<repository::types::Subscription>> {
+            unimplemented!()
+        }
+
+        async fn update_subscription(
+            &self,
+            _user_id: &str,
+            _url: &str,
+            _update: repository::types::SubscriptionUpdate,
+        ) -> RepositoryResult<Option<repository::types::Subscription>> {
+            unimplemented!()
+        }
+
+        async fn ping(&self) -> RepositoryResult<()> {
+            match self.0 {
+                Ping::Ok => Ok(()),
+                Ping::Err => Err(RepositoryError::internal(anyhow::anyhow!(
+                    "connection refused"
+                ))),
+                Ping::Hang => future::pending().await,
+            }
+        }
+
+        fn component_id(&self) -> String {
+            "stub".into()
+        }
+    }
+
+    struct StubFeed;
+
+    #[async_trait]
+    impl FetchCachedFeed for StubFeed {
+        async fn fetch_feed(&self, _url: String) -> FetchFeedResult<Arc<types::Feed>> {
+            unimplemented!()
+        }
+
+        async fn refresh_feed(&self, _url: String) -> FetchFeedResult<Arc<types::Feed>> {
+            unimplemented!()
+        }
+
+        async fn purge_feed(&self, _url: &str) -> bool {
+            unimplemented!()
+        }
+
+        async fn fetch_feeds_parallel(
+            &self,
+            _urls: &[String],
+        ) -> Vec<FetchFeedResult<Arc<types::Feed>>> {
+            unimplemented!()
+        }
+
+        fn cache_stats(&self) -> CacheStats {
+            CacheStats {
+                entry_count: 0,
+                weighted_size: 0,
+                max_capacity: Some(1024),
+            }
+        }
+    }
+
+    fn readiness(ping: Ping) -> Readiness {
+        Readiness {
+            repository: Arc::new(StubRepository(ping)),
+            fetch_feed: Arc::new(StubFeed),
+        }
+    }
+
+    fn status_code(health: Health) -> StatusCode {
+        response(health).into_response().status()
+    }
+
+    #[tokio::test]
+    async fn ready_if_repository_is_reachable() {
+        let health = readiness(Ping::Ok).check().await;
+        let check = &health.checks["repository:connectivity"][0];
+
+        assert_eq!(check.status, Status::Pass);
+        assert_eq!(check.component_id.as_deref(), Some("stub"));
+        assert_eq!(status_code(health), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn unavailable_if_repository_ping_fails() {
+        let health = readiness(Ping::Err).check().await;
+        let check = &health.checks["repository:connectivity"][0];
+
+        assert_eq!(check.status, Status::Fail);
+        assert!(check
+            .output
+            .as_ref()
+            .unwrap()
+            .contains("connection refused"));
+        assert_eq!(status_code(health), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn unavailable_if_repository_ping_times_out() {
+        let health = readiness(Ping::Hang).check().await;
+        let check = &health.checks["repository:connectivity"][0];
+
+        assert_eq!(check.status, Status::Fail);
+        assert_eq!(check.output.as_deref(), Some("no response in 3s"));
+        assert_eq!(status_code(health), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn warn_is_still_ok() {
+        let health = Health::pass()
+            .with_check("a:b", Check::pass())
+            .with_check("otlp_exporter:errors", Check::warn("export failed"));
+
+        assert_eq!(health.status, Status::Warn);
+        assert_eq!(status_code(health), StatusCode::OK);
+    }
+}
@@ -0,0 +1,203 @@
+use std::{
+    future::pending,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use axum_server::tls_rustls::RustlsConfig;
+use synd_o11y::metric;
+use tokio::signal::unix::{signal, SignalKind};
+
+/// Reload the certificate in place when the files are modified or SIGHUP is received.
+/// If the reload fails, the current certificate continues to be used
+pub struct CertificateReloader {
+    config: RustlsConfig,
+    certificate: PathBuf,
+    private_key: PathBuf,
+    /// Interval of checking the modification of the files. zero disables the check
+    interval: Duration,
+}
+
+impl CertificateReloader {
+    pub fn new(
+        config: RustlsConfig,
+        certificate: PathBuf,
+        private_key: PathBuf,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            config,
+            certificate,
+            private_key,
+            interval,
+        }
+    }
+
+    /// Spawn reload task
+    pub fn spawn(self) {
+        tokio::spawn(self.run());
+    }
+
+    async fn run(self) {
+        let mut hangup = signal(SignalKind::hangup())
+            .inspect_err(|err| tracing::warn!("Failed to watch SIGHUP: {err}"))
+            .ok();
+        let mut interval = (!self.interval.is_zero()).then(|| {
+            let mut interval = tokio::time::interval(self.interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            interval
+        });
+        let mut loaded = self.modified();
+
+        loop {
+            let trigger = tokio::select! {
+                Some(()) = async {
+                    match hangup.as_mut() {
+                        Some(hangup) => hangup.recv().await,
+                        None => pending().await,
+                    }
+                } => "signal",
+                _ = async {
+                    match interval.as_mut() {
+                        Some(interval) => interval.tick().await,
+                        None => pending().await,
+                    }
+                } => {
+                    if self.modified() == loaded {
+                        continue;
+                    }
+                    "modified"
+                },
+            };
+
+            // Record the modification time only on success so that a failed reload,
+            // for example while the certificate and key are being replaced, is retried
+            let modified = self.modified();
+            if self.reload(trigger).await {
+                loaded = modified;
+            }
+        }
+    }
+
+    async fn reload(&self, trigger: &'static str) -> bool {
+        match self
+            .config
+            .reload_from_pem_file(&self.certificate, &self.private_key)
+            .await
+        {
+            Ok(()) => {
+                tracing::info!(trigger, "Reloaded tls certificate");
+                metric!(monotonic_counter.tls.reload = 1, result = "success");
+                true
+            }
+            Err(err) => {
+                tracing::error!(
+                    trigger,
+                    certificate = %self.certificate.display(),
+                    private_key = %self.private_key.display(),
+                    "Failed to reload tls certificate, keep using the current one: {err}"
+                );
+                metric!(monotonic_counter.tls.reload = 1, result = "failure");
+                false
+            }
+        }
+    }
+
+    fn modified(&self) -> Option<(SystemTime, SystemTime)> {
+        let modified = |path: &PathBuf| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        Some((modified(&self.certificate)?, modified(&self.private_key)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path, sync::Arc};
+
+    use super::*;
+
+    const CERTIFICATE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../.dev/self_signed_certs/certificate.pem"
+    );
+    const PRIVATE_KEY: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../.dev/self_signed_certs/private_key.pem"
+    );
+    const INTERVAL: Duration = Duration::from_millis(10);
+
+    /// Copy the certificate and key to the dir and spawn the reloader of them
+    async fn spawn_reloader(dir: &Path) -> (RustlsConfig, PathBuf, PathBuf) {
+        let certificate = dir.join("certificate.pem");
+        let private_key = dir.join("private_key.pem");
+        fs::copy(CERTIFICATE, &certificate).unwrap();
+        fs::copy(PRIVATE_KEY, &private_key).unwrap();
+
+        let config = RustlsConfig::from_pem_file(&certificate, &private_key)
+            .await
+            .unwrap();
+        CertificateReloader::new(
+            config.clone(),
+            certificate.clone(),
+            private_key.clone(),
+            INTERVAL,
+        )
+        .spawn();
+        // Let the reloader record the modification time
+        wait_intervals().await;
+
+        (config, certificate, private_key)
+    }
+
+    /// Write the content with a modification time which differs from the current one
+    fn write(path: &Path, content: impl AsRef<[u8]>) {
+        let modified = fs::metadata(path).unwrap().modified().unwrap();
+        fs::write(path, content).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified + Duration::from_secs(1))
+            .unwrap();
+    }
+
+    async fn wait_intervals() {
+        tokio::time::sleep(INTERVAL * 10).await;
+    }
+
+    #[tokio::test]
+    async fn reload_when_modified() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (config, certificate, _) = spawn_reloader(dir.path()).await;
+        let loaded = config.get_inner();
+
+        // Not reloaded unless modified
+        wait_intervals().await;
+        assert!(Arc::ptr_eq(&loaded, &config.get_inner()));
+
+        write(&certificate, fs::read(CERTIFICATE).unwrap());
+        wait_intervals().await;
+        assert!(!Arc::ptr_eq(&loaded, &config.get_inner()));
+    }
+
+    #[tokio::test]
+    async fn keep_config_if_pair_is_broken() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (config, certificate, private_key) = spawn_reloader(dir.path()).await;
+        let loaded = config.get_inner();
+
+        write(&certificate, "broken");
+        wait_intervals().await;
+        assert!(Arc::ptr_eq(&loaded, &config.get_inner()));
+
+        // Valid certificate with broken key
+        write(&certificate, fs::read(CERTIFICATE).unwrap());
+        write(&private_key, "broken");
+        wait_intervals().await;
+        assert!(Arc::ptr_eq(&loaded, &config.get_inner()));
+
+        // Failed reload is retried once the pair is fixed
+        write(&private_key, fs::read(PRIVATE_KEY).unwrap());
+        wait_intervals().await;
+        assert!(!Arc::ptr_eq(&loaded, &config.get_inner()));
+    }
+}
@@ -3,6 +3,9 @@ use std::time::Duration;
 use axum_server::Handle;
 use tokio::sync::broadcast::{self, Receiver, Sender};
 
+/// Time to wait for in-flight requests before closing connections
+pub const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
 pub struct Shutdown {
     tx: Sender<()>,
     rx: Receiver<()>,
@@ -22,7 +25,7 @@ impl Shutdown {
                 Err(err) => tracing::error!("Failed to handle signal {err}"),
             }
             // Signal graceful shutdown to axum_server
-            handle2.graceful_shutdown(Some(Duration::from_secs(3)));
+            handle2.graceful_shutdown(Some(GRACEFUL_SHUTDOWN_TIMEOUT));
             tx2.send(()).ok();
         });
 
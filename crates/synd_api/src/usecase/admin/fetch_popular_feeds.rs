@@ -0,0 +1,81 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    principal::Principal,
+    repository::{SubscriptionRepository, UserRepository},
+    usecase::{authorize::Unauthorized, Error, Input, MakeUsecase, Output, Usecase},
+};
+
+pub struct FetchPopularFeeds {
+    pub user_repo: Arc<dyn UserRepository>,
+    pub subscription_repo: Arc<dyn SubscriptionRepository>,
+}
+
+pub struct FetchPopularFeedsInput {
+    pub first: usize,
+}
+
+pub struct FetchPopularFeedsOutput {
+    /// Feeds ordered by the number of subscribers in descending order
+    pub feeds: Vec<PopularFeed>,
+}
+
+pub struct PopularFeed {
+    pub url: String,
+    pub subscribers: usize,
+}
+
+impl Usecase for FetchPopularFeeds {
+    type Input = FetchPopularFeedsInput;
+
+    type Output = FetchPopularFeedsOutput;
+
+    type Error = anyhow::Error;
+
+    fn new(make: &MakeUsecase) -> Self {
+        Self {
+            user_repo: make.user_repo.clone(),
+            subscription_repo: make.subscription_repo.clone(),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &Self::Input,
+    ) -> Result<Principal, Unauthorized> {
+        super::authorize_admin(principal)
+    }
+
+    async fn usecase(
+        &self,
+        Input {
+            input: FetchPopularFeedsInput { first },
+            ..
+        }: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, Error<Self::Error>> {
+        let mut subscribers = HashMap::<String, usize>::new();
+        for (_, subscriptions) in
+            super::fetch_all_subscriptions(&self.user_repo, &self.subscription_repo).await?
+        {
+            for subscription in subscriptions {
+                *subscribers.entry(subscription.url).or_default() += 1;
+            }
+        }
+
+        let mut feeds = subscribers
+            .into_iter()
+            .map(|(url, subscribers)| PopularFeed { url, subscribers })
+            .collect::<Vec<_>>();
+        feeds.sort_by(|a, b| {
+            b.subscribers
+                .cmp(&a.subscribers)
+                .then_with(|| a.url.cmp(&b.url))
+        });
+        feeds.truncate(first);
+
+        Ok(Output {
+            output: FetchPopularFeedsOutput { feeds },
+        })
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use crate::{
+    principal::Principal,
+    repository::{SubscriptionRepository, UserRepository},
+    usecase::{authorize::Unauthorized, Error, Input, MakeUsecase, Output, Usecase},
+};
+
+pub struct ListUsers {
+    pub user_repo: Arc<dyn UserRepository>,
+    pub subscription_repo: Arc<dyn SubscriptionRepository>,
+}
+
+pub struct ListUsersInput {}
+
+pub struct ListUsersOutput {
+    /// Users ordered by the number of subscriptions in descending order
+    pub users: Vec<UserSummary>,
+}
+
+pub struct UserSummary {
+    pub user_id: String,
+    pub subscriptions: usize,
+    pub disabled: bool,
+}
+
+impl Usecase for ListUsers {
+    type Input = ListUsersInput;
+
+    type Output = ListUsersOutput;
+
+    type Error = anyhow::Error;
+
+    fn new(make: &MakeUsecase) -> Self {
+        Self {
+            user_repo: make.user_repo.clone(),
+            subscription_repo: make.subscription_repo.clone(),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &Self::Input,
+    ) -> Result<Principal, Unauthorized> {
+        super::authorize_admin(principal)
+    }
+
+    async fn usecase(
+        &self,
+        _: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, Error<Self::Error>> {
+        let disabled = self.user_repo.fetch_disabled_user_ids().await?;
+
+        let mut users = super::fetch_all_subscriptions(&self.user_repo, &self.subscription_repo)
+            .await?
+            .into_iter()
+            .map(|(user_id, subscriptions)| UserSummary {
+                disabled: disabled.contains(&user_id),
+                user_id,
+                subscriptions: subscriptions.len(),
+            })
+            .collect::<Vec<_>>();
+        users.sort_by(|a, b| {
+            b.subscriptions
+                .cmp(&a.subscriptions)
+                .then_with(|| a.user_id.cmp(&b.user_id))
+        });
+
+        Ok(Output {
+            output: ListUsersOutput { users },
+        })
+    }
+}
@@ -0,0 +1,59 @@
+//! Usecases which only admins are authorized to run
+
+mod list_users;
+pub use list_users::{ListUsers, ListUsersInput, ListUsersOutput, UserSummary};
+
+mod fetch_popular_feeds;
+pub use fetch_popular_feeds::{
+    FetchPopularFeeds, FetchPopularFeedsInput, FetchPopularFeedsOutput, PopularFeed,
+};
+
+mod purge_feed_cache;
+pub use purge_feed_cache::{PurgeFeedCache, PurgeFeedCacheInput, PurgeFeedCacheOutput};
+
+mod refresh_feed;
+pub use refresh_feed::{RefreshFeed, RefreshFeedError, RefreshFeedInput, RefreshFeedOutput};
+
+mod set_user_disabled;
+pub use set_user_disabled::{SetUserDisabled, SetUserDisabledInput, SetUserDisabledOutput};
+
+use std::sync::Arc;
+
+use futures_util::{stream, StreamExt, TryStreamExt};
+
+use crate::{
+    principal::Principal,
+    repository::{
+        types::Subscription, RepositoryError, RepositoryResult, SubscriptionRepository,
+        UserRepository,
+    },
+    usecase::authorize::Unauthorized,
+};
+
+/// Number of users whose subscriptions are fetched concurrently
+const FETCH_CONCURRENCY: usize = 10;
+
+fn authorize_admin(principal: Principal) -> Result<Principal, Unauthorized> {
+    if principal.is_admin() {
+        Ok(principal)
+    } else {
+        Err(Unauthorized)
+    }
+}
+
+/// Fetch subscriptions of all users
+async fn fetch_all_subscriptions(
+    user_repo: &Arc<dyn UserRepository>,
+    subscription_repo: &Arc<dyn SubscriptionRepository>,
+) -> RepositoryResult<Vec<(String, Vec<Subscription>)>> {
+    let user_ids = user_repo.fetch_user_ids().await?;
+
+    stream::iter(user_ids)
+        .map(|user_id| async move {
+            let subscriptions = subscription_repo.fetch_subscriptions(&user_id).await?;
+            Ok::<_, RepositoryError>((user_id, subscriptions))
+        })
+        .buffered(FETCH_CONCURRENCY)
+        .try_collect()
+        .await
+}
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use synd_feed::feed::cache::FetchCachedFeed;
+use synd_o11y::{audit, tracing_subscriber::audit::Audit};
+
+use crate::{
+    principal::Principal,
+    usecase::{authorize::Unauthorized, Error, Input, MakeUsecase, Output, Usecase},
+};
+
+pub struct PurgeFeedCache {
+    pub fetch_feed: Arc<dyn FetchCachedFeed>,
+}
+
+pub struct PurgeFeedCacheInput {
+    pub url: String,
+}
+
+pub struct PurgeFeedCacheOutput {
+    /// Whether the feed was cached
+    pub purged: bool,
+}
+
+impl Usecase for PurgeFeedCache {
+    type Input = PurgeFeedCacheInput;
+
+    type Output = PurgeFeedCacheOutput;
+
+    type Error = anyhow::Error;
+
+    fn new(make: &MakeUsecase) -> Self {
+        Self {
+            fetch_feed: make.fetch_feed.clone(),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &Self::Input,
+    ) -> Result<Principal, Unauthorized> {
+        super::authorize_admin(principal)
+    }
+
+    async fn usecase(
+        &self,
+        Input {
+            input: PurgeFeedCacheInput { url },
+            ..
+        }: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, Error<Self::Error>> {
+        audit!({ Audit::RESOURCE } = url.as_str());
+
+        let purged = self.fetch_feed.purge_feed(&url).await;
+
+        Ok(Output {
+            output: PurgeFeedCacheOutput { purged },
+        })
+    }
+}
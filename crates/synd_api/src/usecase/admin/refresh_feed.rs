@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use synd_feed::{
+    feed::{cache::FetchCachedFeed, parser::FetchFeedError},
+    types::Feed,
+};
+use synd_o11y::{audit, tracing_subscriber::audit::Audit};
+use thiserror::Error;
+
+use crate::{
+    principal::Principal,
+    usecase::{authorize::Unauthorized, Error, Input, MakeUsecase, Output, Usecase},
+};
+
+pub struct RefreshFeed {
+    pub fetch_feed: Arc<dyn FetchCachedFeed>,
+}
+
+pub struct RefreshFeedInput {
+    pub url: String,
+}
+
+pub struct RefreshFeedOutput {
+    pub feed: Arc<Feed>,
+}
+
+#[derive(Error, Debug)]
+pub enum RefreshFeedError {
+    #[error("fetch feed error: {0}")]
+    FetchFeed(#[from] FetchFeedError),
+}
+
+impl Usecase for RefreshFeed {
+    type Input = RefreshFeedInput;
+
+    type Output = RefreshFeedOutput;
+
+    type Error = RefreshFeedError;
+
+    fn new(make: &MakeUsecase) -> Self {
+        Self {
+            fetch_feed: make.fetch_feed.clone(),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &Self::Input,
+    ) -> Result<Principal, Unauthorized> {
+        super::authorize_admin(principal)
+    }
+
+    async fn usecase(
+        &self,
+        Input {
+            input: RefreshFeedInput { url },
+            ..
+        }: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, Error<Self::Error>> {
+        audit!({ Audit::RESOURCE } = url.as_str());
+
+        // Fetched feed is also cached, indexed and broadcast to subscribers
+        let feed = self
+            .fetch_feed
+            .refresh_feed(url)
+            .await
+            .map_err(|err| Error::Usecase(err.into()))?;
+
+        Ok(Output {
+            output: RefreshFeedOutput { feed },
+        })
+    }
+}
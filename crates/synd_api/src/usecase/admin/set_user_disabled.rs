@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use synd_o11y::{audit, tracing_subscriber::audit::Audit};
+
+use crate::{
+    principal::Principal,
+    repository::UserRepository,
+    usecase::{authorize::Unauthorized, Error, Input, MakeUsecase, Output, Usecase},
+};
+
+pub struct SetUserDisabled {
+    pub user_repo: Arc<dyn UserRepository>,
+}
+
+pub struct SetUserDisabledInput {
+    pub user_id: String,
+    pub disabled: bool,
+}
+
+pub struct SetUserDisabledOutput {}
+
+impl Usecase for SetUserDisabled {
+    type Input = SetUserDisabledInput;
+
+    type Output = SetUserDisabledOutput;
+
+    type Error = anyhow::Error;
+
+    fn new(make: &MakeUsecase) -> Self {
+        Self {
+            user_repo: make.user_repo.clone(),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &Self::Input,
+    ) -> Result<Principal, Unauthorized> {
+        super::authorize_admin(principal)
+    }
+
+    async fn usecase(
+        &self,
+        Input {
+            input: SetUserDisabledInput { user_id, disabled },
+            ..
+        }: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, Error<Self::Error>> {
+        audit!({ Audit::RESOURCE } = user_id.as_str());
+
+        // Authorizer caches the status, so it takes a while to take effect
+        self.user_repo.set_user_disabled(&user_id, disabled).await?;
+
+        Ok(Output {
+            output: SetUserDisabledOutput {},
+        })
+    }
+}
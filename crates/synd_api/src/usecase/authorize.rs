@@ -1,8 +1,22 @@
-use std::ops::Deref;
+use std::{
+    collections::HashSet,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
-use crate::principal::Principal;
+use moka::future::Cache;
+use synd_o11y::metric;
+use thiserror::Error;
 
-use super::Usecase;
+use crate::{
+    principal::{Principal, Scope},
+    repository::{types::Identity, RepositoryResult, SubscriptionRepository, UserRepository},
+    usecase::{Error, Usecase},
+};
 
 pub struct Authorized<T> {
     principal: T,
@@ -12,6 +26,12 @@ impl Authorized<Principal> {
     fn new(principal: Principal) -> Self {
         Self { principal }
     }
+
+    /// Regard the principal as authorized to run usecases directly in tests
+    #[cfg(test)]
+    pub(crate) fn new_for_test(principal: Principal) -> Self {
+        Self::new(principal)
+    }
 }
 
 impl<T> Deref for Authorized<T> {
@@ -25,11 +45,245 @@ impl<T> Deref for Authorized<T> {
 #[derive(Debug)]
 pub struct Unauthorized;
 
-pub struct Authorizer {}
+#[derive(Error, Debug)]
+#[error("{resource} quota exceeded: limit is {limit}")]
+pub struct QuotaExceeded {
+    pub resource: &'static str,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tier {
+    Free,
+    Pro,
+}
+
+/// Limits applied to each user
+#[derive(Debug, Clone)]
+pub struct Quota {
+    pub max_subscriptions: usize,
+    pub mutations_per_minute: usize,
+    pub max_entries_page_size: usize,
+}
+
+/// User in pro tier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProUser {
+    /// Identity at the authentication provider
+    Identity(Identity),
+    /// User id of synd-api
+    User(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct QuotaPolicy {
+    pub free: Quota,
+    pub pro: Quota,
+    /// Identities of the users in pro tier
+    pub pro_identities: HashSet<Identity>,
+    /// User ids of the users in pro tier
+    pub pro_user_ids: HashSet<String>,
+}
+
+impl QuotaPolicy {
+    #[must_use]
+    pub fn with_pro_users(mut self, users: impl IntoIterator<Item = ProUser>) -> Self {
+        for user in users {
+            match user {
+                ProUser::Identity(identity) => self.pro_identities.insert(identity),
+                ProUser::User(user_id) => self.pro_user_ids.insert(user_id),
+            };
+        }
+        self
+    }
+
+    fn quota(&self, tier: Tier) -> &Quota {
+        match tier {
+            Tier::Free => &self.free,
+            Tier::Pro => &self.pro,
+        }
+    }
+}
+
+/// Resources which the usecase consumes. Used to enforce quotas
+#[derive(Debug, Default)]
+pub struct Consumption<'a> {
+    /// Whether the usecase modifies user's data
+    pub mutation: bool,
+    /// Feed urls to be subscribed
+    pub subscribe_urls: Vec<&'a str>,
+    /// Number of requested entries
+    pub entries_page_size: Option<usize>,
+}
+
+struct Quotas {
+    policy: QuotaPolicy,
+    repository: Arc<dyn SubscriptionRepository>,
+    users: Arc<dyn UserRepository>,
+    /// Number of mutations of each user in the current window
+    mutations: Cache<String, Arc<AtomicUsize>>,
+    /// Tier of each user resolved from the linked identities
+    tiers: Cache<String, Tier>,
+}
+
+impl Quotas {
+    async fn check<E>(
+        &self,
+        principal: &Principal,
+        consumption: Consumption<'_>,
+    ) -> Result<(), Error<E>> {
+        let Some(user_id) = principal.user_id() else {
+            return Ok(());
+        };
+        let quota = self.policy.quota(self.tier(principal, user_id).await?);
+
+        if let Some(page_size) = consumption.entries_page_size {
+            if page_size > quota.max_entries_page_size {
+                return Err(Self::exceeded(
+                    "entries page size",
+                    quota.max_entries_page_size,
+                ));
+            }
+        }
+
+        if consumption.mutation {
+            let mutations = self
+                .mutations
+                .get_with(user_id.to_owned(), async { Arc::new(AtomicUsize::new(0)) })
+                .await;
+            if mutations.fetch_add(1, Ordering::Relaxed) >= quota.mutations_per_minute {
+                return Err(Self::exceeded(
+                    "mutations per minute",
+                    quota.mutations_per_minute,
+                ));
+            }
+        }
+
+        if !consumption.subscribe_urls.is_empty() {
+            let subscribed = self
+                .repository
+                .fetch_subscribed_feed_urls(user_id)
+                .await?
+                .into_iter()
+                .collect::<HashSet<_>>();
+            let new_subscriptions = consumption
+                .subscribe_urls
+                .into_iter()
+                .filter(|url| !subscribed.contains(*url))
+                .collect::<HashSet<_>>()
+                .len();
+            if subscribed.len() + new_subscriptions > quota.max_subscriptions {
+                return Err(Self::exceeded("subscriptions", quota.max_subscriptions));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Emails are not used to decide the tier since they are controlled by the providers
+    async fn tier(&self, principal: &Principal, user_id: &str) -> RepositoryResult<Tier> {
+        if principal.is_admin() || self.policy.pro_user_ids.contains(user_id) {
+            return Ok(Tier::Pro);
+        }
+        if self.policy.pro_identities.is_empty() {
+            return Ok(Tier::Free);
+        }
+        if let Some(tier) = self.tiers.get(user_id).await {
+            return Ok(tier);
+        }
+
+        let is_pro = self.users.fetch_user(user_id).await?.is_some_and(|user| {
+            user.identities
+                .iter()
+                .any(|identity| self.policy.pro_identities.contains(identity))
+        });
+        let tier = if is_pro { Tier::Pro } else { Tier::Free };
+        self.tiers.insert(user_id.to_owned(), tier).await;
+
+        Ok(tier)
+    }
+
+    fn exceeded<E>(resource: &'static str, limit: usize) -> Error<E> {
+        metric!(monotonic_counter.quota_exceeded = 1, resource);
+        Error::QuotaExceeded(QuotaExceeded { resource, limit })
+    }
+}
+
+/// Users disabled by admin
+struct DisabledUsers {
+    repository: Arc<dyn UserRepository>,
+    /// Disabled status of each user. Changes take effect after the entry expires
+    cache: Cache<String, bool>,
+}
+
+impl DisabledUsers {
+    async fn is_disabled(&self, user_id: &str) -> RepositoryResult<bool> {
+        if let Some(disabled) = self.cache.get(user_id).await {
+            return Ok(disabled);
+        }
+        let disabled = self.repository.is_user_disabled(user_id).await?;
+        self.cache.insert(user_id.to_owned(), disabled).await;
+
+        Ok(disabled)
+    }
+}
+
+pub struct Authorizer {
+    quotas: Option<Quotas>,
+    disabled_users: Option<DisabledUsers>,
+}
 
 impl Authorizer {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            quotas: None,
+            disabled_users: None,
+        }
+    }
+
+    /// Reject users disabled by admin
+    #[must_use]
+    pub fn with_disabled_users(self, repository: Arc<dyn UserRepository>) -> Self {
+        let cache = Cache::builder()
+            .max_capacity(1024 * 1024)
+            .time_to_live(Duration::from_secs(60))
+            .build();
+
+        Self {
+            disabled_users: Some(DisabledUsers { repository, cache }),
+            ..self
+        }
+    }
+
+    /// Enforce quotas of given policy
+    #[must_use]
+    pub fn with_quota(
+        self,
+        policy: QuotaPolicy,
+        repository: Arc<dyn SubscriptionRepository>,
+        users: Arc<dyn UserRepository>,
+    ) -> Self {
+        // Entries are evicted after a minute, which resets the count
+        let mutations = Cache::builder()
+            .max_capacity(1024 * 1024)
+            .time_to_live(Duration::from_secs(60))
+            .build();
+        // Identities linked later take effect after the entry expires
+        let tiers = Cache::builder()
+            .max_capacity(1024 * 1024)
+            .time_to_live(Duration::from_secs(60))
+            .build();
+
+        Self {
+            quotas: Some(Quotas {
+                policy,
+                repository,
+                users,
+                mutations,
+                tiers,
+            }),
+            ..self
+        }
     }
 
     pub async fn authorize<U: Usecase>(
@@ -37,10 +291,152 @@ impl Authorizer {
         principal: Principal,
         usecase: &U,
         input: &U::Input,
-    ) -> Result<Authorized<Principal>, Unauthorized> {
-        usecase
+    ) -> Result<Authorized<Principal>, Error<U::Error>> {
+        let principal = usecase
             .authorize(principal, input)
             .await
-            .map(Authorized::new)
+            .map_err(Error::Unauthorized)?;
+
+        if let (Some(disabled_users), Some(user_id)) = (&self.disabled_users, principal.user_id()) {
+            // Admins are not disabled so that they can enable themselves
+            if !principal.is_admin() && disabled_users.is_disabled(user_id).await? {
+                return Err(Error::Unauthorized(Unauthorized));
+            }
+        }
+
+        let consumption = usecase.consumption(input);
+        let scope = if consumption.mutation {
+            Scope::Write
+        } else {
+            Scope::Read
+        };
+        if !principal.has_scope(scope) {
+            return Err(Error::Unauthorized(Unauthorized));
+        }
+
+        if let Some(quotas) = &self.quotas {
+            quotas.check(&principal, consumption).await?;
+        }
+
+        Ok(Authorized::new(principal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::{
+        principal::User,
+        repository::{memory::MemoryRepository, types::UserRecord},
+    };
+
+    use super::*;
+
+    fn policy(quota: Quota) -> QuotaPolicy {
+        QuotaPolicy {
+            free: quota.clone(),
+            pro: quota,
+            pro_identities: HashSet::new(),
+            pro_user_ids: HashSet::new(),
+        }
+    }
+
+    fn quotas(quota: Quota) -> Quotas {
+        let Authorizer { quotas, .. } = Authorizer::new().with_quota(
+            policy(quota),
+            Arc::new(MemoryRepository::new()),
+            Arc::new(MemoryRepository::new()),
+        );
+        quotas.unwrap()
+    }
+
+    #[tokio::test]
+    async fn exceed_subscriptions() {
+        // Memory repository has 17 distinct subscriptions
+        let quotas = quotas(Quota {
+            max_subscriptions: 18,
+            mutations_per_minute: 100,
+            max_entries_page_size: 100,
+        });
+        let principal = Principal::User(User::from_email("foo@syndicationd.ymgyt.io"));
+        let subscribe = |urls: Vec<&'static str>| Consumption {
+            subscribe_urls: urls,
+            ..Default::default()
+        };
+
+        let already_subscribed = "https://blog.ymgyt.io/atom.xml";
+        let new = "https://example.com/feed.xml";
+        let new2 = "https://example.com/feed2.xml";
+
+        assert!(quotas
+            .check::<()>(&principal, subscribe(vec![already_subscribed, new, new]))
+            .await
+            .is_ok());
+        assert!(matches!(
+            quotas
+                .check::<()>(&principal, subscribe(vec![new, new2]))
+                .await,
+            Err(Error::QuotaExceeded(QuotaExceeded {
+                resource: "subscriptions",
+                limit: 18
+            }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn exceed_mutations_per_minute() {
+        let quotas = quotas(Quota {
+            max_subscriptions: 100,
+            mutations_per_minute: 2,
+            max_entries_page_size: 100,
+        });
+        let principal = Principal::User(User::from_email("foo@syndicationd.ymgyt.io"));
+        let mutation = || Consumption {
+            mutation: true,
+            ..Default::default()
+        };
+
+        assert!(quotas.check::<()>(&principal, mutation()).await.is_ok());
+        assert!(quotas.check::<()>(&principal, mutation()).await.is_ok());
+        assert!(quotas.check::<()>(&principal, mutation()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn pro_tier_by_user_id_or_identity() {
+        let users = Arc::new(MemoryRepository::new());
+        users
+            .put_user(&UserRecord {
+                id: "linked".into(),
+                email: "linked@example.com".into(),
+                identities: vec![Identity::new("github", "12345")],
+                created_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+        let Authorizer { quotas, .. } = Authorizer::new().with_quota(
+            policy(Quota {
+                max_subscriptions: 100,
+                mutations_per_minute: 100,
+                max_entries_page_size: 100,
+            })
+            .with_pro_users([
+                ProUser::User("pro".into()),
+                ProUser::Identity(Identity::new("github", "12345")),
+            ]),
+            Arc::new(MemoryRepository::new()),
+            users,
+        );
+        let quotas = quotas.unwrap();
+        let tier = |id: &'static str, email: &'static str| {
+            let principal = Principal::User(User::new(id, email));
+            let quotas = &quotas;
+            async move { quotas.tier(&principal, id).await.unwrap() }
+        };
+
+        assert_eq!(tier("pro", "pro@example.com").await, Tier::Pro);
+        assert_eq!(tier("linked", "linked@example.com").await, Tier::Pro);
+        // Email of a pro user does not make other users pro
+        assert_eq!(tier("other", "linked@example.com").await, Tier::Free);
     }
 }
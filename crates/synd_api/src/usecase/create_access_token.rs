@@ -0,0 +1,201 @@
+use std::{sync::Arc, time::Duration};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::Utc;
+use rand::RngCore;
+use synd_o11y::{audit, tracing_subscriber::audit::Audit};
+use thiserror::Error;
+
+use crate::{
+    principal::{Principal, Scope},
+    repository::{types::AccessToken, UserRepository},
+    usecase::{
+        authorize::{Consumption, Unauthorized},
+        Error, Input, MakeUsecase, Output, Usecase,
+    },
+};
+
+pub struct CreateAccessToken {
+    pub repository: Arc<dyn UserRepository>,
+}
+
+pub struct CreateAccessTokenInput {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    /// Token does not expire if not specified
+    pub expires_in: Option<Duration>,
+}
+
+pub struct CreateAccessTokenOutput {
+    /// Plain token. It is not stored, so this is the only chance to get it
+    pub token: String,
+    pub access_token: AccessToken,
+}
+
+#[derive(Error, Debug)]
+pub enum CreateAccessTokenError {
+    #[error("invalid access token: {0}")]
+    InvalidInput(&'static str),
+}
+
+impl Usecase for CreateAccessToken {
+    type Input = CreateAccessTokenInput;
+
+    type Output = CreateAccessTokenOutput;
+
+    type Error = CreateAccessTokenError;
+
+    fn new(make: &MakeUsecase) -> Self {
+        Self {
+            repository: make.user_repo.clone(),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &Self::Input,
+    ) -> Result<Principal, Unauthorized> {
+        // Access tokens can not be used to issue another token
+        if principal.is_token() {
+            Err(Unauthorized)
+        } else {
+            Ok(principal)
+        }
+    }
+
+    fn consumption<'a>(&self, _: &'a Self::Input) -> Consumption<'a> {
+        Consumption {
+            mutation: true,
+            ..Default::default()
+        }
+    }
+
+    async fn usecase(
+        &self,
+        Input {
+            principal,
+            input:
+                CreateAccessTokenInput {
+                    name,
+                    mut scopes,
+                    expires_in,
+                },
+        }: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, Error<Self::Error>> {
+        let user_id = principal
+            .user_id()
+            .expect("user id not found. this is a bug");
+
+        if name.trim().is_empty() {
+            return Err(Error::Usecase(CreateAccessTokenError::InvalidInput(
+                "name is empty",
+            )));
+        }
+        scopes.sort_by_key(|scope| scope.as_str());
+        scopes.dedup();
+        if scopes.is_empty() {
+            return Err(Error::Usecase(CreateAccessTokenError::InvalidInput(
+                "scopes are empty",
+            )));
+        }
+
+        let now = Utc::now();
+        let expires_at = expires_in
+            .map(|expires_in| {
+                chrono::Duration::from_std(expires_in)
+                    .ok()
+                    .and_then(|expires_in| now.checked_add_signed(expires_in))
+                    .ok_or(Error::Usecase(CreateAccessTokenError::InvalidInput(
+                        "invalid expiry",
+                    )))
+            })
+            .transpose()?;
+
+        let token = format!("{}{}", AccessToken::PREFIX, Self::random(32));
+        let access_token = AccessToken {
+            id: Self::random(12),
+            user_id: user_id.to_owned(),
+            name,
+            scopes,
+            hash: AccessToken::hash(&token),
+            created_at: now,
+            expires_at,
+        };
+
+        self.repository.put_access_token(&access_token).await?;
+
+        audit!({ Audit::RESOURCE } = access_token.id.as_str());
+
+        Ok(Output {
+            output: CreateAccessTokenOutput {
+                token,
+                access_token,
+            },
+        })
+    }
+}
+
+impl CreateAccessToken {
+    fn random(len: usize) -> String {
+        let mut bytes = vec![0; len];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        principal::User, repository::memory::MemoryRepository, usecase::authorize::Authorized,
+    };
+
+    use super::*;
+
+    async fn create(
+        expires_in: Option<Duration>,
+    ) -> Result<AccessToken, Error<CreateAccessTokenError>> {
+        let usecase = CreateAccessToken {
+            repository: Arc::new(MemoryRepository::new()),
+        };
+        usecase
+            .usecase(Input {
+                principal: Authorized::new_for_test(Principal::User(User::new(
+                    "user",
+                    "user@example.com",
+                ))),
+                input: CreateAccessTokenInput {
+                    name: "ci".into(),
+                    scopes: vec![Scope::Read],
+                    expires_in,
+                },
+            })
+            .await
+            .map(|Output { output }| output.access_token)
+    }
+
+    #[tokio::test]
+    async fn expiry() {
+        let token = create(None).await.unwrap();
+        assert_eq!(token.expires_at, None);
+
+        let token = create(Some(Duration::from_secs(60 * 60 * 24 * 30)))
+            .await
+            .unwrap();
+        assert_eq!(
+            token.expires_at,
+            Some(token.created_at + chrono::Duration::days(30))
+        );
+    }
+
+    #[tokio::test]
+    async fn reject_out_of_range_expiry() {
+        let days = u64::from(u32::MAX);
+        assert!(matches!(
+            create(Some(Duration::from_secs(days * 60 * 60 * 24))).await,
+            Err(Error::Usecase(CreateAccessTokenError::InvalidInput(
+                "invalid expiry"
+            )))
+        ));
+    }
+}
@@ -0,0 +1,194 @@
+use std::{sync::Arc, time::Duration};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::Utc;
+use rand::RngCore;
+use synd_feed::types::Time;
+use synd_o11y::metric;
+use thiserror::Error;
+
+use crate::{
+    principal::Principal,
+    repository::{
+        types::{AccessToken, DeletionToken},
+        RepositoryResult, UserRepository,
+    },
+    usecase::{
+        authorize::{Consumption, Unauthorized},
+        Error, Input, MakeUsecase, Output, Usecase,
+    },
+};
+
+/// Short-lived tokens to confirm account deletion.
+/// Tokens are stored in the repository, so they can be confirmed on any instance
+#[derive(Clone)]
+pub struct DeletionTokens {
+    repository: Arc<dyn UserRepository>,
+}
+
+impl DeletionTokens {
+    const TIME_TO_LIVE: Duration = Duration::from_secs(60 * 10);
+
+    pub fn new(repository: Arc<dyn UserRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// Issue a token for the user. Previously issued token is invalidated
+    pub async fn issue(&self, user_id: &str) -> RepositoryResult<(String, Time)> {
+        let mut bytes = [0; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let token = URL_SAFE_NO_PAD.encode(bytes);
+
+        let expires_at =
+            Utc::now() + chrono::Duration::from_std(Self::TIME_TO_LIVE).expect("Invalid duration");
+        self.repository
+            .put_deletion_token(&DeletionToken::new(user_id, &token, expires_at))
+            .await?;
+
+        Ok((token, expires_at))
+    }
+
+    /// Return true if the token was issued for the user. Confirmed token can not be used again
+    pub async fn confirm(&self, user_id: &str, token: &str) -> RepositoryResult<bool> {
+        let Some(issued) = self.repository.fetch_deletion_token(user_id).await? else {
+            return Ok(false);
+        };
+
+        if issued.is_expired(Utc::now()) {
+            self.repository.delete_deletion_token(user_id).await?;
+            return Ok(false);
+        }
+        if issued.hash != AccessToken::hash(token) {
+            return Ok(false);
+        }
+
+        self.repository.delete_deletion_token(user_id).await?;
+        Ok(true)
+    }
+}
+
+pub struct DeleteMyAccount {
+    pub repository: Arc<dyn UserRepository>,
+    pub tokens: DeletionTokens,
+}
+
+pub struct DeleteMyAccountInput {
+    pub confirmation_token: String,
+}
+
+pub struct DeleteMyAccountOutput {}
+
+#[derive(Error, Debug)]
+pub enum DeleteMyAccountError {
+    #[error("confirmation token is invalid or expired")]
+    InvalidConfirmationToken,
+}
+
+impl Usecase for DeleteMyAccount {
+    type Input = DeleteMyAccountInput;
+
+    type Output = DeleteMyAccountOutput;
+
+    type Error = DeleteMyAccountError;
+
+    fn new(make: &MakeUsecase) -> Self {
+        Self {
+            repository: make.user_repo.clone(),
+            tokens: DeletionTokens::new(make.user_repo.clone()),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &Self::Input,
+    ) -> Result<Principal, Unauthorized> {
+        // Deleting account requires interactive login
+        if principal.is_token() {
+            Err(Unauthorized)
+        } else {
+            Ok(principal)
+        }
+    }
+
+    fn consumption<'a>(&self, _: &'a Self::Input) -> Consumption<'a> {
+        Consumption {
+            mutation: true,
+            ..Default::default()
+        }
+    }
+
+    async fn usecase(
+        &self,
+        Input {
+            principal,
+            input: DeleteMyAccountInput { confirmation_token },
+        }: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, Error<Self::Error>> {
+        let user_id = principal
+            .user_id()
+            .expect("user id not found. this is a bug");
+
+        if !self.tokens.confirm(user_id, &confirmation_token).await? {
+            return Err(Error::Usecase(
+                DeleteMyAccountError::InvalidConfirmationToken,
+            ));
+        }
+
+        self.repository.delete_user(user_id).await?;
+
+        tracing::info!("Account deleted");
+        metric!(monotonic_counter.account.deletion = 1);
+
+        Ok(Output {
+            output: DeleteMyAccountOutput {},
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repository::memory::MemoryRepository;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn confirmation_token_is_used_once() {
+        let tokens = DeletionTokens::new(Arc::new(MemoryRepository::new()));
+        let (token, _) = tokens.issue("user").await.unwrap();
+
+        assert!(!tokens.confirm("other", &token).await.unwrap());
+        assert!(!tokens.confirm("user", "invalid").await.unwrap());
+        assert!(tokens.confirm("user", &token).await.unwrap());
+        assert!(!tokens.confirm("user", &token).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn expired_token_is_rejected() {
+        let repository = Arc::new(MemoryRepository::new());
+        let tokens = DeletionTokens::new(repository.clone());
+        let expired = DeletionToken::new("user", "token", Utc::now());
+        repository.put_deletion_token(&expired).await.unwrap();
+
+        assert!(!tokens.confirm("user", "token").await.unwrap());
+        assert!(repository
+            .fetch_deletion_token("user")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn token_is_shared_by_instances() {
+        let repository: Arc<dyn UserRepository> = Arc::new(MemoryRepository::new());
+        let (token, _) = DeletionTokens::new(repository.clone())
+            .issue("user")
+            .await
+            .unwrap();
+
+        assert!(DeletionTokens::new(repository)
+            .confirm("user", &token)
+            .await
+            .unwrap());
+    }
+}
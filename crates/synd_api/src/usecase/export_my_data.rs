@@ -0,0 +1,197 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use futures_util::{stream, StreamExt, TryStreamExt};
+use synd_feed::types::Time;
+
+use crate::{
+    principal::Principal,
+    repository::{
+        types::{AccessToken, ArchivedEntry, Identity, Subscription},
+        EntryArchiveRepository, SubscriptionRepository, UserRepository,
+    },
+    usecase::{authorize::Unauthorized, Error, Input, MakeUsecase, Output, Usecase},
+};
+
+pub struct ExportMyData {
+    pub subscription_repository: Arc<dyn SubscriptionRepository>,
+    pub user_repository: Arc<dyn UserRepository>,
+    pub archive_repository: Arc<dyn EntryArchiveRepository>,
+}
+
+pub struct ExportMyDataInput {}
+
+/// Everything stored about the user.
+/// Archived entries are shared by the subscribers of the feed, so archived entries of the
+/// subscribed feeds are included. The search index is derived from the feeds and not included
+pub struct ExportMyDataOutput {
+    pub user_id: String,
+    pub email: Option<String>,
+    /// Provider identities linked to the user
+    pub identities: Vec<Identity>,
+    /// The time at which the user record was created. None for users who have never logged in
+    /// since user records were introduced
+    pub created_at: Option<Time>,
+    /// Access tokens issued for the user. Hashes of the tokens are not exported
+    pub access_tokens: Vec<AccessToken>,
+    pub disabled: bool,
+    pub subscriptions: Vec<Subscription>,
+    pub archived_entries: Vec<ArchivedEntry>,
+    pub exported_at: Time,
+}
+
+impl Usecase for ExportMyData {
+    type Input = ExportMyDataInput;
+
+    type Output = ExportMyDataOutput;
+
+    type Error = anyhow::Error;
+
+    fn new(make: &MakeUsecase) -> Self {
+        Self {
+            subscription_repository: make.subscription_repo.clone(),
+            user_repository: make.user_repo.clone(),
+            archive_repository: make.archive_repo.clone(),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &Self::Input,
+    ) -> Result<Principal, Unauthorized> {
+        Ok(principal)
+    }
+
+    async fn usecase(
+        &self,
+        Input { principal, .. }: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, Error<Self::Error>> {
+        const ARCHIVE_CONCURRENCY: usize = 10;
+
+        let user_id = principal
+            .user_id()
+            .expect("user id not found. this is a bug");
+
+        let user = self.user_repository.fetch_user(user_id).await?;
+        let mut access_tokens = self.user_repository.fetch_access_tokens(user_id).await?;
+        for token in &mut access_tokens {
+            token.hash.clear();
+        }
+        let disabled = self.user_repository.is_user_disabled(user_id).await?;
+        let subscriptions = self
+            .subscription_repository
+            .fetch_subscriptions(user_id)
+            .await?;
+
+        let archived_entries = stream::iter(subscriptions.iter())
+            .map(|subscription| {
+                self.archive_repository
+                    .fetch_archived_entries(&subscription.url)
+            })
+            .buffered(ARCHIVE_CONCURRENCY)
+            .try_concat()
+            .await?;
+
+        let (email, identities, created_at) = match user {
+            Some(user) => (Some(user.email), user.identities, Some(user.created_at)),
+            None => (principal.email().map(ToOwned::to_owned), Vec::new(), None),
+        };
+
+        Ok(Output {
+            output: ExportMyDataOutput {
+                user_id: user_id.to_owned(),
+                email,
+                identities,
+                created_at,
+                access_tokens,
+                disabled,
+                subscriptions,
+                archived_entries,
+                exported_at: Utc::now(),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        principal::{Scope, User},
+        repository::{memory::MemoryRepository, types::UserRecord},
+        usecase::authorize::Authorized,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn export_data_of_every_repository() {
+        let repo = Arc::new(MemoryRepository::new());
+        let user_id = User::legacy_id("user@example.com");
+        let now = Utc::now();
+        let feed_url = "https://blog.rust-lang.org/feed.xml";
+
+        repo.put_user(&UserRecord {
+            id: user_id.clone(),
+            email: "user@example.com".into(),
+            identities: vec![Identity::new("github", "1")],
+            created_at: now,
+        })
+        .await
+        .unwrap();
+        repo.put_access_token(&AccessToken {
+            id: "token".into(),
+            user_id: user_id.clone(),
+            name: "ci".into(),
+            scopes: vec![Scope::Read],
+            hash: AccessToken::hash("synd_pat_secret"),
+            created_at: now,
+            expires_at: None,
+        })
+        .await
+        .unwrap();
+        repo.set_user_disabled(&user_id, true).await.unwrap();
+        let archived = ArchivedEntry {
+            feed_url: feed_url.into(),
+            entry_id: "entry-1".into(),
+            title: None,
+            summary: None,
+            content: None,
+            website_url: None,
+            published: None,
+            updated: None,
+            archived_at: now,
+        };
+        repo.archive_entries(feed_url, vec![archived], now - chrono::Duration::days(1))
+            .await
+            .unwrap();
+
+        let usecase = ExportMyData {
+            subscription_repository: repo.clone(),
+            user_repository: repo.clone(),
+            archive_repository: repo.clone(),
+        };
+        let Output { output } = usecase
+            .usecase(Input {
+                principal: Authorized::new_for_test(Principal::User(User::new(
+                    &user_id,
+                    "user@example.com",
+                ))),
+                input: ExportMyDataInput {},
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(output.user_id, user_id);
+        assert_eq!(output.email.as_deref(), Some("user@example.com"));
+        assert_eq!(output.identities, vec![Identity::new("github", "1")]);
+        assert_eq!(output.created_at, Some(now));
+        assert_eq!(output.access_tokens.len(), 1);
+        assert_eq!(output.access_tokens[0].name, "ci");
+        assert!(output.access_tokens[0].hash.is_empty());
+        assert!(output.disabled);
+        assert!(output.subscriptions.iter().any(|sub| sub.url == feed_url));
+        assert_eq!(output.archived_entries.len(), 1);
+        assert_eq!(output.archived_entries[0].feed_url, feed_url);
+    }
+}
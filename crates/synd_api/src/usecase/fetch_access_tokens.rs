@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use crate::{
+    principal::Principal,
+    repository::{types::AccessToken, UserRepository},
+    usecase::{authorize::Unauthorized, Error, Input, MakeUsecase, Output, Usecase},
+};
+
+pub struct FetchAccessTokens {
+    pub repository: Arc<dyn UserRepository>,
+}
+
+pub struct FetchAccessTokensInput {}
+
+pub struct FetchAccessTokensOutput {
+    pub access_tokens: Vec<AccessToken>,
+}
+
+impl Usecase for FetchAccessTokens {
+    type Input = FetchAccessTokensInput;
+
+    type Output = FetchAccessTokensOutput;
+
+    type Error = anyhow::Error;
+
+    fn new(make: &MakeUsecase) -> Self {
+        Self {
+            repository: make.user_repo.clone(),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &Self::Input,
+    ) -> Result<Principal, Unauthorized> {
+        if principal.is_token() {
+            Err(Unauthorized)
+        } else {
+            Ok(principal)
+        }
+    }
+
+    async fn usecase(
+        &self,
+        Input { principal, .. }: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, Error<Self::Error>> {
+        let user_id = principal
+            .user_id()
+            .expect("user id not found. this is a bug");
+
+        let access_tokens = self.repository.fetch_access_tokens(user_id).await?;
+
+        Ok(Output {
+            output: FetchAccessTokensOutput { access_tokens },
+        })
+    }
+}
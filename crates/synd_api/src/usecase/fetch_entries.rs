@@ -1,32 +1,189 @@
-use std::{cmp::Ordering, collections::HashMap, sync::Arc};
+use std::{cmp::Ordering, collections::HashMap, sync::Arc, time::Duration};
 
+use chrono::Utc;
 use futures_util::{stream::FuturesUnordered, StreamExt};
+use serde::{Deserialize, Serialize};
 use synd_feed::{
     feed::{cache::FetchCachedFeed, parser::FetchFeedError},
-    types::{self, EntryId},
+    types::{self, Time},
 };
 use thiserror::Error;
 
 use crate::{
+    archive,
     principal::Principal,
-    repository::SubscriptionRepository,
-    usecase::{authorize::Unauthorized, Error, Input, MakeUsecase, Output, Usecase},
+    repository::{
+        merge_entries,
+        types::{ArchivedEntry, SubscriptionFilter},
+        EntryArchiveRepository, SubscriptionRepository,
+    },
+    usecase::{
+        authorize::{Consumption, Unauthorized},
+        Error, Input, MakeUsecase, Output, Usecase,
+    },
 };
 
 pub struct FetchEntries {
     pub repository: Arc<dyn SubscriptionRepository>,
     pub fetch_feed: Arc<dyn FetchCachedFeed>,
+    pub archive_repository: Arc<dyn EntryArchiveRepository>,
+    pub entry_retention: Duration,
 }
 
+/// Sort key of entries.
+/// Entries are ordered by published(or updated) time descending,
+/// then by feed url and entry id so that the order is total and stable
+/// even if entries are added or removed between requests.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryCursor {
+    pub published: Option<Time>,
+    pub feed_url: types::FeedUrl,
+    pub entry_id: String,
+}
+
+impl EntryCursor {
+    pub fn new(entry: &ArchivedEntry) -> Self {
+        Self {
+            published: entry.published.or(entry.updated),
+            feed_url: entry.feed_url.clone(),
+            entry_id: entry.entry_id.clone(),
+        }
+    }
+}
+
+impl Ord for EntryCursor {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let published = match (self.published, other.published) {
+            (Some(a), Some(b)) => b.cmp(&a),
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (None, None) => Ordering::Equal,
+        };
+        published
+            .then_with(|| self.feed_url.cmp(&other.feed_url))
+            .then_with(|| self.entry_id.cmp(&other.entry_id))
+    }
+}
+
+impl PartialOrd for EntryCursor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Conditions to narrow down entries
+#[derive(Debug, Clone, Default)]
+pub struct EntryFilter {
+    /// Return only entries of these feeds
+    pub feed_urls: Option<Vec<types::FeedUrl>>,
+    /// Return only entries published at or after this time
+    pub published_after: Option<Time>,
+    /// Return only entries published before this time
+    pub published_before: Option<Time>,
+    /// Return only entries whose title or summary contains this keyword.
+    /// If the entry has no summary, its content is matched instead.
+    /// Matching is case insensitive
+    pub keyword: Option<String>,
+}
+
+impl EntryFilter {
+    fn matches_feed(&self, feed_url: &str) -> bool {
+        self.feed_urls
+            .as_ref()
+            .map_or(true, |urls| urls.iter().any(|url| url == feed_url))
+    }
+
+    fn matches_entry(&self, entry: &ArchivedEntry, keyword: Option<&str>) -> bool {
+        let published = entry.published.or(entry.updated);
+        if let Some(after) = self.published_after {
+            if published.map_or(true, |published| published < after) {
+                return false;
+            }
+        }
+        if let Some(before) = self.published_before {
+            if published.map_or(true, |published| published >= before) {
+                return false;
+            }
+        }
+        if let Some(keyword) = keyword {
+            let contains =
+                |text: Option<&str>| text.is_some_and(|text| text.to_lowercase().contains(keyword));
+            let summary = entry.summary.as_deref().or(entry.content.as_deref());
+            if !contains(entry.title.as_deref()) && !contains(summary) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+struct PageRange {
+    after: Option<EntryCursor>,
+    before: Option<EntryCursor>,
+    first: Option<usize>,
+    last: Option<usize>,
+}
+
+struct Page<T> {
+    entries: Vec<(T, EntryCursor)>,
+    has_previous_page: bool,
+    has_next_page: bool,
+}
+
+/// Sort entries and return the entries in the range.
+/// Pages are determined by comparing sort keys so that pagination is not affected
+/// even if the cursor entry no longer exists
+fn paginate<T>(mut entries: Vec<(T, EntryCursor)>, range: &PageRange) -> Page<T> {
+    entries.sort_unstable_by(|(_, a), (_, b)| a.cmp(b));
+
+    let mut start = range.after.as_ref().map_or(0, |after| {
+        entries.partition_point(|(_, cursor)| cursor <= after)
+    });
+    let mut end = range.before.as_ref().map_or(entries.len(), |before| {
+        entries.partition_point(|(_, cursor)| cursor < before)
+    });
+    end = end.max(start);
+
+    if let Some(first) = range.first {
+        end = end.min(start.saturating_add(first));
+    }
+    if let Some(last) = range.last {
+        start = start.max(end.saturating_sub(last));
+    }
+
+    let has_previous_page = start > 0;
+    let has_next_page = end < entries.len();
+
+    entries.truncate(end);
+    let entries = entries.split_off(start);
+
+    Page {
+        entries,
+        has_previous_page,
+        has_next_page,
+    }
+}
+
+#[derive(Debug)]
 pub struct FetchEntriesInput {
-    pub after: Option<EntryId<'static>>,
-    pub first: usize,
+    /// Return entries after this cursor(exclusive)
+    pub after: Option<EntryCursor>,
+    /// Return entries before this cursor(exclusive)
+    pub before: Option<EntryCursor>,
+    /// Return the first n entries in the range
+    pub first: Option<usize>,
+    /// Return the last n entries in the range
+    pub last: Option<usize>,
+    pub filter: SubscriptionFilter,
+    pub entry_filter: EntryFilter,
 }
 
 #[derive(Default)]
 pub struct FetchEntriesOutput {
-    pub entries: Vec<(types::Entry, types::FeedUrl)>,
+    pub entries: Vec<(ArchivedEntry, EntryCursor)>,
     pub feeds: HashMap<types::FeedUrl, types::FeedMeta>,
+    pub has_previous_page: bool,
+    pub has_next_page: bool,
 }
 
 #[derive(Error, Debug)]
@@ -43,6 +200,8 @@ impl Usecase for FetchEntries {
         Self {
             repository: make.subscription_repo.clone(),
             fetch_feed: make.fetch_feed.clone(),
+            archive_repository: make.archive_repo.clone(),
+            entry_retention: make.entry_retention,
         }
     }
 
@@ -54,41 +213,55 @@ impl Usecase for FetchEntries {
         Ok(principal)
     }
 
+    fn consumption<'a>(&self, input: &'a Self::Input) -> Consumption<'a> {
+        Consumption {
+            entries_page_size: input.first.max(input.last),
+            ..Default::default()
+        }
+    }
+
     #[tracing::instrument(name = "fetch_entries", skip(self, principal))]
     async fn usecase(
         &self,
         Input {
             principal,
-            input: FetchEntriesInput { after, first },
+            input:
+                FetchEntriesInput {
+                    after,
+                    before,
+                    first,
+                    last,
+                    filter,
+                    entry_filter,
+                },
         }: Input<Self::Input>,
     ) -> Result<Output<Self::Output>, Error<Self::Error>> {
         let user_id = principal
             .user_id()
             .expect("user id not found. this is a bug");
 
-        let urls = self.repository.fetch_subscribed_feed_urls(user_id).await?;
+        let urls = self
+            .repository
+            .fetch_subscriptions(user_id)
+            .await?
+            .into_iter()
+            .filter(|subscription| !subscription.paused && filter.matches(subscription))
+            .filter(|subscription| entry_filter.matches_feed(&subscription.url))
+            .map(|subscription| subscription.url)
+            .collect::<Vec<_>>();
 
-        let mut feed_metas = HashMap::new();
-        let mut entries = Vec::with_capacity(urls.len() * 2);
-        let mut handle_feed = |feed: Result<Arc<types::Feed>, FetchFeedError>| {
-            let feed = match feed {
-                Ok(feed) => feed,
-                Err(err) => {
-                    tracing::warn!("Failed to fetch feed {err:?}");
-                    return;
-                }
+        let keyword = entry_filter
+            .keyword
+            .as_deref()
+            .map(str::to_lowercase)
+            .filter(|keyword| !keyword.is_empty());
+        let mut feeds = Vec::with_capacity(urls.len());
+        let mut handle_feed =
+            |(url, feed): (String, Result<Arc<types::Feed>, FetchFeedError>)| match feed {
+                Ok(feed) => feeds.push((url, feed)),
+                Err(err) => tracing::warn!("Failed to fetch feed {err:?}"),
             };
 
-            let meta = feed.meta().clone();
-            let feed_url = meta.url().to_owned();
-            feed_metas.insert(feed_url.clone(), meta);
-            entries.extend(
-                feed.entries()
-                    .cloned()
-                    .map(|entry| (entry, feed_url.clone())),
-            );
-        };
-
         let mut tasks = FuturesUnordered::new();
         let in_flight_limit = 10;
 
@@ -100,49 +273,237 @@ impl Usecase for FetchEntries {
             }
 
             let fetch_feed = Arc::clone(&self.fetch_feed);
-            tasks.push(async move { fetch_feed.fetch_feed(url.clone()).await });
+            tasks.push(async move {
+                let feed = fetch_feed.fetch_feed(url.clone()).await;
+                (url, feed)
+            });
         }
 
         while let Some(result) = tasks.next().await {
             handle_feed(result);
         }
 
-        // Sort by published or updated
-        entries.sort_unstable_by(|(a, _), (b, _)| {
-            match (a.published().or(a.updated()), b.published().or(b.updated())) {
-                (Some(a), Some(b)) => b.cmp(&a),
-                (None, Some(_)) => Ordering::Greater,
-                (Some(_), None) => Ordering::Less,
-                (None, None) => std::cmp::Ordering::Equal,
-            }
-        });
-
-        // Paginate
-        let entries = {
-            let start = after
-                .and_then(|after| {
-                    entries
-                        .iter()
-                        .position(|(entry, _)| entry.id_ref() == after)
-                        .map(|position| position + 1)
-                })
-                .unwrap_or(0);
-
-            if start >= entries.len() {
-                return Ok(Output {
-                    output: Self::Output::default(),
-                });
-            }
-            let mut entries = entries.split_off(start);
-            entries.truncate(first);
-            entries
-        };
+        // Serve the archived entries so that entries which the feed no longer lists are also
+        // returned. The archive is updated when feeds are fetched, so current entries are merged
+        // without writing in case the archive failed or has not caught up yet.
+        // Entries of feeds which could not be fetched are not returned since feed meta is required.
+        // Entries are keyed by the subscribed url so that they match the feed filter and cursors
+        let now = Utc::now();
+        let retain_since = archive::retain_since(self.entry_retention, now);
+        let mut feed_metas = HashMap::with_capacity(feeds.len());
+        let mut entries = Vec::with_capacity(feeds.len() * 2);
+
+        for (url, feed) in feeds {
+            let meta = feed.meta();
+            let fresh = feed
+                .entries()
+                .map(|entry| ArchivedEntry::new(&url, meta, entry, now))
+                .collect::<Vec<_>>();
+
+            let archived = match self.archive_repository.fetch_archived_entries(&url).await {
+                Ok(mut archived) => {
+                    merge_entries(&mut archived, fresh, retain_since);
+                    archived
+                }
+                Err(err) => {
+                    tracing::warn!(feed_url = url, "Failed to fetch archived entries {err:?}");
+                    fresh
+                }
+            };
+
+            entries.extend(
+                archived
+                    .into_iter()
+                    .filter(|entry| entry_filter.matches_entry(entry, keyword.as_deref()))
+                    .map(|entry| {
+                        let cursor = EntryCursor::new(&entry);
+                        (entry, cursor)
+                    }),
+            );
+            feed_metas.insert(url, meta.clone());
+        }
+
+        let Page {
+            entries,
+            has_previous_page,
+            has_next_page,
+        } = paginate(
+            entries,
+            &PageRange {
+                after,
+                before,
+                first,
+                last,
+            },
+        );
 
         Ok(Output {
             output: FetchEntriesOutput {
                 entries,
                 feeds: feed_metas,
+                has_previous_page,
+                has_next_page,
             },
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone as _;
+
+    use super::*;
+
+    fn cursor(published: Option<i64>, feed_url: &str, entry_id: &str) -> EntryCursor {
+        EntryCursor {
+            published: published.map(|secs| Utc.timestamp_opt(secs, 0).unwrap()),
+            feed_url: feed_url.into(),
+            entry_id: entry_id.into(),
+        }
+    }
+
+    /// Entries which share published time are ordered by feed url and entry id
+    fn entries() -> Vec<((), EntryCursor)> {
+        vec![
+            ((), cursor(Some(100), "b", "1")),
+            ((), cursor(None, "a", "1")),
+            ((), cursor(Some(200), "a", "1")),
+            ((), cursor(Some(100), "a", "2")),
+            ((), cursor(Some(100), "a", "1")),
+        ]
+    }
+
+    fn ids(page: &Page<()>) -> Vec<(Option<i64>, &str, &str)> {
+        page.entries
+            .iter()
+            .map(|(_, c)| {
+                (
+                    c.published.map(|p| p.timestamp()),
+                    c.feed_url.as_str(),
+                    c.entry_id.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    fn range(
+        after: Option<EntryCursor>,
+        before: Option<EntryCursor>,
+        first: Option<usize>,
+        last: Option<usize>,
+    ) -> PageRange {
+        PageRange {
+            after,
+            before,
+            first,
+            last,
+        }
+    }
+
+    #[test]
+    fn paginate_forward() {
+        let page = paginate(entries(), &range(None, None, Some(2), None));
+        assert_eq!(
+            ids(&page),
+            vec![(Some(200), "a", "1"), (Some(100), "a", "1")]
+        );
+        assert!(!page.has_previous_page);
+        assert!(page.has_next_page);
+
+        let after = page.entries.last().unwrap().1.clone();
+        let page = paginate(entries(), &range(Some(after), None, Some(2), None));
+        assert_eq!(
+            ids(&page),
+            vec![(Some(100), "a", "2"), (Some(100), "b", "1")]
+        );
+        assert!(page.has_previous_page);
+        assert!(page.has_next_page);
+
+        let after = page.entries.last().unwrap().1.clone();
+        let page = paginate(entries(), &range(Some(after), None, Some(2), None));
+        assert_eq!(ids(&page), vec![(None, "a", "1")]);
+        assert!(!page.has_next_page);
+    }
+
+    #[test]
+    fn paginate_backward() {
+        let page = paginate(entries(), &range(None, None, None, Some(2)));
+        assert_eq!(ids(&page), vec![(Some(100), "b", "1"), (None, "a", "1")]);
+        assert!(page.has_previous_page);
+        assert!(!page.has_next_page);
+
+        let before = page.entries.first().unwrap().1.clone();
+        let page = paginate(entries(), &range(None, Some(before), None, Some(2)));
+        assert_eq!(
+            ids(&page),
+            vec![(Some(100), "a", "1"), (Some(100), "a", "2")]
+        );
+        assert!(page.has_previous_page);
+        assert!(page.has_next_page);
+    }
+
+    fn archived(feed_url: &str, published: Option<i64>, title: &str) -> ArchivedEntry {
+        ArchivedEntry {
+            feed_url: feed_url.into(),
+            entry_id: "1".into(),
+            title: Some(title.into()),
+            summary: Some("Summary".into()),
+            content: None,
+            website_url: None,
+            published: published.map(|secs| Utc.timestamp_opt(secs, 0).unwrap()),
+            updated: None,
+            archived_at: Utc.timestamp_opt(0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn entry_filter_feed_urls() {
+        let filter = EntryFilter::default();
+        assert!(filter.matches_feed("https://a.example.com/feed.xml"));
+
+        let filter = EntryFilter {
+            feed_urls: Some(vec!["https://a.example.com/feed.xml".into()]),
+            ..Default::default()
+        };
+        assert!(filter.matches_feed("https://a.example.com/feed.xml"));
+        assert!(!filter.matches_feed("https://b.example.com/feed.xml"));
+    }
+
+    #[test]
+    fn entry_filter_published_range() {
+        let filter = EntryFilter {
+            published_after: Some(Utc.timestamp_opt(100, 0).unwrap()),
+            published_before: Some(Utc.timestamp_opt(200, 0).unwrap()),
+            ..Default::default()
+        };
+        let matches = |published| filter.matches_entry(&archived("a", published, "title"), None);
+
+        assert!(!matches(Some(99)));
+        // After is inclusive and before is exclusive
+        assert!(matches(Some(100)));
+        assert!(matches(Some(199)));
+        assert!(!matches(Some(200)));
+        // Entries without published time are excluded when range is specified
+        assert!(!matches(None));
+        assert!(EntryFilter::default().matches_entry(&archived("a", None, "title"), None));
+    }
+
+    #[test]
+    fn entry_filter_keyword() {
+        let filter = EntryFilter::default();
+        let entry = archived("a", Some(100), "Rust Release");
+
+        // Keyword is lowercased by the caller
+        assert!(filter.matches_entry(&entry, Some("release")));
+        assert!(filter.matches_entry(&entry, Some("summary")));
+        assert!(!filter.matches_entry(&entry, Some("go")));
+    }
+
+    #[test]
+    fn paginate_with_removed_cursor_entry() {
+        // Cursor entry does not exist, but the position is determined by the sort key
+        let after = cursor(Some(100), "a", "15");
+        let page = paginate(entries(), &range(Some(after), None, Some(1), None));
+        assert_eq!(ids(&page), vec![(Some(100), "a", "2")]);
+    }
+}
@@ -5,7 +5,10 @@ use thiserror::Error;
 
 use crate::{
     principal::Principal,
-    repository::SubscriptionRepository,
+    repository::{
+        types::{Subscription, SubscriptionFilter},
+        SubscriptionRepository,
+    },
     usecase::{authorize::Unauthorized, Error, Input, MakeUsecase, Output, Usecase},
 };
 
@@ -17,11 +20,12 @@ pub struct FetchSubscribedFeeds {
 pub struct FetchSubscribedFeedsInput {
     pub after: Option<String>,
     pub first: usize,
+    pub filter: SubscriptionFilter,
 }
 
 #[derive(Default)]
 pub struct FetchSubscribedFeedsOutput {
-    pub feeds: Vec<Arc<types::Feed>>,
+    pub feeds: Vec<(Arc<types::Feed>, Subscription)>,
 }
 
 #[derive(Error, Debug)]
@@ -53,40 +57,67 @@ impl Usecase for FetchSubscribedFeeds {
         &self,
         Input {
             principal,
-            input: FetchSubscribedFeedsInput { after, first },
+            input:
+                FetchSubscribedFeedsInput {
+                    after,
+                    first,
+                    filter,
+                },
         }: Input<Self::Input>,
     ) -> Result<Output<Self::Output>, Error<Self::Error>> {
         let user_id = principal.user_id().unwrap();
 
-        // fetch all urls from repository
-        let urls = self.repository.fetch_subscribed_feed_urls(user_id).await?;
+        // fetch all subscriptions from repository
+        let subscriptions = self
+            .repository
+            .fetch_subscriptions(user_id)
+            .await?
+            .into_iter()
+            .filter(|subscription| filter.matches(subscription))
+            .collect::<Vec<_>>();
 
         // paginate
-        let urls = {
+        let subscriptions = {
             let start = after
-                .and_then(|after| urls.iter().position(|url| url == &after).map(|p| p + 1))
+                .and_then(|after| {
+                    subscriptions
+                        .iter()
+                        .position(|subscription| subscription.url == after)
+                        .map(|p| p + 1)
+                })
                 .unwrap_or(0);
-            if start >= urls.len() {
+            if start >= subscriptions.len() {
                 return Ok(Output {
                     output: FetchSubscribedFeedsOutput::default(),
                 });
             }
-            let urls = &urls[start..];
-            let end = (start + first).min(urls.len());
-            &urls[..end]
+            let subscriptions = &subscriptions[start..];
+            let end = first.min(subscriptions.len());
+            &subscriptions[..end]
         };
 
         // fetch feeds
-        let feeds = self.fetch_feed.fetch_feeds_parallel(urls).await;
+        let urls = subscriptions
+            .iter()
+            .map(|subscription| subscription.url.clone())
+            .collect::<Vec<_>>();
+        let feeds = self.fetch_feed.fetch_feeds_parallel(&urls).await;
 
         // TODO: return failed feeds
-        let (feeds, errors): (Vec<_>, Vec<_>) = feeds.into_iter().partition(Result::is_ok);
+        let (feeds, errors): (Vec<_>, Vec<_>) = feeds
+            .into_iter()
+            .zip(subscriptions.iter().cloned())
+            .partition(|(feed, _)| feed.is_ok());
 
         if !errors.is_empty() {
+            let errors = errors.into_iter().map(|(err, _)| err).collect::<Vec<_>>();
             tracing::error!("{errors:?}");
         }
 
-        let feeds = feeds.into_iter().map(Result::unwrap).collect();
+        let feeds = feeds
+            .into_iter()
+            .map(|(feed, subscription)| (feed.unwrap(), subscription))
+            .collect();
 
         Ok(Output {
             output: FetchSubscribedFeedsOutput { feeds },
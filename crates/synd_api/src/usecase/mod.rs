@@ -3,9 +3,25 @@ pub use subscribe_feed::{
     SubscribeFeed, SubscribeFeedError, SubscribeFeedInput, SubscribeFeedOutput,
 };
 
+mod subscribe_feeds;
+pub use subscribe_feeds::{
+    SubscribeFeeds, SubscribeFeedsInput, SubscribeFeedsOutput, SubscribeFeedsResult,
+};
+
 mod unsubscribe_feed;
 pub use unsubscribe_feed::{UnsubscribeFeed, UnsubscribeFeedInput, UnsubscribeFeedOutput};
 
+mod unsubscribe_feeds;
+pub use unsubscribe_feeds::{
+    UnsubscribeFeeds, UnsubscribeFeedsInput, UnsubscribeFeedsOutput, UnsubscribeFeedsResult,
+    UnsubscribeStatus,
+};
+
+mod update_subscription;
+pub use update_subscription::{
+    UpdateSubscription, UpdateSubscriptionError, UpdateSubscriptionInput, UpdateSubscriptionOutput,
+};
+
 mod fetch_subscribed_feeds;
 pub use fetch_subscribed_feeds::{
     FetchSubscribedFeeds, FetchSubscribedFeedsError, FetchSubscribedFeedsInput,
@@ -13,26 +29,76 @@ pub use fetch_subscribed_feeds::{
 };
 
 mod fetch_entries;
-pub use fetch_entries::{FetchEntries, FetchEntriesError, FetchEntriesInput, FetchEntriesOutput};
+pub use fetch_entries::{
+    EntryCursor, EntryFilter, FetchEntries, FetchEntriesError, FetchEntriesInput,
+    FetchEntriesOutput,
+};
+
+mod watch_new_entries;
+pub use watch_new_entries::{
+    WatchNewEntries, WatchNewEntriesError, WatchNewEntriesInput, WatchNewEntriesOutput,
+};
+
+mod search_entries;
+pub use search_entries::{
+    SearchEntries, SearchEntriesError, SearchEntriesInput, SearchEntriesOutput,
+};
+
+mod export_my_data;
+pub use export_my_data::{ExportMyData, ExportMyDataInput, ExportMyDataOutput};
+
+mod request_account_deletion;
+pub use request_account_deletion::{
+    RequestAccountDeletion, RequestAccountDeletionInput, RequestAccountDeletionOutput,
+};
+
+mod delete_my_account;
+pub use delete_my_account::{
+    DeleteMyAccount, DeleteMyAccountError, DeleteMyAccountInput, DeleteMyAccountOutput,
+    DeletionTokens,
+};
+
+mod create_access_token;
+pub use create_access_token::{
+    CreateAccessToken, CreateAccessTokenError, CreateAccessTokenInput, CreateAccessTokenOutput,
+};
+
+mod fetch_access_tokens;
+pub use fetch_access_tokens::{FetchAccessTokens, FetchAccessTokensInput, FetchAccessTokensOutput};
+
+mod revoke_access_token;
+pub use revoke_access_token::{
+    RevokeAccessToken, RevokeAccessTokenError, RevokeAccessTokenInput, RevokeAccessTokenOutput,
+};
+
+pub mod admin;
 
 use tracing::error;
 
 pub mod authorize;
-use std::{future::Future, sync::Arc};
+use std::{future::Future, sync::Arc, time::Duration};
 
 use synd_feed::feed::cache::FetchCachedFeed;
 use synd_o11y::{audit, metric, tracing_subscriber::audit::Audit};
 
 use crate::{
     principal::Principal,
-    repository::{RepositoryError, SubscriptionRepository},
+    realtime::EntryBroadcaster,
+    repository::{EntryArchiveRepository, RepositoryError, SubscriptionRepository, UserRepository},
+    search::SearchIndex,
 };
 
-use self::authorize::{Authorized, Authorizer, Unauthorized};
+use self::authorize::{Authorized, Authorizer, Consumption, QuotaExceeded, Unauthorized};
 
 pub struct MakeUsecase {
     pub subscription_repo: Arc<dyn SubscriptionRepository>,
+    pub user_repo: Arc<dyn UserRepository>,
     pub fetch_feed: Arc<dyn FetchCachedFeed>,
+    pub search_index: Arc<SearchIndex>,
+    pub archive_repo: Arc<dyn EntryArchiveRepository>,
+    /// Archived entries older than this are purged
+    pub entry_retention: Duration,
+    pub broadcaster: EntryBroadcaster,
 }
 
 impl MakeUsecase {
@@ -58,6 +124,8 @@ pub enum Error<T> {
     Unauthorized(Unauthorized),
     #[error("repository error")]
     Repository(#[from] RepositoryError),
+    #[error(transparent)]
+    QuotaExceeded(QuotaExceeded),
 }
 
 pub trait Usecase {
@@ -80,6 +148,11 @@ pub trait Usecase {
         input: &Self::Input,
     ) -> impl Future<Output = Result<Principal, Unauthorized>>;
 
+    /// Resources consumed by given input
+    fn consumption<'a>(&self, _input: &'a Self::Input) -> Consumption<'a> {
+        Consumption::default()
+    }
+
     /// Usecase entrypoint
     fn usecase(
         &self,
@@ -100,6 +173,10 @@ impl Runtime {
         }
     }
 
+    pub fn make_usecase(&self) -> &MakeUsecase {
+        &self.make_usecase
+    }
+
     pub async fn run<Uc, Cx, In>(
         &self,
         cx: Cx,
@@ -126,9 +203,13 @@ impl Runtime {
 
         let principal = match self.authorizer.authorize(principal, &uc, &input).await {
             Ok(authorized_principal) => authorized_principal,
-            Err(unauthorized) => {
-                audit!({ Audit::RESULT } = "unauthorized");
-                return Err(Error::Unauthorized(unauthorized));
+            Err(err) => {
+                let result = match err {
+                    Error::QuotaExceeded(_) => "quota_exceeded",
+                    _ => "unauthorized",
+                };
+                audit!({ Audit::RESULT } = result);
+                return Err(err);
             }
         };
 
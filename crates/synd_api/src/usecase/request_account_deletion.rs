@@ -0,0 +1,73 @@
+use synd_feed::types::Time;
+
+use crate::{
+    principal::Principal,
+    usecase::{
+        authorize::{Consumption, Unauthorized},
+        DeletionTokens, Error, Input, MakeUsecase, Output, Usecase,
+    },
+};
+
+pub struct RequestAccountDeletion {
+    pub tokens: DeletionTokens,
+}
+
+pub struct RequestAccountDeletionInput {}
+
+pub struct RequestAccountDeletionOutput {
+    /// Token to be passed to `DeleteMyAccount`
+    pub confirmation_token: String,
+    pub expires_at: Time,
+}
+
+impl Usecase for RequestAccountDeletion {
+    type Input = RequestAccountDeletionInput;
+
+    type Output = RequestAccountDeletionOutput;
+
+    type Error = anyhow::Error;
+
+    fn new(make: &MakeUsecase) -> Self {
+        Self {
+            tokens: DeletionTokens::new(make.user_repo.clone()),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &Self::Input,
+    ) -> Result<Principal, Unauthorized> {
+        // Deleting account requires interactive login
+        if principal.is_token() {
+            Err(Unauthorized)
+        } else {
+            Ok(principal)
+        }
+    }
+
+    fn consumption<'a>(&self, _: &'a Self::Input) -> Consumption<'a> {
+        Consumption {
+            mutation: true,
+            ..Default::default()
+        }
+    }
+
+    async fn usecase(
+        &self,
+        Input { principal, .. }: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, Error<Self::Error>> {
+        let user_id = principal
+            .user_id()
+            .expect("user id not found. this is a bug");
+
+        let (confirmation_token, expires_at) = self.tokens.issue(user_id).await?;
+
+        Ok(Output {
+            output: RequestAccountDeletionOutput {
+                confirmation_token,
+                expires_at,
+            },
+        })
+    }
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use synd_o11y::{audit, tracing_subscriber::audit::Audit};
+use thiserror::Error;
+
+use crate::{
+    principal::Principal,
+    repository::UserRepository,
+    usecase::{
+        authorize::{Consumption, Unauthorized},
+        Error, Input, MakeUsecase, Output, Usecase,
+    },
+};
+
+pub struct RevokeAccessToken {
+    pub repository: Arc<dyn UserRepository>,
+}
+
+pub struct RevokeAccessTokenInput {
+    pub id: String,
+}
+
+pub struct RevokeAccessTokenOutput {}
+
+#[derive(Error, Debug)]
+pub enum RevokeAccessTokenError {
+    #[error("access token not found")]
+    NotFound,
+}
+
+impl Usecase for RevokeAccessToken {
+    type Input = RevokeAccessTokenInput;
+
+    type Output = RevokeAccessTokenOutput;
+
+    type Error = RevokeAccessTokenError;
+
+    fn new(make: &MakeUsecase) -> Self {
+        Self {
+            repository: make.user_repo.clone(),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &Self::Input,
+    ) -> Result<Principal, Unauthorized> {
+        if principal.is_token() {
+            Err(Unauthorized)
+        } else {
+            Ok(principal)
+        }
+    }
+
+    fn consumption<'a>(&self, _: &'a Self::Input) -> Consumption<'a> {
+        Consumption {
+            mutation: true,
+            ..Default::default()
+        }
+    }
+
+    async fn usecase(
+        &self,
+        Input {
+            principal,
+            input: RevokeAccessTokenInput { id },
+        }: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, Error<Self::Error>> {
+        let user_id = principal
+            .user_id()
+            .expect("user id not found. this is a bug");
+
+        if !self.repository.delete_access_token(user_id, &id).await? {
+            return Err(Error::Usecase(RevokeAccessTokenError::NotFound));
+        }
+
+        audit!({ Audit::RESOURCE } = id.as_str());
+
+        Ok(Output {
+            output: RevokeAccessTokenOutput {},
+        })
+    }
+}
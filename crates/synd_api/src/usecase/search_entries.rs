@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use crate::{
+    principal::Principal,
+    repository::SubscriptionRepository,
+    search::{SearchError, SearchHit, SearchIndex},
+    usecase::{
+        authorize::{Consumption, Unauthorized},
+        Error, Input, MakeUsecase, Output, Usecase,
+    },
+};
+
+pub struct SearchEntries {
+    pub repository: Arc<dyn SubscriptionRepository>,
+    pub search_index: Arc<SearchIndex>,
+}
+
+#[derive(Debug)]
+pub struct SearchEntriesInput {
+    pub query: String,
+    /// Number of hits to skip
+    pub offset: usize,
+    /// Number of hits to return
+    pub first: usize,
+}
+
+pub struct SearchEntriesOutput {
+    /// Hits ordered by relevance
+    pub hits: Vec<SearchHit>,
+    pub has_next_page: bool,
+}
+
+#[derive(Error, Debug)]
+pub enum SearchEntriesError {
+    #[error(transparent)]
+    Search(#[from] SearchError),
+    #[error("search offset must be at most {}", SearchEntries::MAX_OFFSET)]
+    OffsetTooLarge,
+}
+
+impl SearchEntries {
+    /// Maximum number of hits to skip.
+    /// The index allocates memory in proportion to the offset, so deep pagination is rejected
+    pub const MAX_OFFSET: usize = 1000;
+}
+
+impl Usecase for SearchEntries {
+    type Input = SearchEntriesInput;
+
+    type Output = SearchEntriesOutput;
+
+    type Error = SearchEntriesError;
+
+    fn new(make: &MakeUsecase) -> Self {
+        Self {
+            repository: make.subscription_repo.clone(),
+            search_index: make.search_index.clone(),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &Self::Input,
+    ) -> Result<Principal, Unauthorized> {
+        Ok(principal)
+    }
+
+    fn consumption<'a>(&self, input: &'a Self::Input) -> Consumption<'a> {
+        Consumption {
+            entries_page_size: Some(input.first),
+            ..Default::default()
+        }
+    }
+
+    #[tracing::instrument(name = "search_entries", skip(self, principal))]
+    async fn usecase(
+        &self,
+        Input {
+            principal,
+            input:
+                SearchEntriesInput {
+                    query,
+                    offset,
+                    first,
+                },
+        }: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, Error<Self::Error>> {
+        let user_id = principal
+            .user_id()
+            .ok_or(Error::Unauthorized(Unauthorized))?;
+
+        if offset > Self::MAX_OFFSET {
+            return Err(Error::Usecase(SearchEntriesError::OffsetTooLarge));
+        }
+
+        let urls = self.repository.fetch_subscribed_feed_urls(user_id).await?;
+
+        // Search one more hit to know whether the next page exists
+        let limit = first.saturating_add(1);
+        let search_index = Arc::clone(&self.search_index);
+        let mut hits =
+            tokio::task::spawn_blocking(move || search_index.search(&query, &urls, offset, limit))
+                .await
+                .map_err(SearchError::from)
+                .and_then(|result| result)
+                .map_err(|err| Error::Usecase(err.into()))?;
+
+        let has_next_page = hits.len() > first;
+        hits.truncate(first);
+
+        Ok(Output {
+            output: SearchEntriesOutput {
+                hits,
+                has_next_page,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        principal::User, repository::memory::MemoryRepository, usecase::authorize::Authorized,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn reject_too_large_offset() {
+        let dir = tempfile::tempdir().unwrap();
+        let usecase = SearchEntries {
+            repository: Arc::new(MemoryRepository::new()),
+            search_index: Arc::new(SearchIndex::open(dir.path().join("index")).unwrap()),
+        };
+        let search = |offset| {
+            usecase.usecase(Input {
+                principal: Authorized::new_for_test(Principal::User(User::new(
+                    "user",
+                    "user@example.com",
+                ))),
+                input: SearchEntriesInput {
+                    query: "rust".into(),
+                    offset,
+                    first: 10,
+                },
+            })
+        };
+
+        assert!(search(SearchEntries::MAX_OFFSET).await.is_ok());
+        for offset in [SearchEntries::MAX_OFFSET + 1, 1_000_000_000_000, usize::MAX] {
+            assert!(matches!(
+                search(offset).await,
+                Err(Error::Usecase(SearchEntriesError::OffsetTooLarge))
+            ));
+        }
+    }
+}
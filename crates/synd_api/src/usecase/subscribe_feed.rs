@@ -13,7 +13,10 @@ use crate::{
     usecase::{Input, Output},
 };
 
-use super::{authorize::Unauthorized, Usecase};
+use super::{
+    authorize::{Consumption, Unauthorized},
+    Usecase,
+};
 
 pub struct SubscribeFeed {
     pub repository: Arc<dyn SubscriptionRepository>,
@@ -56,6 +59,14 @@ impl Usecase for SubscribeFeed {
         Ok(principal)
     }
 
+    fn consumption<'a>(&self, input: &'a SubscribeFeedInput) -> Consumption<'a> {
+        Consumption {
+            mutation: true,
+            subscribe_urls: vec![input.url.as_str()],
+            ..Default::default()
+        }
+    }
+
     async fn usecase(
         &self,
         Input {
@@ -0,0 +1,197 @@
+use std::{collections::HashSet, sync::Arc};
+
+use futures_util::{stream, StreamExt};
+use synd_feed::{feed::cache::FetchCachedFeed, types::Feed};
+use synd_o11y::metric;
+
+use crate::{
+    principal::Principal,
+    repository::SubscriptionRepository,
+    usecase::{Input, Output, SubscribeFeedError},
+};
+
+use super::{
+    authorize::{Consumption, Unauthorized},
+    Usecase,
+};
+
+pub struct SubscribeFeeds {
+    pub repository: Arc<dyn SubscriptionRepository>,
+    pub fetch_feed: Arc<dyn FetchCachedFeed>,
+}
+
+pub struct SubscribeFeedsInput {
+    pub urls: Vec<String>,
+}
+
+pub struct SubscribeFeedsOutput {
+    /// Results in the order of given urls. Duplicated urls are removed
+    pub results: Vec<SubscribeFeedsResult>,
+}
+
+pub struct SubscribeFeedsResult {
+    pub url: String,
+    pub result: Result<Arc<Feed>, SubscribeFeedError>,
+}
+
+impl SubscribeFeeds {
+    const FETCH_CONCURRENCY: usize = 10;
+}
+
+impl Usecase for SubscribeFeeds {
+    type Input = SubscribeFeedsInput;
+
+    type Output = SubscribeFeedsOutput;
+
+    type Error = anyhow::Error;
+
+    fn new(make: &super::MakeUsecase) -> Self {
+        Self {
+            repository: make.subscription_repo.clone(),
+            fetch_feed: make.fetch_feed.clone(),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &SubscribeFeedsInput,
+    ) -> Result<Principal, Unauthorized> {
+        Ok(principal)
+    }
+
+    fn consumption<'a>(&self, input: &'a Self::Input) -> Consumption<'a> {
+        Consumption {
+            mutation: true,
+            subscribe_urls: input.urls.iter().map(String::as_str).collect(),
+            ..Default::default()
+        }
+    }
+
+    async fn usecase(
+        &self,
+        Input {
+            principal,
+            input: SubscribeFeedsInput { mut urls },
+            ..
+        }: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, super::Error<Self::Error>> {
+        let mut seen = HashSet::new();
+        urls.retain(|url| seen.insert(url.clone()));
+
+        tracing::debug!("Subscribe {} feeds", urls.len());
+
+        let fetch_feed = &self.fetch_feed;
+        let results = stream::iter(urls)
+            .map(|url| async move {
+                let result = fetch_feed
+                    .fetch_feed(url.clone())
+                    .await
+                    .map_err(SubscribeFeedError::FetchFeed);
+                SubscribeFeedsResult { url, result }
+            })
+            .buffered(Self::FETCH_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await;
+
+        let subscribe_urls = results
+            .iter()
+            .filter(|result| result.result.is_ok())
+            .map(|result| result.url.clone())
+            .collect::<Vec<_>>();
+        let subscribed = subscribe_urls.len();
+
+        if !subscribe_urls.is_empty() {
+            self.repository
+                .put_feed_subscriptions(principal.user_id().unwrap(), subscribe_urls)
+                .await?;
+        }
+
+        metric!(monotonic_counter.feed.subscription = subscribed);
+
+        Ok(Output {
+            output: SubscribeFeedsOutput { results },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use synd_feed::feed::{
+        cache::CacheLayer,
+        parser::{FeedService, FetchFeed, FetchFeedError, FetchFeedResult},
+    };
+
+    use crate::{
+        principal::User,
+        repository::memory::MemoryRepository,
+        usecase::{authorize::Authorized, Input},
+    };
+
+    use super::*;
+
+    /// Return an empty feed unless the url contains "invalid"
+    #[derive(Clone)]
+    struct StubFeed;
+
+    #[async_trait]
+    impl FetchFeed for StubFeed {
+        async fn fetch_feed(&self, url: String) -> FetchFeedResult<Feed> {
+            if url.contains("invalid") {
+                return Err(FetchFeedError::Other(anyhow::anyhow!("invalid feed")));
+            }
+            let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel>
+                <title>feed</title><link>https://example.com</link></channel></rss>"#;
+            FeedService::new("synd-test", 1024 * 1024).parse(url, rss.as_bytes())
+        }
+
+        async fn fetch_feeds_parallel(&self, urls: &[String]) -> FetchFeedResult<Vec<Feed>> {
+            let mut feeds = Vec::with_capacity(urls.len());
+            for url in urls {
+                feeds.push(self.fetch_feed(url.clone()).await?);
+            }
+            Ok(feeds)
+        }
+    }
+
+    #[tokio::test]
+    async fn subscribe_valid_urls_once() {
+        let repository = Arc::new(MemoryRepository::new());
+        let usecase = SubscribeFeeds {
+            repository: repository.clone(),
+            fetch_feed: Arc::new(CacheLayer::new(StubFeed)),
+        };
+        let a = "https://a.example.com/feed.xml";
+        let b = "https://b.example.com/feed.xml";
+        let invalid = "https://invalid.example.com/feed.xml";
+
+        let Output {
+            output: SubscribeFeedsOutput { results },
+        } = usecase
+            .usecase(Input {
+                principal: Authorized::new_for_test(Principal::User(User::new(
+                    "user",
+                    "user@example.com",
+                ))),
+                input: SubscribeFeedsInput {
+                    urls: vec![a.into(), invalid.into(), a.into(), b.into()],
+                },
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| (result.url.as_str(), result.result.is_ok()))
+                .collect::<Vec<_>>(),
+            vec![(a, true), (invalid, false), (b, true)]
+        );
+
+        let subscribed = repository.fetch_subscribed_feed_urls("user").await.unwrap();
+        assert_eq!(subscribed.iter().filter(|url| *url == a).count(), 1);
+        assert_eq!(subscribed.iter().filter(|url| *url == b).count(), 1);
+        assert!(!subscribed.iter().any(|url| url == invalid));
+    }
+}
@@ -8,7 +8,10 @@ use crate::{
     usecase::{Input, Output},
 };
 
-use super::{authorize::Unauthorized, Usecase};
+use super::{
+    authorize::{Consumption, Unauthorized},
+    Usecase,
+};
 
 pub struct UnsubscribeFeed {
     pub repository: Arc<dyn SubscriptionRepository>,
@@ -41,6 +44,13 @@ impl Usecase for UnsubscribeFeed {
         Ok(principal)
     }
 
+    fn consumption<'a>(&self, _: &'a Self::Input) -> Consumption<'a> {
+        Consumption {
+            mutation: true,
+            ..Default::default()
+        }
+    }
+
     async fn usecase(
         &self,
         Input {
@@ -0,0 +1,180 @@
+use std::{collections::HashSet, sync::Arc};
+
+use synd_o11y::metric;
+
+use crate::{
+    principal::Principal,
+    repository::SubscriptionRepository,
+    usecase::{Input, Output},
+};
+
+use super::{
+    authorize::{Consumption, Unauthorized},
+    Usecase,
+};
+
+pub struct UnsubscribeFeeds {
+    pub repository: Arc<dyn SubscriptionRepository>,
+}
+
+pub struct UnsubscribeFeedsInput {
+    pub urls: Vec<String>,
+}
+
+pub struct UnsubscribeFeedsOutput {
+    /// Results in the order of given urls. Duplicated urls are removed
+    pub results: Vec<UnsubscribeFeedsResult>,
+}
+
+pub struct UnsubscribeFeedsResult {
+    pub url: String,
+    pub status: UnsubscribeStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsubscribeStatus {
+    Unsubscribed,
+    /// The feed was not subscribed, so nothing was done
+    NotSubscribed,
+}
+
+impl Usecase for UnsubscribeFeeds {
+    type Input = UnsubscribeFeedsInput;
+
+    type Output = UnsubscribeFeedsOutput;
+
+    type Error = anyhow::Error;
+
+    fn new(make: &super::MakeUsecase) -> Self {
+        Self {
+            repository: make.subscription_repo.clone(),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &UnsubscribeFeedsInput,
+    ) -> Result<Principal, Unauthorized> {
+        Ok(principal)
+    }
+
+    fn consumption<'a>(&self, _: &'a Self::Input) -> Consumption<'a> {
+        Consumption {
+            mutation: true,
+            ..Default::default()
+        }
+    }
+
+    async fn usecase(
+        &self,
+        Input {
+            principal,
+            input: UnsubscribeFeedsInput { mut urls },
+            ..
+        }: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, super::Error<Self::Error>> {
+        let user_id = principal.user_id().unwrap();
+
+        let mut seen = HashSet::new();
+        urls.retain(|url| seen.insert(url.clone()));
+
+        tracing::debug!("Unsubscribe {} feeds", urls.len());
+
+        let subscribed = self
+            .repository
+            .fetch_subscribed_feed_urls(user_id)
+            .await?
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let results = urls
+            .into_iter()
+            .map(|url| {
+                let status = if subscribed.contains(&url) {
+                    UnsubscribeStatus::Unsubscribed
+                } else {
+                    UnsubscribeStatus::NotSubscribed
+                };
+                UnsubscribeFeedsResult { url, status }
+            })
+            .collect::<Vec<_>>();
+
+        let unsubscribe_urls = results
+            .iter()
+            .filter(|result| result.status == UnsubscribeStatus::Unsubscribed)
+            .map(|result| result.url.clone())
+            .collect::<Vec<_>>();
+        let unsubscribed = unsubscribe_urls.len();
+
+        if !unsubscribe_urls.is_empty() {
+            self.repository
+                .delete_feed_subscriptions(user_id, unsubscribe_urls)
+                .await?;
+        }
+
+        metric!(monotonic_counter.feed.unsubscription = unsubscribed);
+
+        Ok(Output {
+            output: UnsubscribeFeedsOutput { results },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        principal::User,
+        repository::memory::MemoryRepository,
+        usecase::{authorize::Authorized, Input},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn report_status_of_each_url() {
+        let repository = Arc::new(MemoryRepository::new());
+        let usecase = UnsubscribeFeeds {
+            repository: repository.clone(),
+        };
+        let subscribed = "https://blog.rust-lang.org/feed.xml";
+        let not_subscribed = "https://example.com/feed.xml";
+        let invalid = "not a url";
+
+        let Output {
+            output: UnsubscribeFeedsOutput { results },
+        } = usecase
+            .usecase(Input {
+                principal: Authorized::new_for_test(Principal::User(User::new(
+                    "user",
+                    "user@example.com",
+                ))),
+                input: UnsubscribeFeedsInput {
+                    urls: vec![
+                        subscribed.into(),
+                        not_subscribed.into(),
+                        subscribed.into(),
+                        invalid.into(),
+                    ],
+                },
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| (result.url.as_str(), result.status))
+                .collect::<Vec<_>>(),
+            vec![
+                (subscribed, UnsubscribeStatus::Unsubscribed),
+                (not_subscribed, UnsubscribeStatus::NotSubscribed),
+                (invalid, UnsubscribeStatus::NotSubscribed),
+            ]
+        );
+        assert!(!repository
+            .fetch_subscribed_feed_urls("user")
+            .await
+            .unwrap()
+            .contains(&subscribed.to_owned()));
+    }
+}
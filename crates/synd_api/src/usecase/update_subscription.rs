@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use crate::{
+    principal::Principal,
+    repository::{
+        types::{Subscription, SubscriptionUpdate},
+        SubscriptionRepository,
+    },
+    usecase::{Input, Output},
+};
+
+use super::{
+    authorize::{Consumption, Unauthorized},
+    Usecase,
+};
+
+pub struct UpdateSubscription {
+    pub repository: Arc<dyn SubscriptionRepository>,
+}
+
+pub struct UpdateSubscriptionInput {
+    pub url: String,
+    pub update: SubscriptionUpdate,
+}
+
+pub struct UpdateSubscriptionOutput {
+    pub subscription: Subscription,
+}
+
+#[derive(Error, Debug)]
+pub enum UpdateSubscriptionError {
+    #[error("subscription not found: {url}")]
+    NotFound { url: String },
+}
+
+impl Usecase for UpdateSubscription {
+    type Input = UpdateSubscriptionInput;
+
+    type Output = UpdateSubscriptionOutput;
+
+    type Error = UpdateSubscriptionError;
+
+    fn new(make: &super::MakeUsecase) -> Self {
+        Self {
+            repository: make.subscription_repo.clone(),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &UpdateSubscriptionInput,
+    ) -> Result<Principal, Unauthorized> {
+        Ok(principal)
+    }
+
+    fn consumption<'a>(&self, _: &'a Self::Input) -> Consumption<'a> {
+        Consumption {
+            mutation: true,
+            ..Default::default()
+        }
+    }
+
+    async fn usecase(
+        &self,
+        Input {
+            principal,
+            input: UpdateSubscriptionInput { url, update },
+            ..
+        }: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, super::Error<Self::Error>> {
+        tracing::debug!("Update subscription: {url}");
+
+        let user_id = principal.user_id().unwrap();
+
+        let Some(subscription) = self
+            .repository
+            .update_subscription(user_id, &url, update)
+            .await?
+        else {
+            return Err(super::Error::Usecase(UpdateSubscriptionError::NotFound {
+                url,
+            }));
+        };
+
+        Ok(Output {
+            output: UpdateSubscriptionOutput { subscription },
+        })
+    }
+}
@@ -0,0 +1,142 @@
+use std::{collections::HashSet, sync::Arc};
+
+use futures_util::stream::{self, BoxStream, StreamExt};
+use thiserror::Error;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    principal::Principal,
+    realtime::{EntryBroadcaster, NewEntries},
+    repository::SubscriptionRepository,
+    usecase::{authorize::Unauthorized, Error, Input, MakeUsecase, Output, Usecase},
+};
+
+pub struct WatchNewEntries {
+    pub repository: Arc<dyn SubscriptionRepository>,
+    pub broadcaster: EntryBroadcaster,
+}
+
+pub struct WatchNewEntriesInput {}
+
+pub struct WatchNewEntriesOutput {
+    /// New entries of the feeds which were subscribed when watching started
+    pub new_entries: BoxStream<'static, Arc<NewEntries>>,
+}
+
+#[derive(Error, Debug)]
+pub enum WatchNewEntriesError {}
+
+impl Usecase for WatchNewEntries {
+    type Input = WatchNewEntriesInput;
+
+    type Output = WatchNewEntriesOutput;
+
+    type Error = WatchNewEntriesError;
+
+    fn new(make: &MakeUsecase) -> Self {
+        Self {
+            repository: make.subscription_repo.clone(),
+            broadcaster: make.broadcaster.clone(),
+        }
+    }
+
+    async fn authorize(
+        &self,
+        principal: Principal,
+        _: &Self::Input,
+    ) -> Result<Principal, Unauthorized> {
+        Ok(principal)
+    }
+
+    async fn usecase(
+        &self,
+        Input { principal, .. }: Input<Self::Input>,
+    ) -> Result<Output<Self::Output>, Error<Self::Error>> {
+        let user_id = principal.user_id().unwrap();
+
+        let urls = self
+            .repository
+            .fetch_subscriptions(user_id)
+            .await?
+            .into_iter()
+            .filter(|subscription| !subscription.paused)
+            .map(|subscription| subscription.url)
+            .collect::<Vec<_>>();
+
+        let (guard, rx) = self.broadcaster.watch(urls.clone());
+        let urls = urls.into_iter().collect::<HashSet<_>>();
+
+        // Keep the guard in the stream state to watch while the stream is alive
+        let new_entries = stream::unfold((rx, guard), |(mut rx, guard)| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(new_entries) => break Some((new_entries, (rx, guard))),
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "New entries watcher lagged");
+                    }
+                    Err(RecvError::Closed) => break None,
+                }
+            }
+        })
+        .filter(move |new_entries| std::future::ready(urls.contains(new_entries.meta.url())))
+        .boxed();
+
+        Ok(Output {
+            output: WatchNewEntriesOutput { new_entries },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        principal::User, realtime::tests::feed, repository::memory::MemoryRepository,
+        usecase::authorize::Authorized,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn stream_new_entries_of_subscribed_feeds() {
+        let broadcaster = EntryBroadcaster::new(Duration::from_secs(60));
+        let usecase = WatchNewEntries {
+            repository: Arc::new(MemoryRepository::new()),
+            broadcaster: broadcaster.clone(),
+        };
+        // Included in the test data of the memory repository
+        let subscribed = "https://blog.rust-lang.org/feed.xml";
+        let not_subscribed = "https://example.com/feed.xml";
+
+        let Output {
+            output: WatchNewEntriesOutput { mut new_entries },
+        } = usecase
+            .usecase(Input {
+                principal: Authorized::new_for_test(Principal::User(User::new(
+                    "user",
+                    "user@example.com",
+                ))),
+                input: WatchNewEntriesInput {},
+            })
+            .await
+            .unwrap();
+        assert!(broadcaster.watched_urls().contains(&subscribed.to_owned()));
+
+        for url in [not_subscribed, subscribed] {
+            broadcaster.observe(&feed(url, &["1"]));
+            broadcaster.observe(&feed(url, &["2", "1"]));
+        }
+
+        let received = tokio::time::timeout(Duration::from_secs(1), new_entries.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(received.meta.url(), subscribed);
+        assert_eq!(received.entries.len(), 1);
+
+        // Stop watching when the stream is dropped
+        drop(new_entries);
+        assert!(broadcaster.watched_urls().is_empty());
+    }
+}
@@ -0,0 +1,185 @@
+use std::borrow::Cow;
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    device_flow::{DeviceAuthorizationRequest, Provider},
+    jwt::oidc::ProviderMetadata,
+};
+
+/// Generic OpenID Connect provider which supports the device authorization grant
+#[derive(Clone)]
+pub struct Oidc {
+    pub client_id: Cow<'static, str>,
+    pub client_secret: Option<Cow<'static, str>>,
+    device_authorization_endpoint: Url,
+    token_endpoint: Url,
+}
+
+impl Oidc {
+    const SCOPE: &'static str = "openid email";
+
+    pub fn new(
+        client_id: impl Into<Cow<'static, str>>,
+        device_authorization_endpoint: Url,
+        token_endpoint: Url,
+    ) -> Self {
+        Self {
+            client_id: client_id.into(),
+            client_secret: None,
+            device_authorization_endpoint,
+            token_endpoint,
+        }
+    }
+
+    /// Construct provider from the endpoints discovered from the issuer
+    pub async fn discover(
+        client_id: impl Into<Cow<'static, str>>,
+        issuer: &str,
+    ) -> anyhow::Result<Self> {
+        let client = reqwest::ClientBuilder::new()
+            .user_agent(crate::USER_AGENT)
+            .build()?;
+        let metadata = ProviderMetadata::discover(&client, issuer).await?;
+
+        Self::from_metadata(client_id, metadata)
+    }
+
+    pub fn from_metadata(
+        client_id: impl Into<Cow<'static, str>>,
+        metadata: ProviderMetadata,
+    ) -> anyhow::Result<Self> {
+        let Some(device_authorization_endpoint) = metadata.device_authorization_endpoint else {
+            anyhow::bail!(
+                "{} does not support device authorization grant",
+                metadata.issuer
+            );
+        };
+        let Some(token_endpoint) = metadata.token_endpoint else {
+            anyhow::bail!("{} does not provide token endpoint", metadata.issuer);
+        };
+
+        Ok(Self::new(
+            client_id,
+            Url::parse(&device_authorization_endpoint)?,
+            Url::parse(&token_endpoint)?,
+        ))
+    }
+
+    #[must_use]
+    pub fn with_client_secret(self, client_secret: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            client_secret: Some(client_secret.into()),
+            ..self
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeviceAccessTokenRequest<'s> {
+    grant_type: Cow<'static, str>,
+    pub device_code: Cow<'s, str>,
+    pub client_id: Cow<'s, str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<Cow<'s, str>>,
+}
+
+impl<'s> DeviceAccessTokenRequest<'s> {
+    const GRANT_TYPE: &'static str = "urn:ietf:params:oauth:grant-type:device_code";
+}
+
+impl Provider for Oidc {
+    type DeviceAccessTokenRequest<'d> = DeviceAccessTokenRequest<'d>;
+
+    fn device_authorization_endpoint(&self) -> Url {
+        self.device_authorization_endpoint.clone()
+    }
+
+    fn token_endpoint(&self) -> Url {
+        self.token_endpoint.clone()
+    }
+
+    fn device_authorization_request(&self) -> DeviceAuthorizationRequest {
+        DeviceAuthorizationRequest {
+            client_id: self.client_id.clone(),
+            scope: Self::SCOPE.into(),
+        }
+    }
+
+    fn device_access_token_request<'d, 'p: 'd>(
+        &'p self,
+        device_code: &'d str,
+    ) -> DeviceAccessTokenRequest<'d> {
+        DeviceAccessTokenRequest {
+            grant_type: DeviceAccessTokenRequest::GRANT_TYPE.into(),
+            device_code: device_code.into(),
+            client_id: self.client_id.clone(),
+            client_secret: self.client_secret.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> ProviderMetadata {
+        ProviderMetadata {
+            issuer: "https://issuer.example.com".into(),
+            jwks_uri: "https://issuer.example.com/jwks".into(),
+            token_endpoint: Some("https://issuer.example.com/token".into()),
+            device_authorization_endpoint: Some("https://issuer.example.com/device".into()),
+        }
+    }
+
+    #[test]
+    fn from_metadata() {
+        let oidc = Oidc::from_metadata("synd", metadata()).unwrap();
+
+        assert_eq!(
+            oidc.device_authorization_endpoint().as_str(),
+            "https://issuer.example.com/device"
+        );
+        assert_eq!(
+            oidc.token_endpoint().as_str(),
+            "https://issuer.example.com/token"
+        );
+        let request = oidc.device_authorization_request();
+        assert_eq!(request.client_id, "synd");
+        assert_eq!(request.scope, "openid email");
+    }
+
+    #[test]
+    fn reject_metadata_without_device_flow() {
+        let metadata = ProviderMetadata {
+            device_authorization_endpoint: None,
+            ..metadata()
+        };
+        assert!(Oidc::from_metadata("synd", metadata).is_err());
+
+        let metadata = ProviderMetadata {
+            token_endpoint: None,
+            ..metadata()
+        };
+        assert!(Oidc::from_metadata("synd", metadata).is_err());
+    }
+
+    #[test]
+    fn serialize_device_access_token_request() {
+        let oidc = Oidc::from_metadata("synd", metadata()).unwrap();
+        let request = serde_json::to_value(oidc.device_access_token_request("code")).unwrap();
+        assert_eq!(
+            request,
+            serde_json::json!({
+                "grant_type": "urn:ietf:params:oauth:grant-type:device_code",
+                "device_code": "code",
+                "client_id": "synd",
+            })
+        );
+
+        let oidc = oidc.with_client_secret("secret");
+        let request = serde_json::to_value(oidc.device_access_token_request("code")).unwrap();
+        assert_eq!(request["client_secret"], "secret");
+    }
+}
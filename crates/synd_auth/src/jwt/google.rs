@@ -1,9 +1,4 @@
-use std::{
-    borrow::Cow,
-    collections::HashMap,
-    sync::{Arc, RwLock},
-    time::Duration,
-};
+use std::{borrow::Cow, collections::HashMap, sync::Arc, time::Duration};
 
 use chrono::{DateTime, Utc};
 use jsonwebtoken::{DecodingKey, Validation};
@@ -11,9 +6,11 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::{config, USER_AGENT};
-
-type Kid = String;
+use crate::{
+    config,
+    jwt::key_cache::{FetchedKeys, KeyCache, LookupError},
+    USER_AGENT,
+};
 
 #[derive(Debug, Error)]
 pub enum JwtError {
@@ -35,7 +32,8 @@ pub struct Claims {
     iss: String,
     azp: String,
     aud: String,
-    sub: String,
+    /// Identifier of the user which is unique and never reused within google
+    pub sub: String,
     pub email: String,
     pub email_verified: bool,
     iat: i64,
@@ -53,7 +51,7 @@ pub struct JwtService {
     client: Client,
     client_id: Cow<'static, str>,
     client_secret: Cow<'static, str>,
-    key_cache: Arc<RwLock<HashMap<Kid, Arc<DecodingKey>>>>,
+    key_cache: KeyCache,
 }
 
 impl Default for JwtService {
@@ -83,7 +81,7 @@ impl JwtService {
             client,
             client_id: client_id.into(),
             client_secret: client_secret.into(),
-            key_cache: Arc::new(RwLock::default()),
+            key_cache: KeyCache::new(Self::ISSUERS[0]),
         }
     }
 
@@ -132,65 +130,44 @@ impl JwtService {
     }
 
     async fn lookup_decoding_pem(&self, kid: &str) -> Result<Arc<DecodingKey>, JwtError> {
-        if let Some(key) = self.key_cache.read().unwrap().get(kid) {
-            return Ok(key.clone());
-        }
-
-        self.refresh_key_cache().await?;
-
         self.key_cache
-            .read()
-            .unwrap()
-            .get(kid)
-            .cloned()
-            .ok_or(JwtError::DecodingKeyPemNotFound)
-    }
-
-    async fn refresh_key_cache(&self) -> Result<(), JwtError> {
-        let keys = self
-            .fetch_decoding_key_pem()
-            .await?
-            .into_iter()
-            .filter_map(|key| match DecodingKey::from_rsa_pem(key.pem.as_bytes()) {
-                Ok(de_key) => Some((key.kid, Arc::new(de_key))),
-                Err(err) => {
-                    tracing::warn!("failed to create jwt decoding key from pem: {err}");
-                    None
-                }
-            });
-
-        let mut cache = self.key_cache.write().unwrap();
-        keys.for_each(|(kid, de_key)| {
-            cache.insert(kid, de_key);
-        });
-
-        Ok(())
+            .lookup(kid, || self.fetch_decoding_keys())
+            .await
+            .map_err(|err| match err {
+                LookupError::Fetch(err) => err,
+                LookupError::NotFound => JwtError::DecodingKeyPemNotFound,
+            })
     }
 
-    async fn fetch_decoding_key_pem(&self) -> Result<Vec<DecodingKeyPem>, JwtError> {
-        async fn call(
-            client: &Client,
-            endpoint: &str,
-        ) -> Result<HashMap<String, String>, reqwest::Error> {
-            let payload = client
-                .get(endpoint)
-                .header(http::header::ACCEPT, "application/json")
-                .send()
-                .await?
-                .error_for_status()?
-                .json::<HashMap<String, String>>()
-                .await?;
-            Ok(payload)
-        }
-
-        let payload = call(&self.client, Self::PEM_ENDPOINT)
+    async fn fetch_decoding_keys(&self) -> Result<FetchedKeys, JwtError> {
+        let response = self
+            .client
+            .get(Self::PEM_ENDPOINT)
+            .header(http::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(JwtError::FetchPem)?;
+        let max_age = FetchedKeys::max_age(response.headers());
+        let payload = response
+            .json::<HashMap<String, String>>()
             .await
             .map_err(JwtError::FetchPem)?;
 
-        Ok(payload
+        let keys = payload
             .into_iter()
-            .map(|(kid, pem)| DecodingKeyPem { kid, pem })
-            .collect())
+            .filter_map(
+                |(kid, pem)| match DecodingKey::from_rsa_pem(pem.as_bytes()) {
+                    Ok(key) => Some((kid, Arc::new(key))),
+                    Err(err) => {
+                        tracing::warn!("failed to create jwt decoding key from pem: {err}");
+                        None
+                    }
+                },
+            )
+            .collect();
+
+        Ok(FetchedKeys { keys, max_age })
     }
 
     /// Refresh id token
@@ -241,8 +218,3 @@ impl JwtService {
         Ok(response.id_token)
     }
 }
-
-struct DecodingKeyPem {
-    kid: String,
-    pem: String,
-}
@@ -0,0 +1,383 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use http::{header::CACHE_CONTROL, HeaderMap};
+use jsonwebtoken::DecodingKey;
+
+/// Emit the metric event which is exported by the o11y layer of the server.
+/// Same as `synd_o11y::metric!`, which is not depended on to keep server dependencies out of
+/// the clients
+macro_rules! metric {
+    ($($tt:tt)*) => {
+        tracing::event!(target: "metrics", tracing::Level::INFO, $($tt)*)
+    };
+}
+
+type Kid = String;
+
+/// Keys fetched from the provider with the lifetime advertised by `Cache-Control`
+pub(crate) struct FetchedKeys {
+    pub keys: HashMap<Kid, Arc<DecodingKey>>,
+    pub max_age: Option<Duration>,
+}
+
+impl FetchedKeys {
+    /// Parse `max-age` directive of `Cache-Control` header
+    pub fn max_age(headers: &HeaderMap) -> Option<Duration> {
+        headers
+            .get_all(CACHE_CONTROL)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|directive| directive.trim().strip_prefix("max-age="))
+            .find_map(|secs| secs.trim().parse::<u64>().ok())
+            .map(Duration::from_secs)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum LookupError<E> {
+    Fetch(E),
+    NotFound,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct KeyCacheConfig {
+    /// Lifetime of the keys if the provider does not specify `max-age`
+    pub default_max_age: Duration,
+    /// Minimum interval between refreshes triggered by unknown kids
+    pub min_refresh_interval: Duration,
+    /// Duration for which unknown kids are rejected without refreshing
+    pub negative_ttl: Duration,
+    /// Maximum number of unknown kids to remember
+    pub negative_capacity: usize,
+}
+
+impl Default for KeyCacheConfig {
+    fn default() -> Self {
+        Self {
+            default_max_age: Duration::from_secs(60 * 60),
+            min_refresh_interval: Duration::from_secs(30),
+            negative_ttl: Duration::from_secs(60 * 5),
+            negative_capacity: 1024,
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    keys: HashMap<Kid, Arc<DecodingKey>>,
+    expires_at: Option<Instant>,
+    refreshed_at: Option<Instant>,
+    /// Kids which were not found in the latest key set
+    unknown: HashMap<Kid, Instant>,
+}
+
+/// Cache of the decoding keys published by an id token issuer.
+/// The key set is refetched when it expires or when an unseen kid arrives,
+/// but refreshes are limited to `min_refresh_interval` and kids which are still unknown
+/// after a refresh are rejected locally for `negative_ttl`
+#[derive(Clone)]
+pub(crate) struct KeyCache {
+    /// Identify the issuer in metrics
+    issuer: Arc<str>,
+    config: KeyCacheConfig,
+    state: Arc<RwLock<State>>,
+    refresh: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl KeyCache {
+    pub fn new(issuer: impl Into<Arc<str>>) -> Self {
+        Self {
+            issuer: issuer.into(),
+            config: KeyCacheConfig::default(),
+            state: Arc::new(RwLock::default()),
+            refresh: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    pub async fn lookup<F, Fut, E>(
+        &self,
+        kid: &str,
+        fetch: F,
+    ) -> Result<Arc<DecodingKey>, LookupError<E>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<FetchedKeys, E>>,
+        E: std::fmt::Display,
+    {
+        match self.lookup_cached(kid, Instant::now()) {
+            Cached::Found(key) => return Ok(key),
+            Cached::Rejected => {
+                metric!(
+                    monotonic_counter.jwks.unknown_kid_rejected = 1,
+                    issuer = &*self.issuer
+                );
+                return Err(LookupError::NotFound);
+            }
+            Cached::Miss => {}
+        }
+
+        // Only one task fetches the key set, the others wait for the result
+        let _guard = self.refresh.lock().await;
+
+        let now = Instant::now();
+        match self.lookup_cached(kid, now) {
+            Cached::Found(key) => return Ok(key),
+            Cached::Rejected => return Err(LookupError::NotFound),
+            Cached::Miss => {}
+        }
+
+        if !self.should_refresh(now) {
+            // Expired keys remain usable until the next refresh is allowed.
+            // Unknown kid is not remembered since it has not been checked against the latest
+            // key set yet
+            let stale = self.state.read().unwrap().keys.get(kid).cloned();
+            return stale.ok_or_else(|| {
+                metric!(
+                    monotonic_counter.jwks.unknown_kid_rejected = 1,
+                    issuer = &*self.issuer
+                );
+                LookupError::NotFound
+            });
+        }
+
+        match fetch().await {
+            Ok(fetched) => {
+                metric!(
+                    monotonic_counter.jwks.refresh = 1,
+                    issuer = &*self.issuer,
+                    result = "success"
+                );
+                self.replace(fetched, Instant::now());
+            }
+            Err(err) => {
+                metric!(
+                    monotonic_counter.jwks.refresh = 1,
+                    issuer = &*self.issuer,
+                    result = "failure"
+                );
+                // Prefer stale keys to failing all requests while the provider is unavailable
+                let stale = {
+                    let mut state = self.state.write().unwrap();
+                    state.refreshed_at = Some(now);
+                    state.keys.get(kid).cloned()
+                };
+                return match stale {
+                    Some(key) => {
+                        tracing::warn!(issuer = &*self.issuer, "Use stale decoding key: {err}");
+                        Ok(key)
+                    }
+                    None => Err(LookupError::Fetch(err)),
+                };
+            }
+        }
+
+        let key = self.state.read().unwrap().keys.get(kid).cloned();
+        key.ok_or_else(|| {
+            self.reject(kid, Instant::now());
+            LookupError::NotFound
+        })
+    }
+
+    fn lookup_cached(&self, kid: &str, now: Instant) -> Cached {
+        let state = self.state.read().unwrap();
+        let fresh = state.expires_at.is_some_and(|expires_at| now < expires_at);
+
+        match state.keys.get(kid) {
+            Some(key) if fresh => Cached::Found(key.clone()),
+            Some(_) => Cached::Miss,
+            None if state.unknown.get(kid).is_some_and(|until| now < *until) => Cached::Rejected,
+            None => Cached::Miss,
+        }
+    }
+
+    fn should_refresh(&self, now: Instant) -> bool {
+        self.state
+            .read()
+            .unwrap()
+            .refreshed_at
+            .map_or(true, |refreshed_at| {
+                now.duration_since(refreshed_at) >= self.config.min_refresh_interval
+            })
+    }
+
+    fn replace(&self, fetched: FetchedKeys, now: Instant) {
+        let max_age = fetched.max_age.unwrap_or(self.config.default_max_age);
+        let mut state = self.state.write().unwrap();
+
+        state.keys = fetched.keys;
+        state.expires_at = Some(now + max_age);
+        state.refreshed_at = Some(now);
+        // Kids rejected so far may be contained in the new key set
+        state.unknown.clear();
+    }
+
+    fn reject(&self, kid: &str, now: Instant) {
+        let mut state = self.state.write().unwrap();
+
+        if state.unknown.len() >= self.config.negative_capacity {
+            state.unknown.retain(|_, until| now < *until);
+            if state.unknown.len() >= self.config.negative_capacity {
+                state.unknown.clear();
+            }
+        }
+        state
+            .unknown
+            .insert(kid.to_owned(), now + self.config.negative_ttl);
+
+        metric!(
+            monotonic_counter.jwks.unknown_kid_rejected = 1,
+            issuer = &*self.issuer
+        );
+    }
+}
+
+enum Cached {
+    Found(Arc<DecodingKey>),
+    Rejected,
+    Miss,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn cache(config: KeyCacheConfig) -> KeyCache {
+        KeyCache {
+            config,
+            ..KeyCache::new("https://issuer.example.com")
+        }
+    }
+
+    /// Return fetch function which counts the calls
+    fn fetch<'a>(
+        count: &'a AtomicUsize,
+        kids: &'a [&'a str],
+        max_age: Option<Duration>,
+    ) -> impl FnOnce() -> std::future::Ready<Result<FetchedKeys, String>> + 'a {
+        move || {
+            count.fetch_add(1, Ordering::SeqCst);
+            let keys = kids
+                .iter()
+                .map(|kid| {
+                    (
+                        (*kid).to_owned(),
+                        Arc::new(DecodingKey::from_secret(b"secret")),
+                    )
+                })
+                .collect();
+            std::future::ready(Ok(FetchedKeys { keys, max_age }))
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_kid_is_rejected_without_refresh() {
+        let cache = cache(KeyCacheConfig {
+            min_refresh_interval: Duration::ZERO,
+            ..Default::default()
+        });
+        let count = AtomicUsize::new(0);
+
+        let result = cache.lookup("unknown", fetch(&count, &["kid"], None)).await;
+        assert!(matches!(result, Err(LookupError::NotFound)));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        let result = cache.lookup("unknown", fetch(&count, &["kid"], None)).await;
+        assert!(matches!(result, Err(LookupError::NotFound)));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn refresh_on_unknown_kid_is_rate_limited() {
+        let cache = cache(KeyCacheConfig {
+            min_refresh_interval: Duration::from_secs(60 * 60),
+            negative_ttl: Duration::ZERO,
+            ..Default::default()
+        });
+        let count = AtomicUsize::new(0);
+
+        assert!(cache.lookup("a", fetch(&count, &["a"], None)).await.is_ok());
+        for _ in 0..3 {
+            let result = cache.lookup("b", fetch(&count, &["a", "b"], None)).await;
+            assert!(matches!(result, Err(LookupError::NotFound)));
+        }
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn kid_rejected_by_rate_limit_is_not_remembered() {
+        let cache = cache(KeyCacheConfig {
+            min_refresh_interval: Duration::from_millis(100),
+            ..Default::default()
+        });
+        let count = AtomicUsize::new(0);
+
+        assert!(cache.lookup("a", fetch(&count, &["a"], None)).await.is_ok());
+        let result = cache.lookup("b", fetch(&count, &["a", "b"], None)).await;
+        assert!(matches!(result, Err(LookupError::NotFound)));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        // Rotated key is found once the refresh is allowed
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(cache
+            .lookup("b", fetch(&count, &["a", "b"], None))
+            .await
+            .is_ok());
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn keys_are_refreshed_after_max_age() {
+        let cache = cache(KeyCacheConfig {
+            min_refresh_interval: Duration::ZERO,
+            ..Default::default()
+        });
+        let count = AtomicUsize::new(0);
+        let max_age = Some(Duration::from_millis(100));
+
+        assert!(cache
+            .lookup("a", fetch(&count, &["a"], max_age))
+            .await
+            .is_ok());
+        assert!(cache
+            .lookup("a", fetch(&count, &["a"], max_age))
+            .await
+            .is_ok());
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(cache
+            .lookup("a", fetch(&count, &["a"], max_age))
+            .await
+            .is_ok());
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn stale_key_is_used_if_refresh_fails() {
+        let cache = cache(KeyCacheConfig {
+            min_refresh_interval: Duration::ZERO,
+            ..Default::default()
+        });
+        let count = AtomicUsize::new(0);
+
+        assert!(cache
+            .lookup("a", fetch(&count, &["a"], Some(Duration::ZERO)))
+            .await
+            .is_ok());
+
+        let failed = || std::future::ready(Err::<FetchedKeys, _>("unavailable".to_owned()));
+        assert!(cache.lookup("a", failed).await.is_ok());
+        assert!(matches!(
+            cache.lookup("b", failed).await,
+            Err(LookupError::Fetch(_))
+        ));
+    }
+}
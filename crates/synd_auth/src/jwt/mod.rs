@@ -1 +1,3 @@
 pub mod google;
+mod key_cache;
+pub mod oidc;
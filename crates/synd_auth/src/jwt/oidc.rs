@@ -0,0 +1,400 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use jsonwebtoken::{jwk::JwkSet, DecodingKey, Validation};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use thiserror::Error;
+use tokio::sync::OnceCell;
+
+use crate::{
+    jwt::key_cache::{FetchedKeys, KeyCache, LookupError},
+    USER_AGENT,
+};
+
+#[derive(Debug, Error)]
+pub enum OidcError {
+    #[error("discover provider metadata: {0}")]
+    Discovery(reqwest::Error),
+    #[error("issuer mismatch. expected: {expected} actual: {actual}")]
+    IssuerMismatch { expected: String, actual: String },
+    #[error("fetch jwks: {0}")]
+    FetchJwks(reqwest::Error),
+    #[error("decoding key not found")]
+    DecodingKeyNotFound,
+    #[error("decode id token: {0}")]
+    Decode(#[from] jsonwebtoken::errors::Error),
+    #[error("invalid jwt header: {0}")]
+    InvalidHeader(String),
+    #[error("claim `{0}` not found")]
+    MissingClaim(String),
+}
+
+/// Subset of OpenID Provider Metadata
+/// <https://openid.net/specs/openid-connect-discovery-1_0.html#ProviderMetadata>
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub jwks_uri: String,
+    pub token_endpoint: Option<String>,
+    /// <https://datatracker.ietf.org/doc/html/rfc8628#section-4>
+    pub device_authorization_endpoint: Option<String>,
+}
+
+impl ProviderMetadata {
+    const DISCOVERY_PATH: &'static str = "/.well-known/openid-configuration";
+
+    /// Fetch the metadata from the well-known endpoint of the issuer
+    pub async fn discover(client: &Client, issuer: &str) -> Result<Self, OidcError> {
+        let endpoint = format!(
+            "{issuer}{path}",
+            issuer = issuer.trim_end_matches('/'),
+            path = Self::DISCOVERY_PATH
+        );
+        let metadata = client
+            .get(endpoint)
+            .header(http::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(OidcError::Discovery)?
+            .json::<ProviderMetadata>()
+            .await
+            .map_err(OidcError::Discovery)?;
+
+        // The issuer in the metadata must be identical to the one used for discovery
+        if metadata.issuer.trim_end_matches('/') != issuer.trim_end_matches('/') {
+            return Err(OidcError::IssuerMismatch {
+                expected: issuer.to_owned(),
+                actual: metadata.issuer,
+            });
+        }
+
+        Ok(metadata)
+    }
+}
+
+/// Names of the claims from which user attributes are read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimMapping {
+    pub subject: String,
+    pub email: String,
+    /// Email is regarded as verified only if the claim is true
+    pub email_verified: String,
+}
+
+impl Default for ClaimMapping {
+    fn default() -> Self {
+        Self {
+            subject: "sub".into(),
+            email: "email".into(),
+            email_verified: "email_verified".into(),
+        }
+    }
+}
+
+/// Configuration of an OpenID Connect provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OidcConfig {
+    pub issuer: String,
+    /// Expected `aud` claim. usually the client id
+    pub audience: String,
+    /// Discovered from the issuer if not specified
+    pub jwks_url: Option<String>,
+    pub claims: ClaimMapping,
+}
+
+/// User attributes read from the validated id token
+#[derive(Debug, Clone)]
+pub struct OidcClaims {
+    pub subject: String,
+    pub email: String,
+    pub email_verified: bool,
+}
+
+/// Return the `exp` claim of the id token without validating it.
+/// Used by clients to decide whether to login again before sending the token
+pub fn id_token_expiration_insecure(id_token: &str) -> Result<i64, OidcError> {
+    #[derive(Deserialize)]
+    struct Expiration {
+        exp: i64,
+    }
+
+    let validation = {
+        let mut v = Validation::default();
+        v.insecure_disable_signature_validation();
+        v.set_required_spec_claims(&["exp"]);
+        v.validate_exp = false;
+        v.validate_aud = false;
+        v
+    };
+
+    Ok(
+        jsonwebtoken::decode::<Expiration>(id_token, &DecodingKey::from_secret(&[]), &validation)?
+            .claims
+            .exp,
+    )
+}
+
+/// Validate id tokens issued by an OpenID Connect provider
+#[derive(Clone)]
+pub struct OidcJwtService {
+    client: Client,
+    config: Arc<OidcConfig>,
+    jwks_url: Arc<OnceCell<String>>,
+    key_cache: KeyCache,
+}
+
+impl OidcJwtService {
+    pub fn new(config: OidcConfig) -> Self {
+        let client = reqwest::ClientBuilder::new()
+            .user_agent(USER_AGENT)
+            .connect_timeout(Duration::from_secs(10))
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap();
+
+        Self {
+            client,
+            key_cache: KeyCache::new(config.issuer.as_str()),
+            config: Arc::new(config),
+            jwks_url: Arc::new(OnceCell::new()),
+        }
+    }
+
+    pub fn config(&self) -> &OidcConfig {
+        &self.config
+    }
+
+    /// Decode and validate JWT id token
+    pub async fn decode_id_token(&self, id_token: &str) -> Result<OidcClaims, OidcError> {
+        let header = jsonwebtoken::decode_header(id_token)?;
+        let kid = header
+            .kid
+            .ok_or_else(|| OidcError::InvalidHeader("kid not found".into()))?;
+        let decoding_key = self.lookup_decoding_key(&kid).await?;
+        let validation = {
+            let mut v = Validation::new(header.alg);
+            v.set_audience(&[self.config.audience.as_str()]);
+            v.set_issuer(&[self.config.issuer.as_str()]);
+            v.set_required_spec_claims(&["exp"]);
+            v.validate_exp = true;
+            v
+        };
+
+        let claims =
+            jsonwebtoken::decode::<HashMap<String, Value>>(id_token, &decoding_key, &validation)?
+                .claims;
+
+        self.map_claims(&claims)
+    }
+
+    fn map_claims(&self, claims: &HashMap<String, Value>) -> Result<OidcClaims, OidcError> {
+        let mapping = &self.config.claims;
+        let string_claim = |name: &str| {
+            claims
+                .get(name)
+                .and_then(Value::as_str)
+                .map(ToOwned::to_owned)
+                .ok_or_else(|| OidcError::MissingClaim(name.to_owned()))
+        };
+
+        Ok(OidcClaims {
+            subject: string_claim(&mapping.subject)?,
+            email: string_claim(&mapping.email)?,
+            email_verified: claims.get(&mapping.email_verified).and_then(Value::as_bool)
+                == Some(true),
+        })
+    }
+
+    async fn lookup_decoding_key(&self, kid: &str) -> Result<Arc<DecodingKey>, OidcError> {
+        self.key_cache
+            .lookup(kid, || self.fetch_decoding_keys())
+            .await
+            .map_err(|err| match err {
+                LookupError::Fetch(err) => err,
+                LookupError::NotFound => OidcError::DecodingKeyNotFound,
+            })
+    }
+
+    async fn jwks_url(&self) -> Result<&str, OidcError> {
+        self.jwks_url
+            .get_or_try_init(|| async {
+                match self.config.jwks_url.as_ref() {
+                    Some(url) => Ok(url.clone()),
+                    None => ProviderMetadata::discover(&self.client, &self.config.issuer)
+                        .await
+                        .map(|metadata| metadata.jwks_uri),
+                }
+            })
+            .await
+            .map(String::as_str)
+    }
+
+    async fn fetch_decoding_keys(&self) -> Result<FetchedKeys, OidcError> {
+        let response = self
+            .client
+            .get(self.jwks_url().await?)
+            .header(http::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(OidcError::FetchJwks)?;
+        let max_age = FetchedKeys::max_age(response.headers());
+        let jwks = response
+            .json::<JwkSet>()
+            .await
+            .map_err(OidcError::FetchJwks)?;
+
+        let keys = jwks
+            .keys
+            .iter()
+            .filter_map(|jwk| {
+                let kid = jwk.common.key_id.clone()?;
+                match DecodingKey::from_jwk(jwk) {
+                    Ok(key) => Some((kid, Arc::new(key))),
+                    Err(err) => {
+                        tracing::warn!("failed to create jwt decoding key from jwk: {err}");
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Ok(FetchedKeys { keys, max_age })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+
+    use super::*;
+
+    const ISSUER: &str = "https://issuer.example.com";
+    const AUDIENCE: &str = "synd";
+    const KID: &str = "kid";
+    const SECRET: &[u8] = b"secret";
+
+    async fn service(claims: ClaimMapping) -> OidcJwtService {
+        let service = OidcJwtService::new(OidcConfig {
+            issuer: ISSUER.into(),
+            audience: AUDIENCE.into(),
+            jwks_url: None,
+            claims,
+        });
+        // Prime the key cache instead of serving jwks
+        service
+            .key_cache
+            .lookup(KID, || async {
+                Ok::<_, OidcError>(FetchedKeys {
+                    keys: HashMap::from([(
+                        KID.to_owned(),
+                        Arc::new(DecodingKey::from_secret(SECRET)),
+                    )]),
+                    max_age: None,
+                })
+            })
+            .await
+            .unwrap();
+        service
+    }
+
+    fn id_token(claims: &Value) -> String {
+        let header = Header {
+            kid: Some(KID.into()),
+            ..Header::default()
+        };
+        jsonwebtoken::encode(&header, claims, &EncodingKey::from_secret(SECRET)).unwrap()
+    }
+
+    fn claims() -> Value {
+        json!({
+            "iss": ISSUER,
+            "aud": AUDIENCE,
+            "exp": jsonwebtoken::get_current_timestamp() + 60 * 60,
+            "sub": "subject",
+            "email": "user@example.com",
+            "email_verified": true,
+        })
+    }
+
+    #[tokio::test]
+    async fn decode_valid_id_token() {
+        let service = service(ClaimMapping::default()).await;
+
+        let claims = service.decode_id_token(&id_token(&claims())).await.unwrap();
+        assert_eq!(claims.subject, "subject");
+        assert_eq!(claims.email, "user@example.com");
+        assert!(claims.email_verified);
+    }
+
+    #[tokio::test]
+    async fn reject_other_issuer_or_audience() {
+        let service = service(ClaimMapping::default()).await;
+
+        let mut other_issuer = claims();
+        other_issuer["iss"] = "https://other.example.com".into();
+        assert!(matches!(
+            service.decode_id_token(&id_token(&other_issuer)).await,
+            Err(OidcError::Decode(_))
+        ));
+
+        let mut other_audience = claims();
+        other_audience["aud"] = "other".into();
+        assert!(matches!(
+            service.decode_id_token(&id_token(&other_audience)).await,
+            Err(OidcError::Decode(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn email_is_verified_only_if_claim_is_true() {
+        let service = service(ClaimMapping::default()).await;
+
+        for verified in [json!(false), json!("true"), Value::Null] {
+            let mut claims = claims();
+            claims["email_verified"] = verified;
+            let claims = service.decode_id_token(&id_token(&claims)).await.unwrap();
+            assert!(!claims.email_verified);
+        }
+    }
+
+    #[tokio::test]
+    async fn map_configured_claims() {
+        let service = service(ClaimMapping {
+            subject: "oid".into(),
+            email: "upn".into(),
+            email_verified: "verified".into(),
+        })
+        .await;
+
+        let mut claims = claims();
+        claims["oid"] = "object-id".into();
+        claims["upn"] = "upn@example.com".into();
+        claims["verified"] = true.into();
+        let claims = service.decode_id_token(&id_token(&claims)).await.unwrap();
+        assert_eq!(claims.subject, "object-id");
+        assert_eq!(claims.email, "upn@example.com");
+        assert!(claims.email_verified);
+
+        assert!(matches!(
+            service.decode_id_token(&id_token(&self::claims())).await,
+            Err(OidcError::MissingClaim(claim)) if claim == "oid"
+        ));
+    }
+
+    #[test]
+    fn read_expiration_without_validation() {
+        let mut claims = claims();
+        claims["exp"] = 1_000.into();
+        claims["aud"] = "other".into();
+
+        assert_eq!(
+            id_token_expiration_insecure(&id_token(&claims)).unwrap(),
+            1_000
+        );
+        assert!(id_token_expiration_insecure("invalid").is_err());
+    }
+}
@@ -42,12 +42,26 @@ impl CacheConfig {
     }
 }
 
+/// Approximate usage of the cache
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CacheStats {
+    pub entry_count: u64,
+    /// Total size of the cached feeds in bytes
+    pub weighted_size: u64,
+    pub max_capacity: Option<u64>,
+}
+
 #[async_trait]
 pub trait FetchCachedFeed: Send + Sync {
     async fn fetch_feed(&self, url: String) -> FetchFeedResult<Arc<types::Feed>>;
+    /// Fetch feed bypassing the cache and update the cache
+    async fn refresh_feed(&self, url: String) -> FetchFeedResult<Arc<types::Feed>>;
+    /// Remove the feed from the cache. Return whether the feed was cached
+    async fn purge_feed(&self, url: &str) -> bool;
     /// Fetch feeds by spawning tasks
     async fn fetch_feeds_parallel(&self, urls: &[String])
         -> Vec<FetchFeedResult<Arc<types::Feed>>>;
+    fn cache_stats(&self) -> CacheStats;
 }
 
 #[derive(Clone)]
@@ -95,6 +109,11 @@ where
             return Ok(feed);
         }
 
+        self.refresh_feed(url).await
+    }
+
+    #[tracing::instrument(skip_all, fields(%url))]
+    async fn refresh_feed(&self, url: String) -> FetchFeedResult<Arc<types::Feed>> {
         let feed = self.service.fetch_feed(url.clone()).await.map(Arc::new)?;
 
         self.cache.insert(url, Arc::clone(&feed)).await;
@@ -102,6 +121,19 @@ where
         Ok(feed)
     }
 
+    #[tracing::instrument(skip_all, fields(%url))]
+    async fn purge_feed(&self, url: &str) -> bool {
+        self.cache.remove(url).await.is_some()
+    }
+
+    fn cache_stats(&self) -> CacheStats {
+        CacheStats {
+            entry_count: self.cache.entry_count(),
+            weighted_size: self.cache.weighted_size(),
+            max_capacity: self.cache.policy().max_capacity(),
+        }
+    }
+
     /// Fetch feeds by spawning tasks
     async fn fetch_feeds_parallel(
         &self,
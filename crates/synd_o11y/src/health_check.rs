@@ -2,12 +2,13 @@
 //! [RFC Draft](https://datatracker.ietf.org/doc/html/draft-inadarei-api-health-check)
 
 use core::fmt;
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::BTreeMap};
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Indicates whether the service status is acceptable or not.
-#[derive(Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Status {
     /// healthy
     #[default]
@@ -28,11 +29,34 @@ impl fmt::Display for Status {
     }
 }
 
+impl Status {
+    fn severity(self) -> u8 {
+        match self {
+            Status::Pass => 0,
+            Status::Warn => 1,
+            Status::Fail => 2,
+        }
+    }
+
+    /// Return the more severe status
+    #[must_use]
+    pub fn worse(self, other: Status) -> Status {
+        if other.severity() > self.severity() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
 #[derive(Default, Debug, Serialize, Deserialize)]
 pub struct Health {
     pub status: Status,
     pub version: Option<Cow<'static, str>>,
     pub description: Option<Cow<'static, str>>,
+    /// Status of the downstream dependencies keyed by `{componentName}:{measurementName}`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub checks: BTreeMap<String, Vec<Check>>,
 }
 
 impl Health {
@@ -61,4 +85,121 @@ impl Health {
             ..self
         }
     }
+
+    /// Add the check. The status of the health is downgraded to the status of the check
+    #[must_use]
+    pub fn with_check(mut self, key: impl Into<String>, check: Check) -> Self {
+        self.status = self.status.worse(check.status);
+        self.checks.entry(key.into()).or_default().push(check);
+        self
+    }
+}
+
+/// [RFC The Checks Object](https://datatracker.ietf.org/doc/html/draft-inadarei-api-health-check#name-the-checks-object)
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Check {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component_id: Option<Cow<'static, str>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component_type: Option<Cow<'static, str>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub observed_value: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub observed_unit: Option<Cow<'static, str>>,
+    pub status: Status,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<DateTime<Utc>>,
+    /// Error details of the check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+impl Check {
+    pub fn new(status: Status) -> Self {
+        Self {
+            status,
+            time: Some(Utc::now()),
+            ..Default::default()
+        }
+    }
+
+    pub fn pass() -> Self {
+        Self::new(Status::Pass)
+    }
+
+    pub fn fail(output: impl Into<String>) -> Self {
+        Self::new(Status::Fail).with_output(output)
+    }
+
+    pub fn warn(output: impl Into<String>) -> Self {
+        Self::new(Status::Warn).with_output(output)
+    }
+
+    #[must_use]
+    pub fn with_component_id(self, component_id: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            component_id: Some(component_id.into()),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn with_component_type(self, component_type: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            component_type: Some(component_type.into()),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn with_observed_value(
+        self,
+        value: impl Into<serde_json::Value>,
+        unit: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        Self {
+            observed_value: Some(value.into()),
+            observed_unit: Some(unit.into()),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn with_output(self, output: impl Into<String>) -> Self {
+        Self {
+            output: Some(output.into()),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn worse_status() {
+        assert_eq!(Status::Pass.worse(Status::Pass), Status::Pass);
+        assert_eq!(Status::Pass.worse(Status::Warn), Status::Warn);
+        assert_eq!(Status::Warn.worse(Status::Pass), Status::Warn);
+        assert_eq!(Status::Warn.worse(Status::Fail), Status::Fail);
+        assert_eq!(Status::Fail.worse(Status::Warn), Status::Fail);
+        assert_eq!(Status::Fail.worse(Status::Pass), Status::Fail);
+    }
+
+    #[test]
+    fn health_is_downgraded_by_checks() {
+        let health = Health::pass().with_check("a:b", Check::pass());
+        assert_eq!(health.status, Status::Pass);
+
+        let health = health.with_check("a:b", Check::warn("slow"));
+        assert_eq!(health.status, Status::Warn);
+        assert_eq!(health.checks["a:b"].len(), 2);
+
+        let health = health
+            .with_check("c:d", Check::fail("down"))
+            .with_check("e:f", Check::pass());
+        assert_eq!(health.status, Status::Fail);
+    }
 }
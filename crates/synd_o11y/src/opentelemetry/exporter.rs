@@ -0,0 +1,34 @@
+use std::{
+    sync::{Mutex, OnceLock},
+    time::SystemTime,
+};
+
+static STATUS: OnceLock<Mutex<ExporterStatus>> = OnceLock::new();
+
+/// Errors reported by the opentelemetry pipelines since the process started
+#[derive(Default, Debug, Clone)]
+pub struct ExporterStatus {
+    pub errors: u64,
+    pub last_error: Option<(SystemTime, String)>,
+}
+
+/// Install the global error handler which records the errors of the exporters.
+/// Errors are written to stderr instead of tracing to avoid feeding them back to the exporters
+pub fn init_error_handler() -> Result<(), opentelemetry::global::Error> {
+    STATUS.get_or_init(Mutex::default);
+
+    opentelemetry::global::set_error_handler(|err| {
+        eprintln!("OpenTelemetry error occurred. {err}");
+
+        if let Some(status) = STATUS.get() {
+            let mut status = status.lock().unwrap();
+            status.errors += 1;
+            status.last_error = Some((SystemTime::now(), err.to_string()));
+        }
+    })
+}
+
+/// Return the status of the exporters. None if the error handler is not installed
+pub fn exporter_status() -> Option<ExporterStatus> {
+    STATUS.get().map(|status| status.lock().unwrap().clone())
+}
@@ -8,3 +8,6 @@ pub use opentelemetry::KeyValue;
 
 mod guard;
 pub use guard::OpenTelemetryGuard;
+
+mod exporter;
+pub use exporter::{exporter_status, init_error_handler, ExporterStatus};
@@ -47,6 +47,8 @@ impl Audit {
     pub const USER_ID: &'static str = "enduser.id";
     pub const OPERATION: &'static str = "operation";
     pub const RESULT: &'static str = "result";
+    /// Identifier of the resource which the operation targets
+    pub const RESOURCE: &'static str = "resource";
 
     /// # Panics
     /// panic when directive is invalid
@@ -115,6 +117,7 @@ impl<'a> field::Visit for AuditEventVisitor<'a> {
             Audit::USER_ID => self.ctx.user_id = Some(value.to_owned()),
             Audit::OPERATION => self.ctx.operation = Some(value.to_string()),
             Audit::RESULT => self.ctx.result = Some(value.to_string()),
+            Audit::RESOURCE => self.ctx.resource = Some(value.to_string()),
             _ => {}
         }
     }
@@ -124,6 +127,7 @@ struct AuditContext {
     user_id: Option<String>,
     operation: Option<String>,
     result: Option<String>,
+    resource: Option<String>,
 }
 
 impl AuditContext {
@@ -132,6 +136,7 @@ impl AuditContext {
             user_id: None,
             operation: None,
             result: None,
+            resource: None,
         }
     }
 }
@@ -186,6 +191,7 @@ where
             user_id,
             operation,
             result,
+            resource,
         }) = extensions.remove::<AuditContext>()
         else {
             return;
@@ -195,14 +201,26 @@ where
         let operation = operation.as_deref().unwrap_or("?");
         let result = result.as_deref().unwrap_or("?");
 
-        tracing::event!(
-            name: Audit::EMIT_EVENT_NAME,
-            target: Audit::EMIT_TARGET,
-            Level::INFO,
-            { Audit::USER_ID } = user_id,
-            { Audit::OPERATION } = operation,
-            { Audit::RESULT } = result,
-        );
+        if let Some(resource) = resource {
+            tracing::event!(
+                name: Audit::EMIT_EVENT_NAME,
+                target: Audit::EMIT_TARGET,
+                Level::INFO,
+                { Audit::USER_ID } = user_id,
+                { Audit::OPERATION } = operation,
+                { Audit::RESULT } = result,
+                { Audit::RESOURCE } = resource.as_str(),
+            );
+        } else {
+            tracing::event!(
+                name: Audit::EMIT_EVENT_NAME,
+                target: Audit::EMIT_TARGET,
+                Level::INFO,
+                { Audit::USER_ID } = user_id,
+                { Audit::OPERATION } = operation,
+                { Audit::RESULT } = result,
+            );
+        }
     }
 }
 
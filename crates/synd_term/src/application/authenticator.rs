@@ -3,9 +3,19 @@ use synd_auth::{
     jwt,
 };
 
+use crate::auth::AuthenticationProvider;
+
 pub struct DeviceFlows {
     pub github: DeviceFlow<provider::Github>,
     pub google: DeviceFlow<provider::Google>,
+    pub oidc: Option<OidcDeviceFlow>,
+}
+
+/// Device flow of the OpenID Connect provider configured in synd_api
+pub struct OidcDeviceFlow {
+    /// Provider name sent as the authorization scheme
+    pub name: String,
+    pub device_flow: DeviceFlow<provider::Oidc>,
 }
 
 pub struct JwtService {
@@ -31,6 +41,7 @@ impl Authenticator {
             device_flows: DeviceFlows {
                 github: DeviceFlow::new(provider::Github::default()),
                 google: DeviceFlow::new(provider::Google::default()),
+                oidc: None,
             },
             jwt_service: JwtService::new(),
         }
@@ -43,4 +54,25 @@ impl Authenticator {
             ..self
         }
     }
+
+    #[must_use]
+    pub fn with_oidc(mut self, name: impl Into<String>, oidc: provider::Oidc) -> Self {
+        self.device_flows.oidc = Some(OidcDeviceFlow {
+            name: name.into(),
+            device_flow: DeviceFlow::new(oidc),
+        });
+        self
+    }
+
+    /// Providers which can be selected on login
+    pub fn providers(&self) -> Vec<AuthenticationProvider> {
+        let mut providers = vec![
+            AuthenticationProvider::Github,
+            AuthenticationProvider::Google,
+        ];
+        if self.device_flows.oidc.is_some() {
+            providers.push(AuthenticationProvider::Oidc);
+        }
+        providers
+    }
 }
@@ -4,14 +4,14 @@ use crossterm::event::{Event as CrosstermEvent, KeyEvent, KeyEventKind};
 use futures_util::{FutureExt, Stream, StreamExt};
 use ratatui::{style::palette::tailwind, widgets::Widget};
 use synd_auth::device_flow::{
-    self, DeviceAccessTokenResponse, DeviceAuthorizationResponse, DeviceFlow,
+    self, provider, DeviceAccessTokenResponse, DeviceAuthorizationResponse, DeviceFlow,
 };
 use tokio::time::{Instant, Sleep};
 
 use crate::{
     application::input_parser::ParseFeedUrlError,
     auth::{AuthenticationProvider, Credential},
-    client::Client,
+    client::{Client, EntriesFilter},
     command::Command,
     config,
     interact::Interactor,
@@ -20,7 +20,12 @@ use crate::{
     terminal::Terminal,
     ui::{
         self,
-        components::{authentication::AuthenticateState, root::Root, tabs::Tab, Components},
+        components::{
+            authentication::{AuthenticateState, Authentication},
+            root::Root,
+            tabs::Tab,
+            Components,
+        },
         theme::Theme,
     },
 };
@@ -35,7 +40,7 @@ mod input_parser;
 use input_parser::InputParser;
 
 mod authenticator;
-pub use authenticator::{Authenticator, DeviceFlows, JwtService};
+pub use authenticator::{Authenticator, DeviceFlows, JwtService, OidcDeviceFlow};
 
 enum Screen {
     Login,
@@ -119,7 +124,8 @@ impl Application {
     }
 
     #[must_use]
-    pub fn with_authenticator(self, authenticator: Authenticator) -> Self {
+    pub fn with_authenticator(mut self, authenticator: Authenticator) -> Self {
+        self.components.auth = Authentication::new(authenticator.providers());
         Self {
             authenticator,
             ..self
@@ -256,6 +262,11 @@ impl Application {
                                 self.authenticator.device_flows.google.clone(),
                             );
                         }
+                        AuthenticationProvider::Oidc => {
+                            if let Some(device_flow) = self.oidc_device_flow() {
+                                self.authenticate(provider, device_flow);
+                            }
+                        }
                     }
                 }
                 Command::MoveAuthenticationProvider(direction) => {
@@ -280,6 +291,11 @@ impl Application {
                             device_authorization,
                         );
                     }
+                    AuthenticationProvider::Oidc => {
+                        if let Some(device_flow) = self.oidc_device_flow() {
+                            self.device_authorize_flow(provider, device_flow, device_authorization);
+                        }
+                    }
                 },
                 Command::CompleteDevieAuthorizationFlow {
                     provider,
@@ -589,7 +605,10 @@ impl Application {
         let client = self.client.clone();
         let request_seq = self.in_flight.add(RequestId::FetchEntries);
         let fut = async move {
-            match client.fetch_entries(after, first).await {
+            match client
+                .fetch_entries(after, first, EntriesFilter::default())
+                .await
+            {
                 Ok(payload) => Ok(Command::UpdateEntries {
                     action,
                     payload,
@@ -607,6 +626,14 @@ impl Application {
 }
 
 impl Application {
+    fn oidc_device_flow(&self) -> Option<DeviceFlow<provider::Oidc>> {
+        self.authenticator
+            .device_flows
+            .oidc
+            .as_ref()
+            .map(|oidc| oidc.device_flow.clone())
+    }
+
     #[tracing::instrument(skip(self, device_flow))]
     fn authenticate<P>(&mut self, provider: AuthenticationProvider, device_flow: DeviceFlow<P>)
     where
@@ -684,6 +711,17 @@ impl Application {
                     .refresh_token
                     .expect("refresh token not found"),
             },
+            AuthenticationProvider::Oidc => Credential::Oidc {
+                provider: self
+                    .authenticator
+                    .device_flows
+                    .oidc
+                    .as_ref()
+                    .expect("oidc provider not configured")
+                    .name
+                    .clone(),
+                id_token: device_access_token.id_token.expect("id token not found"),
+            },
         };
 
         // should test with tmp file?
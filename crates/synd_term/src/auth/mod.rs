@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 use chrono::Utc;
 use futures_util::TryFutureExt;
 use serde::{Deserialize, Serialize};
-use synd_auth::jwt::google::JwtError;
+use synd_auth::jwt::{google::JwtError, oidc::OidcError};
 use thiserror::Error;
 use tracing::debug;
 
@@ -13,6 +13,8 @@ use crate::{application::JwtService, config};
 pub enum AuthenticationProvider {
     Github,
     Google,
+    /// OpenID Connect provider specified by `--oidc-provider`
+    Oidc,
 }
 
 #[derive(Debug, Error)]
@@ -21,6 +23,10 @@ pub enum CredentialError {
     GoogleJwtExpired { refresh_token: String },
     #[error("google jwt email not verified")]
     GoogleJwtEmailNotVerified,
+    #[error("oidc jwt expired")]
+    OidcJwtExpired,
+    #[error("decode oidc jwt: {0}")]
+    DecodeOidcJwt(OidcError),
     #[error("failed to open: {0}")]
     Open(std::io::Error),
     #[error("deserialize credential: {0}")]
@@ -42,6 +48,11 @@ pub enum Credential {
         id_token: String,
         refresh_token: String,
     },
+    Oidc {
+        /// Name of the provider configured in synd_api
+        provider: String,
+        id_token: String,
+    },
 }
 
 impl Credential {
@@ -92,6 +103,15 @@ impl Credential {
 
                 Ok(credential)
             }
+            // Id token is not refreshed, so login again after it expired
+            Credential::Oidc { id_token, .. } => {
+                let expiration = synd_auth::jwt::oidc::id_token_expiration_insecure(id_token)
+                    .map_err(CredentialError::DecodeOidcJwt)?;
+                if expiration <= Utc::now().timestamp() {
+                    return Err(CredentialError::OidcJwtExpired);
+                }
+                Ok(credential)
+            }
         }
     }
 }
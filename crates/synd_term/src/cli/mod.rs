@@ -2,6 +2,7 @@ use std::{path::PathBuf, time::Duration};
 
 use clap::{Parser, Subcommand};
 use ratatui::style::palette::tailwind;
+use synd_auth::device_flow::provider::Oidc;
 use url::Url;
 
 use crate::config;
@@ -9,6 +10,7 @@ use crate::config;
 mod check;
 mod clear;
 mod export;
+mod token;
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug, clap::ValueEnum)]
 pub enum Palette {
@@ -79,6 +81,10 @@ pub struct Args {
     /// Client timeout
     #[arg(long, value_parser = parse_duration::parse, default_value = config::client::DEFAULT_TIMEOUT)]
     pub timeout: Duration,
+    /// OpenID Connect provider to login with. The name must be the one configured in synd_api.
+    /// Format: `name=<name>,issuer=<url>,client_id=<client_id>[,client_secret=<secret>]`
+    #[arg(long = "oidc-provider", value_name = "SPEC", value_parser = parse_oidc_provider, env = config::env::OIDC_PROVIDER)]
+    pub oidc_provider: Option<OidcProvider>,
     #[command(subcommand)]
     pub command: Option<Command>,
 }
@@ -88,8 +94,94 @@ pub enum Command {
     Clear(clear::ClearCommand),
     Check(check::CheckCommand),
     Export(export::ExportCommand),
+    Token(token::TokenCommand),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OidcProvider {
+    pub name: String,
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+}
+
+impl OidcProvider {
+    /// Discover the endpoints of the device authorization grant
+    pub async fn discover(&self) -> anyhow::Result<Oidc> {
+        let oidc = Oidc::discover(self.client_id.clone(), &self.issuer).await?;
+        Ok(match self.client_secret.clone() {
+            Some(client_secret) => oidc.with_client_secret(client_secret),
+            None => oidc,
+        })
+    }
+}
+
+fn parse_oidc_provider(spec: &str) -> Result<OidcProvider, String> {
+    let mut name = None;
+    let mut issuer = None;
+    let mut client_id = None;
+    let mut client_secret = None;
+
+    for pair in spec
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+    {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("`{pair}` is not a key=value pair"))?;
+        let value = value.trim().to_owned();
+        match key.trim() {
+            "name" => name = Some(value),
+            "issuer" => issuer = Some(value),
+            "client_id" => client_id = Some(value),
+            "client_secret" => client_secret = Some(value),
+            other => return Err(format!("unknown oidc provider key `{other}`")),
+        }
+    }
+
+    Ok(OidcProvider {
+        name: name.ok_or("name is required")?,
+        issuer: issuer.ok_or("issuer is required")?,
+        client_id: client_id.ok_or("client_id is required")?,
+        client_secret,
+    })
 }
 
 pub fn parse() -> Args {
     Args::parse()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_oidc_provider_spec() {
+        let provider =
+            parse_oidc_provider("name=dex, issuer=https://dex.example.com,client_id=synd").unwrap();
+
+        assert_eq!(
+            provider,
+            OidcProvider {
+                name: "dex".into(),
+                issuer: "https://dex.example.com".into(),
+                client_id: "synd".into(),
+                client_secret: None,
+            }
+        );
+    }
+
+    #[test]
+    fn reject_invalid_oidc_provider_spec() {
+        for spec in [
+            "issuer=https://dex.example.com,client_id=synd",
+            "name=dex,client_id=synd",
+            "name=dex,issuer=https://dex.example.com",
+            "name=dex,issuer=https://dex.example.com,client_id",
+            "name=dex,issuer=https://dex.example.com,client_id=synd,unknown=1",
+        ] {
+            assert!(parse_oidc_provider(spec).is_err(), "{spec}");
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::{io, time::Duration};
+
+use anyhow::anyhow;
+use clap::{Args, Subcommand};
+use url::Url;
+
+use crate::{
+    application::JwtService,
+    auth,
+    client::{mutation::create_access_token::AccessTokenScope, Client},
+    types::{AccessToken, TimeExt},
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, clap::ValueEnum)]
+pub enum Scope {
+    /// Read subscriptions and entries
+    Read,
+    /// Modify subscriptions
+    Write,
+}
+
+impl From<Scope> for AccessTokenScope {
+    fn from(scope: Scope) -> Self {
+        match scope {
+            Scope::Read => AccessTokenScope::READ,
+            Scope::Write => AccessTokenScope::WRITE,
+        }
+    }
+}
+
+/// Manage personal access tokens
+#[derive(Args, Debug)]
+pub struct TokenCommand {
+    #[command(subcommand)]
+    pub command: TokenSubcommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TokenSubcommand {
+    /// Issue a token. The token is printed only once
+    Create {
+        /// Name to identify the token
+        name: String,
+        /// Operations allowed for the token
+        #[arg(value_enum, long = "scope", default_values_t = [Scope::Read])]
+        scopes: Vec<Scope>,
+        /// Number of days until the token expires. The token does not expire if not specified
+        #[arg(long)]
+        expires_in_days: Option<u32>,
+    },
+    /// List issued tokens
+    List,
+    /// Revoke the token
+    Revoke {
+        /// Id of the token
+        id: String,
+    },
+}
+
+impl TokenCommand {
+    pub async fn run(self, endpoint: Url) -> i32 {
+        if let Err(err) = self.token(endpoint).await {
+            tracing::error!("{err:?}");
+            1
+        } else {
+            0
+        }
+    }
+
+    async fn token(self, endpoint: Url) -> anyhow::Result<()> {
+        let mut client = Client::new(endpoint, Duration::from_secs(10))?;
+        let jwt_service = JwtService::new();
+
+        // Tokens can be managed only with the credential of interactive login
+        let credentials = auth::credential_from_cache(&jwt_service)
+            .await
+            .ok_or_else(|| anyhow!("You are not authenticated, try login in first"))?;
+        client.set_credential(credentials);
+
+        match self.command {
+            TokenSubcommand::Create {
+                name,
+                scopes,
+                expires_in_days,
+            } => {
+                let (token, access_token) = client
+                    .create_access_token(
+                        name,
+                        scopes.into_iter().map(Into::into).collect(),
+                        expires_in_days.map(i64::from),
+                    )
+                    .await?;
+                eprintln!(
+                    "Created token {id}. Store the token now, it can not be shown again",
+                    id = access_token.id
+                );
+                println!("{token}");
+            }
+            TokenSubcommand::List => {
+                let tokens = client.fetch_access_tokens().await?;
+                Self::print(io::stdout(), &tokens)?;
+            }
+            TokenSubcommand::Revoke { id } => {
+                client.revoke_access_token(id.clone()).await?;
+                println!("Revoked token {id}");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn print(mut writer: impl io::Write, tokens: &[AccessToken]) -> io::Result<()> {
+        let w = &mut writer;
+
+        writeln!(
+            w,
+            "{:<18} {:<24} {:<12} {:<10} EXPIRES",
+            "ID", "NAME", "SCOPES", "CREATED"
+        )?;
+        for token in tokens {
+            writeln!(
+                w,
+                "{:<18} {:<24} {:<12} {:<10} {}",
+                token.id,
+                token.name,
+                token.scopes.join(","),
+                token.created_at.local_ymd(),
+                token
+                    .expires_at
+                    .as_ref()
+                    .map_or("never".into(), TimeExt::local_ymd),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,9 +1,10 @@
 use std::{fmt::Debug, time::Duration};
 
 use anyhow::anyhow;
-use graphql_client::{GraphQLQuery, Response};
+use graphql_client::{GraphQLQuery, QueryBody, Response};
 use reqwest::header::{self, HeaderValue};
 use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
 use synd_o11y::{health_check::Health, opentelemetry::extension::*};
 use thiserror::Error;
 use tracing::{error, Span};
@@ -27,6 +28,17 @@ pub enum SubscribeFeedError {
     Internal(anyhow::Error),
 }
 
+/// Conditions to narrow down entries
+#[derive(Debug, Clone, Default)]
+pub struct EntriesFilter {
+    /// Feed ids or urls
+    pub feeds: Option<Vec<String>>,
+    pub published_after: Option<Rfc3339Time>,
+    pub published_before: Option<Rfc3339Time>,
+    /// Case insensitive keyword matched against title and summary
+    pub keyword: Option<String>,
+}
+
 /// synd-api client
 #[derive(Clone)]
 pub struct Client {
@@ -38,6 +50,7 @@ pub struct Client {
 impl Client {
     const GRAPHQL: &'static str = "/graphql";
     const HEALTH_CHECK: &'static str = "/health";
+    const PERSISTED_QUERY_NOT_FOUND: &'static str = "PersistedQueryNotFound";
 
     pub fn new(endpoint: Url, timeout: Duration) -> anyhow::Result<Self> {
         let client = reqwest::ClientBuilder::new()
@@ -59,6 +72,8 @@ impl Client {
         let mut token = HeaderValue::try_from(match auth {
             Credential::Github { access_token } => format!("github {access_token}"),
             Credential::Google { id_token, .. } => format!("google {id_token}"),
+            // Provider name is the scheme configured in synd_api
+            Credential::Oidc { provider, id_token } => format!("{provider} {id_token}"),
         })
         .unwrap();
         token.set_sensitive(true);
@@ -131,10 +146,24 @@ impl Client {
         &self,
         after: Option<String>,
         first: i64,
+        filter: EntriesFilter,
     ) -> anyhow::Result<payload::FetchEntriesPayload> {
         tracing::debug!("Fetch entries...");
 
-        let var = query::entries::Variables { after, first };
+        let EntriesFilter {
+            feeds,
+            published_after,
+            published_before,
+            keyword,
+        } = filter;
+        let var = query::entries::Variables {
+            after,
+            first,
+            feeds,
+            published_after,
+            published_before,
+            keyword,
+        };
         let request = query::Entries::build_query(var);
         let response: query::entries::ResponseData = self.request(&request).await?;
 
@@ -156,11 +185,122 @@ impl Client {
         Ok(response.output.into())
     }
 
+    /// Issue a personal access token. Return the token and its metadata
+    #[tracing::instrument(skip(self))]
+    pub async fn create_access_token(
+        &self,
+        name: String,
+        scopes: Vec<mutation::create_access_token::AccessTokenScope>,
+        expires_in_days: Option<i64>,
+    ) -> anyhow::Result<(String, types::AccessToken)> {
+        use mutation::create_access_token::CreateAccessTokenCreateAccessToken as Response;
+        let var = mutation::create_access_token::Variables {
+            input: mutation::create_access_token::CreateAccessTokenInput {
+                name,
+                scopes,
+                expires_in_days,
+            },
+        };
+        let request = mutation::CreateAccessToken::build_query(var);
+        let response: mutation::create_access_token::ResponseData = self.request(&request).await?;
+
+        match response.create_access_token {
+            Response::CreateAccessTokenSuccess(success) => {
+                Ok((success.token, success.access_token.into()))
+            }
+            Response::CreateAccessTokenError(err) => {
+                Err(anyhow!("{} ({:?})", err.message, err.status.code))
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn fetch_access_tokens(&self) -> anyhow::Result<Vec<types::AccessToken>> {
+        let request = query::AccessTokens::build_query(query::access_tokens::Variables);
+        let response: query::access_tokens::ResponseData = self.request(&request).await?;
+
+        Ok(response
+            .access_tokens
+            .into_iter()
+            .map(types::AccessToken::from)
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn revoke_access_token(&self, id: String) -> anyhow::Result<()> {
+        use mutation::revoke_access_token::RevokeAccessTokenRevokeAccessToken as Response;
+        let var = mutation::revoke_access_token::Variables {
+            input: mutation::revoke_access_token::RevokeAccessTokenInput { id },
+        };
+        let request = mutation::RevokeAccessToken::build_query(var);
+        let response: mutation::revoke_access_token::ResponseData = self.request(&request).await?;
+
+        match response.revoke_access_token {
+            Response::RevokeAccessTokenSuccess(_) => Ok(()),
+            Response::RevokeAccessTokenError(err) => {
+                Err(anyhow!("{} ({:?})", err.message, err.status.code))
+            }
+        }
+    }
+
+    /// Send the hash of the document first, then the document if the server does not know the hash
+    /// <https://www.apollographql.com/docs/apollo-server/performance/apq/>
     #[tracing::instrument(skip_all, err(Display))]
-    async fn request<Body, ResponseData>(&self, body: &Body) -> anyhow::Result<ResponseData>
+    async fn request<Variables, ResponseData>(
+        &self,
+        body: &QueryBody<Variables>,
+    ) -> anyhow::Result<ResponseData>
     where
-        Body: Serialize + Debug + ?Sized,
+        Variables: Serialize + Debug,
         ResponseData: DeserializeOwned + Debug,
+    {
+        let sha256_hash = format!("{:x}", Sha256::digest(body.query.as_bytes()));
+        let mut request = PersistedQueryBody {
+            query: None,
+            operation_name: body.operation_name,
+            variables: &body.variables,
+            extensions: PersistedQueryExtensions {
+                persisted_query: PersistedQuery {
+                    version: 1,
+                    sha256_hash: &sha256_hash,
+                },
+            },
+        };
+
+        let mut response: Response<ResponseData> = self.send(&request).await?;
+
+        let not_found = response.errors.as_ref().is_some_and(|errs| {
+            errs.iter()
+                .any(|err| err.message == Self::PERSISTED_QUERY_NOT_FOUND)
+        });
+        if not_found {
+            tracing::debug!(
+                operation = body.operation_name,
+                "Persisted query not found, send the document"
+            );
+            request.query = Some(body.query);
+            response = self.send(&request).await?;
+        }
+
+        match (response.data, response.errors) {
+            (_, Some(errs)) if !errs.is_empty() => {
+                for err in &errs {
+                    error!("{err:?}");
+                }
+                Err(anyhow::anyhow!(
+                    "failed to request synd api: {}",
+                    errs.first().unwrap()
+                ))
+            }
+            (Some(data), _) => Ok(data),
+            _ => Err(anyhow::anyhow!("unexpected response",)),
+        }
+    }
+
+    async fn send<Body, ResponseData>(&self, body: &Body) -> anyhow::Result<Response<ResponseData>>
+    where
+        Body: Serialize + ?Sized,
+        ResponseData: DeserializeOwned,
     {
         let mut request = self
             .client
@@ -183,7 +323,7 @@ impl Client {
 
         tracing::debug!(url = request.url().as_str(), "Send request");
 
-        let response: Response<ResponseData> = self
+        let response = self
             .client
             .execute(request)
             .await?
@@ -191,19 +331,7 @@ impl Client {
             .json()
             .await?;
 
-        match (response.data, response.errors) {
-            (_, Some(errs)) if !errs.is_empty() => {
-                for err in &errs {
-                    error!("{err:?}");
-                }
-                Err(anyhow::anyhow!(
-                    "failed to request synd api: {}",
-                    errs.first().unwrap()
-                ))
-            }
-            (Some(data), _) => Ok(data),
-            _ => Err(anyhow::anyhow!("unexpected response",)),
-        }
+        Ok(response)
     }
 
     // call health check api
@@ -218,3 +346,62 @@ impl Client {
             .map_err(anyhow::Error::from)
     }
 }
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedQueryBody<'a, Variables> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query: Option<&'static str>,
+    operation_name: &'static str,
+    variables: &'a Variables,
+    extensions: PersistedQueryExtensions<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedQueryExtensions<'a> {
+    persisted_query: PersistedQuery<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedQuery<'a> {
+    version: u8,
+    sha256_hash: &'a str,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mutation, query, Client, Credential};
+
+    /// synd-api registers the gql files as persisted queries,
+    /// so the sent documents must be the same text as the files
+    #[test]
+    fn documents_are_same_as_gql_files() {
+        let normalize = |file: &str| file.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+
+        assert_eq!(
+            query::subscription::QUERY,
+            normalize(include_str!("../../gql/query.gql"))
+        );
+        assert_eq!(
+            mutation::subscribe_feed::QUERY,
+            normalize(include_str!("../../gql/mutation.gql"))
+        );
+    }
+
+    #[test]
+    fn send_oidc_id_token_with_provider_scheme() {
+        let mut client = Client::new(
+            "https://localhost:5959/graphql".parse().unwrap(),
+            std::time::Duration::from_secs(10),
+        )
+        .unwrap();
+        client.set_credential(Credential::Oidc {
+            provider: "dex".into(),
+            id_token: "token".into(),
+        });
+
+        assert_eq!(client.credential.unwrap(), "dex token");
+    }
+}
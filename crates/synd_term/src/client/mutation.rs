@@ -4,7 +4,7 @@ pub mod subscribe_feed {
     #![allow(dead_code)]
     use std::result::Result;
     pub const OPERATION_NAME: &str = "SubscribeFeed";
-    pub const QUERY : & str = "mutation SubscribeFeed($input: SubscribeFeedInput!) {\n  subscribeFeed(input: $input) {\n    __typename\n    ... on SubscribeFeedSuccess {\n      feed {\n        ...Feed\n      }\n      status {\n        code\n      }\n    }\n    ... on SubscribeFeedError {\n      status {\n        code\n      }\n      message\n    }\n  }\n}\n\nmutation UnsubscribeFeed($input: UnsubscribeFeedInput!) {\n  unsubscribeFeed(input: $input) {\n    __typename\n    ... on UnsubscribeFeedSuccess {\n      status {\n        code\n      }\n    }\n    ... on UnsubscribeFeedError {\n      status {\n        code\n      }\n    }\n  }\n}\n\nfragment Feed on Feed {\n  id\n  type\n  title\n  url\n  updated\n  websiteUrl\n  description\n  generator\n  entries(first: 10) {\n    nodes {\n      ...EntryMeta\n    }\n  }\n  links {\n    nodes {\n      ...Link\n    }\n  }\n  authors {\n    nodes\n  }\n}\n\nfragment EntryMeta on Entry {\n    title,\n    published,\n    updated,\n    summary,\n}\n\nfragment Link on Link {\n  href\n  rel\n  mediaType\n  title  \n}\n" ;
+    pub const QUERY : & str = "mutation SubscribeFeed($input: SubscribeFeedInput!) {\n  subscribeFeed(input: $input) {\n    __typename\n    ... on SubscribeFeedSuccess {\n      feed {\n        ...Feed\n      }\n      status {\n        code\n      }\n    }\n    ... on SubscribeFeedError {\n      status {\n        code\n      }\n      message\n    }\n  }\n}\n\nmutation UnsubscribeFeed($input: UnsubscribeFeedInput!) {\n  unsubscribeFeed(input: $input) {\n    __typename\n    ... on UnsubscribeFeedSuccess {\n      status {\n        code\n      }\n    }\n    ... on UnsubscribeFeedError {\n      status {\n        code\n      }\n    }\n  }\n}\n\nfragment Feed on Feed {\n  id\n  type\n  title\n  url\n  updated\n  websiteUrl\n  description\n  generator\n  entries(first: 10) {\n    nodes {\n      ...EntryMeta\n    }\n  }\n  links {\n    nodes {\n      ...Link\n    }\n  }\n  authors {\n    nodes\n  }\n}\n\nfragment EntryMeta on Entry {\n    title,\n    published,\n    updated,\n    summary,\n}\n\nfragment Link on Link {\n  href\n  rel\n  mediaType\n  title  \n}\n\nmutation CreateAccessToken($input: CreateAccessTokenInput!) {\n  createAccessToken(input: $input) {\n    __typename\n    ... on CreateAccessTokenSuccess {\n      token\n      accessToken {\n        ...AccessToken\n      }\n    }\n    ... on CreateAccessTokenError {\n      status {\n        code\n      }\n      message\n    }\n  }\n}\n\nmutation RevokeAccessToken($input: RevokeAccessTokenInput!) {\n  revokeAccessToken(input: $input) {\n    __typename\n    ... on RevokeAccessTokenSuccess {\n      status {\n        code\n      }\n    }\n    ... on RevokeAccessTokenError {\n      status {\n        code\n      }\n      message\n    }\n  }\n}\n\nfragment AccessToken on AccessToken {\n  id\n  name\n  scopes\n  createdAt\n  expiresAt\n}\n" ;
     use super::*;
     use serde::{Deserialize, Serialize};
     #[allow(dead_code)]
@@ -55,6 +55,11 @@ pub mod subscribe_feed {
         OK,
         UNAUTHORIZED,
         INVALID_FEED_URL,
+        NOT_FOUND,
+        INVALID_SEARCH_QUERY,
+        QUOTA_EXCEEDED,
+        INVALID_CONFIRMATION_TOKEN,
+        INVALID_INPUT,
         INTERNAL_ERROR,
         Other(String),
     }
@@ -64,6 +69,11 @@ pub mod subscribe_feed {
                 ResponseCode::OK => "OK",
                 ResponseCode::UNAUTHORIZED => "UNAUTHORIZED",
                 ResponseCode::INVALID_FEED_URL => "INVALID_FEED_URL",
+                ResponseCode::NOT_FOUND => "NOT_FOUND",
+                ResponseCode::INVALID_SEARCH_QUERY => "INVALID_SEARCH_QUERY",
+                ResponseCode::QUOTA_EXCEEDED => "QUOTA_EXCEEDED",
+                ResponseCode::INVALID_CONFIRMATION_TOKEN => "INVALID_CONFIRMATION_TOKEN",
+                ResponseCode::INVALID_INPUT => "INVALID_INPUT",
                 ResponseCode::INTERNAL_ERROR => "INTERNAL_ERROR",
                 ResponseCode::Other(ref s) => &s,
             })
@@ -76,6 +86,11 @@ pub mod subscribe_feed {
                 "OK" => Ok(ResponseCode::OK),
                 "UNAUTHORIZED" => Ok(ResponseCode::UNAUTHORIZED),
                 "INVALID_FEED_URL" => Ok(ResponseCode::INVALID_FEED_URL),
+                "NOT_FOUND" => Ok(ResponseCode::NOT_FOUND),
+                "INVALID_SEARCH_QUERY" => Ok(ResponseCode::INVALID_SEARCH_QUERY),
+                "QUOTA_EXCEEDED" => Ok(ResponseCode::QUOTA_EXCEEDED),
+                "INVALID_CONFIRMATION_TOKEN" => Ok(ResponseCode::INVALID_CONFIRMATION_TOKEN),
+                "INVALID_INPUT" => Ok(ResponseCode::INVALID_INPUT),
                 "INTERNAL_ERROR" => Ok(ResponseCode::INTERNAL_ERROR),
                 _ => Ok(ResponseCode::Other(s)),
             }
@@ -182,7 +197,7 @@ pub mod unsubscribe_feed {
     #![allow(dead_code)]
     use std::result::Result;
     pub const OPERATION_NAME: &str = "UnsubscribeFeed";
-    pub const QUERY : & str = "mutation SubscribeFeed($input: SubscribeFeedInput!) {\n  subscribeFeed(input: $input) {\n    __typename\n    ... on SubscribeFeedSuccess {\n      feed {\n        ...Feed\n      }\n      status {\n        code\n      }\n    }\n    ... on SubscribeFeedError {\n      status {\n        code\n      }\n      message\n    }\n  }\n}\n\nmutation UnsubscribeFeed($input: UnsubscribeFeedInput!) {\n  unsubscribeFeed(input: $input) {\n    __typename\n    ... on UnsubscribeFeedSuccess {\n      status {\n        code\n      }\n    }\n    ... on UnsubscribeFeedError {\n      status {\n        code\n      }\n    }\n  }\n}\n\nfragment Feed on Feed {\n  id\n  type\n  title\n  url\n  updated\n  websiteUrl\n  description\n  generator\n  entries(first: 10) {\n    nodes {\n      ...EntryMeta\n    }\n  }\n  links {\n    nodes {\n      ...Link\n    }\n  }\n  authors {\n    nodes\n  }\n}\n\nfragment EntryMeta on Entry {\n    title,\n    published,\n    updated,\n    summary,\n}\n\nfragment Link on Link {\n  href\n  rel\n  mediaType\n  title  \n}\n" ;
+    pub const QUERY : & str = "mutation SubscribeFeed($input: SubscribeFeedInput!) {\n  subscribeFeed(input: $input) {\n    __typename\n    ... on SubscribeFeedSuccess {\n      feed {\n        ...Feed\n      }\n      status {\n        code\n      }\n    }\n    ... on SubscribeFeedError {\n      status {\n        code\n      }\n      message\n    }\n  }\n}\n\nmutation UnsubscribeFeed($input: UnsubscribeFeedInput!) {\n  unsubscribeFeed(input: $input) {\n    __typename\n    ... on UnsubscribeFeedSuccess {\n      status {\n        code\n      }\n    }\n    ... on UnsubscribeFeedError {\n      status {\n        code\n      }\n    }\n  }\n}\n\nfragment Feed on Feed {\n  id\n  type\n  title\n  url\n  updated\n  websiteUrl\n  description\n  generator\n  entries(first: 10) {\n    nodes {\n      ...EntryMeta\n    }\n  }\n  links {\n    nodes {\n      ...Link\n    }\n  }\n  authors {\n    nodes\n  }\n}\n\nfragment EntryMeta on Entry {\n    title,\n    published,\n    updated,\n    summary,\n}\n\nfragment Link on Link {\n  href\n  rel\n  mediaType\n  title  \n}\n\nmutation CreateAccessToken($input: CreateAccessTokenInput!) {\n  createAccessToken(input: $input) {\n    __typename\n    ... on CreateAccessTokenSuccess {\n      token\n      accessToken {\n        ...AccessToken\n      }\n    }\n    ... on CreateAccessTokenError {\n      status {\n        code\n      }\n      message\n    }\n  }\n}\n\nmutation RevokeAccessToken($input: RevokeAccessTokenInput!) {\n  revokeAccessToken(input: $input) {\n    __typename\n    ... on RevokeAccessTokenSuccess {\n      status {\n        code\n      }\n    }\n    ... on RevokeAccessTokenError {\n      status {\n        code\n      }\n      message\n    }\n  }\n}\n\nfragment AccessToken on AccessToken {\n  id\n  name\n  scopes\n  createdAt\n  expiresAt\n}\n" ;
     use super::*;
     use serde::{Deserialize, Serialize};
     #[allow(dead_code)]
@@ -198,6 +213,11 @@ pub mod unsubscribe_feed {
         OK,
         UNAUTHORIZED,
         INVALID_FEED_URL,
+        NOT_FOUND,
+        INVALID_SEARCH_QUERY,
+        QUOTA_EXCEEDED,
+        INVALID_CONFIRMATION_TOKEN,
+        INVALID_INPUT,
         INTERNAL_ERROR,
         Other(String),
     }
@@ -207,6 +227,11 @@ pub mod unsubscribe_feed {
                 ResponseCode::OK => "OK",
                 ResponseCode::UNAUTHORIZED => "UNAUTHORIZED",
                 ResponseCode::INVALID_FEED_URL => "INVALID_FEED_URL",
+                ResponseCode::NOT_FOUND => "NOT_FOUND",
+                ResponseCode::INVALID_SEARCH_QUERY => "INVALID_SEARCH_QUERY",
+                ResponseCode::QUOTA_EXCEEDED => "QUOTA_EXCEEDED",
+                ResponseCode::INVALID_CONFIRMATION_TOKEN => "INVALID_CONFIRMATION_TOKEN",
+                ResponseCode::INVALID_INPUT => "INVALID_INPUT",
                 ResponseCode::INTERNAL_ERROR => "INTERNAL_ERROR",
                 ResponseCode::Other(ref s) => &s,
             })
@@ -219,6 +244,11 @@ pub mod unsubscribe_feed {
                 "OK" => Ok(ResponseCode::OK),
                 "UNAUTHORIZED" => Ok(ResponseCode::UNAUTHORIZED),
                 "INVALID_FEED_URL" => Ok(ResponseCode::INVALID_FEED_URL),
+                "NOT_FOUND" => Ok(ResponseCode::NOT_FOUND),
+                "INVALID_SEARCH_QUERY" => Ok(ResponseCode::INVALID_SEARCH_QUERY),
+                "QUOTA_EXCEEDED" => Ok(ResponseCode::QUOTA_EXCEEDED),
+                "INVALID_CONFIRMATION_TOKEN" => Ok(ResponseCode::INVALID_CONFIRMATION_TOKEN),
+                "INVALID_INPUT" => Ok(ResponseCode::INVALID_INPUT),
                 "INTERNAL_ERROR" => Ok(ResponseCode::INTERNAL_ERROR),
                 _ => Ok(ResponseCode::Other(s)),
             }
@@ -272,3 +302,263 @@ impl graphql_client::GraphQLQuery for UnsubscribeFeed {
         }
     }
 }
+pub struct CreateAccessToken;
+pub mod create_access_token {
+    #![allow(dead_code)]
+    use std::result::Result;
+    pub const OPERATION_NAME: &str = "CreateAccessToken";
+    pub const QUERY : & str = "mutation SubscribeFeed($input: SubscribeFeedInput!) {\n  subscribeFeed(input: $input) {\n    __typename\n    ... on SubscribeFeedSuccess {\n      feed {\n        ...Feed\n      }\n      status {\n        code\n      }\n    }\n    ... on SubscribeFeedError {\n      status {\n        code\n      }\n      message\n    }\n  }\n}\n\nmutation UnsubscribeFeed($input: UnsubscribeFeedInput!) {\n  unsubscribeFeed(input: $input) {\n    __typename\n    ... on UnsubscribeFeedSuccess {\n      status {\n        code\n      }\n    }\n    ... on UnsubscribeFeedError {\n      status {\n        code\n      }\n    }\n  }\n}\n\nfragment Feed on Feed {\n  id\n  type\n  title\n  url\n  updated\n  websiteUrl\n  description\n  generator\n  entries(first: 10) {\n    nodes {\n      ...EntryMeta\n    }\n  }\n  links {\n    nodes {\n      ...Link\n    }\n  }\n  authors {\n    nodes\n  }\n}\n\nfragment EntryMeta on Entry {\n    title,\n    published,\n    updated,\n    summary,\n}\n\nfragment Link on Link {\n  href\n  rel\n  mediaType\n  title  \n}\n\nmutation CreateAccessToken($input: CreateAccessTokenInput!) {\n  createAccessToken(input: $input) {\n    __typename\n    ... on CreateAccessTokenSuccess {\n      token\n      accessToken {\n        ...AccessToken\n      }\n    }\n    ... on CreateAccessTokenError {\n      status {\n        code\n      }\n      message\n    }\n  }\n}\n\nmutation RevokeAccessToken($input: RevokeAccessTokenInput!) {\n  revokeAccessToken(input: $input) {\n    __typename\n    ... on RevokeAccessTokenSuccess {\n      status {\n        code\n      }\n    }\n    ... on RevokeAccessTokenError {\n      status {\n        code\n      }\n      message\n    }\n  }\n}\n\nfragment AccessToken on AccessToken {\n  id\n  name\n  scopes\n  createdAt\n  expiresAt\n}\n" ;
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    #[allow(dead_code)]
+    type Boolean = bool;
+    #[allow(dead_code)]
+    type Float = f64;
+    #[allow(dead_code)]
+    type Int = i64;
+    #[allow(dead_code)]
+    type ID = String;
+    type Rfc3339Time = crate::client::scalar::Rfc3339Time;
+    #[derive(Clone, Debug)]
+    pub enum AccessTokenScope {
+        READ,
+        WRITE,
+        Other(String),
+    }
+    impl ::serde::Serialize for AccessTokenScope {
+        fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            ser.serialize_str(match *self {
+                AccessTokenScope::READ => "READ",
+                AccessTokenScope::WRITE => "WRITE",
+                AccessTokenScope::Other(ref s) => &s,
+            })
+        }
+    }
+    impl<'de> ::serde::Deserialize<'de> for AccessTokenScope {
+        fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let s: String = ::serde::Deserialize::deserialize(deserializer)?;
+            match s.as_str() {
+                "READ" => Ok(AccessTokenScope::READ),
+                "WRITE" => Ok(AccessTokenScope::WRITE),
+                _ => Ok(AccessTokenScope::Other(s)),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    pub enum ResponseCode {
+        OK,
+        UNAUTHORIZED,
+        INVALID_FEED_URL,
+        NOT_FOUND,
+        INVALID_SEARCH_QUERY,
+        QUOTA_EXCEEDED,
+        INVALID_CONFIRMATION_TOKEN,
+        INVALID_INPUT,
+        INTERNAL_ERROR,
+        Other(String),
+    }
+    impl ::serde::Serialize for ResponseCode {
+        fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            ser.serialize_str(match *self {
+                ResponseCode::OK => "OK",
+                ResponseCode::UNAUTHORIZED => "UNAUTHORIZED",
+                ResponseCode::INVALID_FEED_URL => "INVALID_FEED_URL",
+                ResponseCode::NOT_FOUND => "NOT_FOUND",
+                ResponseCode::INVALID_SEARCH_QUERY => "INVALID_SEARCH_QUERY",
+                ResponseCode::QUOTA_EXCEEDED => "QUOTA_EXCEEDED",
+                ResponseCode::INVALID_CONFIRMATION_TOKEN => "INVALID_CONFIRMATION_TOKEN",
+                ResponseCode::INVALID_INPUT => "INVALID_INPUT",
+                ResponseCode::INTERNAL_ERROR => "INTERNAL_ERROR",
+                ResponseCode::Other(ref s) => &s,
+            })
+        }
+    }
+    impl<'de> ::serde::Deserialize<'de> for ResponseCode {
+        fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let s: String = ::serde::Deserialize::deserialize(deserializer)?;
+            match s.as_str() {
+                "OK" => Ok(ResponseCode::OK),
+                "UNAUTHORIZED" => Ok(ResponseCode::UNAUTHORIZED),
+                "INVALID_FEED_URL" => Ok(ResponseCode::INVALID_FEED_URL),
+                "NOT_FOUND" => Ok(ResponseCode::NOT_FOUND),
+                "INVALID_SEARCH_QUERY" => Ok(ResponseCode::INVALID_SEARCH_QUERY),
+                "QUOTA_EXCEEDED" => Ok(ResponseCode::QUOTA_EXCEEDED),
+                "INVALID_CONFIRMATION_TOKEN" => Ok(ResponseCode::INVALID_CONFIRMATION_TOKEN),
+                "INVALID_INPUT" => Ok(ResponseCode::INVALID_INPUT),
+                "INTERNAL_ERROR" => Ok(ResponseCode::INTERNAL_ERROR),
+                _ => Ok(ResponseCode::Other(s)),
+            }
+        }
+    }
+    #[derive(Serialize, Debug)]
+    pub struct CreateAccessTokenInput {
+        pub name: String,
+        pub scopes: Vec<AccessTokenScope>,
+        #[serde(rename = "expiresInDays")]
+        pub expires_in_days: Option<Int>,
+    }
+    #[derive(Serialize, Debug)]
+    pub struct Variables {
+        pub input: CreateAccessTokenInput,
+    }
+    impl Variables {}
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct AccessToken {
+        pub id: ID,
+        pub name: String,
+        pub scopes: Vec<AccessTokenScope>,
+        #[serde(rename = "createdAt")]
+        pub created_at: Rfc3339Time,
+        #[serde(rename = "expiresAt")]
+        pub expires_at: Option<Rfc3339Time>,
+    }
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct ResponseData {
+        #[serde(rename = "createAccessToken")]
+        pub create_access_token: CreateAccessTokenCreateAccessToken,
+    }
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(tag = "__typename")]
+    pub enum CreateAccessTokenCreateAccessToken {
+        CreateAccessTokenSuccess(CreateAccessTokenCreateAccessTokenOnCreateAccessTokenSuccess),
+        CreateAccessTokenError(CreateAccessTokenCreateAccessTokenOnCreateAccessTokenError),
+    }
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct CreateAccessTokenCreateAccessTokenOnCreateAccessTokenSuccess {
+        pub token: String,
+        #[serde(rename = "accessToken")]
+        pub access_token: CreateAccessTokenCreateAccessTokenOnCreateAccessTokenSuccessAccessToken,
+    }
+    pub type CreateAccessTokenCreateAccessTokenOnCreateAccessTokenSuccessAccessToken = AccessToken;
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct CreateAccessTokenCreateAccessTokenOnCreateAccessTokenError {
+        pub status: CreateAccessTokenCreateAccessTokenOnCreateAccessTokenErrorStatus,
+        pub message: String,
+    }
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct CreateAccessTokenCreateAccessTokenOnCreateAccessTokenErrorStatus {
+        pub code: ResponseCode,
+    }
+}
+impl graphql_client::GraphQLQuery for CreateAccessToken {
+    type Variables = create_access_token::Variables;
+    type ResponseData = create_access_token::ResponseData;
+    fn build_query(variables: Self::Variables) -> ::graphql_client::QueryBody<Self::Variables> {
+        graphql_client::QueryBody {
+            variables,
+            query: create_access_token::QUERY,
+            operation_name: create_access_token::OPERATION_NAME,
+        }
+    }
+}
+pub struct RevokeAccessToken;
+pub mod revoke_access_token {
+    #![allow(dead_code)]
+    use std::result::Result;
+    pub const OPERATION_NAME: &str = "RevokeAccessToken";
+    pub const QUERY : & str = "mutation SubscribeFeed($input: SubscribeFeedInput!) {\n  subscribeFeed(input: $input) {\n    __typename\n    ... on SubscribeFeedSuccess {\n      feed {\n        ...Feed\n      }\n      status {\n        code\n      }\n    }\n    ... on SubscribeFeedError {\n      status {\n        code\n      }\n      message\n    }\n  }\n}\n\nmutation UnsubscribeFeed($input: UnsubscribeFeedInput!) {\n  unsubscribeFeed(input: $input) {\n    __typename\n    ... on UnsubscribeFeedSuccess {\n      status {\n        code\n      }\n    }\n    ... on UnsubscribeFeedError {\n      status {\n        code\n      }\n    }\n  }\n}\n\nfragment Feed on Feed {\n  id\n  type\n  title\n  url\n  updated\n  websiteUrl\n  description\n  generator\n  entries(first: 10) {\n    nodes {\n      ...EntryMeta\n    }\n  }\n  links {\n    nodes {\n      ...Link\n    }\n  }\n  authors {\n    nodes\n  }\n}\n\nfragment EntryMeta on Entry {\n    title,\n    published,\n    updated,\n    summary,\n}\n\nfragment Link on Link {\n  href\n  rel\n  mediaType\n  title  \n}\n\nmutation CreateAccessToken($input: CreateAccessTokenInput!) {\n  createAccessToken(input: $input) {\n    __typename\n    ... on CreateAccessTokenSuccess {\n      token\n      accessToken {\n        ...AccessToken\n      }\n    }\n    ... on CreateAccessTokenError {\n      status {\n        code\n      }\n      message\n    }\n  }\n}\n\nmutation RevokeAccessToken($input: RevokeAccessTokenInput!) {\n  revokeAccessToken(input: $input) {\n    __typename\n    ... on RevokeAccessTokenSuccess {\n      status {\n        code\n      }\n    }\n    ... on RevokeAccessTokenError {\n      status {\n        code\n      }\n      message\n    }\n  }\n}\n\nfragment AccessToken on AccessToken {\n  id\n  name\n  scopes\n  createdAt\n  expiresAt\n}\n" ;
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    #[allow(dead_code)]
+    type Boolean = bool;
+    #[allow(dead_code)]
+    type Float = f64;
+    #[allow(dead_code)]
+    type Int = i64;
+    #[allow(dead_code)]
+    type ID = String;
+    #[derive(Clone, Debug)]
+    pub enum ResponseCode {
+        OK,
+        UNAUTHORIZED,
+        INVALID_FEED_URL,
+        NOT_FOUND,
+        INVALID_SEARCH_QUERY,
+        QUOTA_EXCEEDED,
+        INVALID_CONFIRMATION_TOKEN,
+        INVALID_INPUT,
+        INTERNAL_ERROR,
+        Other(String),
+    }
+    impl ::serde::Serialize for ResponseCode {
+        fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            ser.serialize_str(match *self {
+                ResponseCode::OK => "OK",
+                ResponseCode::UNAUTHORIZED => "UNAUTHORIZED",
+                ResponseCode::INVALID_FEED_URL => "INVALID_FEED_URL",
+                ResponseCode::NOT_FOUND => "NOT_FOUND",
+                ResponseCode::INVALID_SEARCH_QUERY => "INVALID_SEARCH_QUERY",
+                ResponseCode::QUOTA_EXCEEDED => "QUOTA_EXCEEDED",
+                ResponseCode::INVALID_CONFIRMATION_TOKEN => "INVALID_CONFIRMATION_TOKEN",
+                ResponseCode::INVALID_INPUT => "INVALID_INPUT",
+                ResponseCode::INTERNAL_ERROR => "INTERNAL_ERROR",
+                ResponseCode::Other(ref s) => &s,
+            })
+        }
+    }
+    impl<'de> ::serde::Deserialize<'de> for ResponseCode {
+        fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let s: String = ::serde::Deserialize::deserialize(deserializer)?;
+            match s.as_str() {
+                "OK" => Ok(ResponseCode::OK),
+                "UNAUTHORIZED" => Ok(ResponseCode::UNAUTHORIZED),
+                "INVALID_FEED_URL" => Ok(ResponseCode::INVALID_FEED_URL),
+                "NOT_FOUND" => Ok(ResponseCode::NOT_FOUND),
+                "INVALID_SEARCH_QUERY" => Ok(ResponseCode::INVALID_SEARCH_QUERY),
+                "QUOTA_EXCEEDED" => Ok(ResponseCode::QUOTA_EXCEEDED),
+                "INVALID_CONFIRMATION_TOKEN" => Ok(ResponseCode::INVALID_CONFIRMATION_TOKEN),
+                "INVALID_INPUT" => Ok(ResponseCode::INVALID_INPUT),
+                "INTERNAL_ERROR" => Ok(ResponseCode::INTERNAL_ERROR),
+                _ => Ok(ResponseCode::Other(s)),
+            }
+        }
+    }
+    #[derive(Serialize, Debug)]
+    pub struct RevokeAccessTokenInput {
+        pub id: ID,
+    }
+    #[derive(Serialize, Debug)]
+    pub struct Variables {
+        pub input: RevokeAccessTokenInput,
+    }
+    impl Variables {}
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct ResponseData {
+        #[serde(rename = "revokeAccessToken")]
+        pub revoke_access_token: RevokeAccessTokenRevokeAccessToken,
+    }
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(tag = "__typename")]
+    pub enum RevokeAccessTokenRevokeAccessToken {
+        RevokeAccessTokenSuccess(RevokeAccessTokenRevokeAccessTokenOnRevokeAccessTokenSuccess),
+        RevokeAccessTokenError(RevokeAccessTokenRevokeAccessTokenOnRevokeAccessTokenError),
+    }
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct RevokeAccessTokenRevokeAccessTokenOnRevokeAccessTokenSuccess {
+        pub status: RevokeAccessTokenRevokeAccessTokenOnRevokeAccessTokenSuccessStatus,
+    }
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct RevokeAccessTokenRevokeAccessTokenOnRevokeAccessTokenSuccessStatus {
+        pub code: ResponseCode,
+    }
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct RevokeAccessTokenRevokeAccessTokenOnRevokeAccessTokenError {
+        pub status: RevokeAccessTokenRevokeAccessTokenOnRevokeAccessTokenErrorStatus,
+        pub message: String,
+    }
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct RevokeAccessTokenRevokeAccessTokenOnRevokeAccessTokenErrorStatus {
+        pub code: ResponseCode,
+    }
+}
+impl graphql_client::GraphQLQuery for RevokeAccessToken {
+    type Variables = revoke_access_token::Variables;
+    type ResponseData = revoke_access_token::ResponseData;
+    fn build_query(variables: Self::Variables) -> ::graphql_client::QueryBody<Self::Variables> {
+        graphql_client::QueryBody {
+            variables,
+            query: revoke_access_token::QUERY,
+            operation_name: revoke_access_token::OPERATION_NAME,
+        }
+    }
+}
@@ -21,13 +21,15 @@ pub mod env {
     pub const ENDPOINT: &str = env_key!("ENDPOINT");
     pub const LOG_PATH: &str = env_key!("LOG");
     pub const THEME: &str = env_key!("THEME");
+    pub const OIDC_PROVIDER: &str = env_key!("OIDC_PROVIDER");
 }
 
 pub mod client {
     pub const DEFAULT_TIMEOUT: &str = "30s";
     pub const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
 
-    /// Number of entries to fetch
+    /// Number of entries to fetch.
+    /// Must not exceed the entries page size of the api's free tier
     pub const INITIAL_ENTRIES_TO_FETCH: i64 = 200;
     /// Number of feeds to fetch
     pub const INITIAL_FEEDS_TO_FETCH: i64 = 50;
@@ -48,3 +50,14 @@ fn project_dirs() -> &'static ProjectDirs {
         ProjectDirs::from("ymgyt.io", "syndicationd", "synd").expect("Failed to get project dirs")
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn initial_entries_are_within_free_quota() {
+        let first = usize::try_from(client::INITIAL_ENTRIES_TO_FETCH).unwrap();
+        assert!(first <= synd_api::config::quota::DEFAULT_FREE_MAX_ENTRIES_PAGE_SIZE);
+    }
+}
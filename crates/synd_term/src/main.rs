@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use crossterm::event::EventStream;
 use synd_term::{
-    application::Application,
+    application::{Application, Authenticator},
     auth,
     cli::{self, Args},
     client::Client,
@@ -65,6 +65,7 @@ async fn main() {
         command,
         palette,
         timeout,
+        oidc_provider,
     } = cli::parse();
 
     let log = if command.is_some() { None } else { Some(log) };
@@ -75,15 +76,29 @@ async fn main() {
             cli::Command::Clear(clear) => clear.run(),
             cli::Command::Check(check) => check.run(endpoint).await,
             cli::Command::Export(export) => export.run(endpoint).await,
+            cli::Command::Token(token) => token.run(endpoint).await,
         };
 
         std::process::exit(exit_code);
     };
 
+    let authenticator = match oidc_provider {
+        Some(provider) => match provider.discover().await {
+            Ok(oidc) => Authenticator::new().with_oidc(provider.name, oidc),
+            Err(err) => {
+                error!("Discover OpenID Connect provider: {err}");
+                std::process::exit(1);
+            }
+        },
+        None => Authenticator::new(),
+    };
+
     let mut app = {
         let terminal = Terminal::new().expect("Failed to construct terminal");
         let client = Client::new(endpoint, timeout).expect("Failed to construct client");
-        Application::new(terminal, client).with_theme(Theme::with_palette(&palette.into()))
+        Application::new(terminal, client)
+            .with_theme(Theme::with_palette(&palette.into()))
+            .with_authenticator(authenticator)
     };
 
     if let Some(auth) = auth::credential_from_cache(app.jwt_service()).await {
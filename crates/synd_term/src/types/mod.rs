@@ -192,6 +192,58 @@ impl From<query::export_subscription::ExportSubscriptionOutputFeedsNodes> for Ex
     }
 }
 
+/// Personal access token
+#[derive(Debug, Clone)]
+pub struct AccessToken {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: Time,
+    pub expires_at: Option<Time>,
+}
+
+impl From<query::access_tokens::AccessTokensAccessTokens> for AccessToken {
+    fn from(v: query::access_tokens::AccessTokensAccessTokens) -> Self {
+        use query::access_tokens::AccessTokenScope;
+        Self {
+            id: v.id,
+            name: v.name,
+            scopes: v
+                .scopes
+                .into_iter()
+                .map(|scope| match scope {
+                    AccessTokenScope::READ => "read".into(),
+                    AccessTokenScope::WRITE => "write".into(),
+                    AccessTokenScope::Other(other) => other,
+                })
+                .collect(),
+            created_at: parse_time(v.created_at),
+            expires_at: v.expires_at.map(parse_time),
+        }
+    }
+}
+
+impl From<mutation::create_access_token::AccessToken> for AccessToken {
+    fn from(v: mutation::create_access_token::AccessToken) -> Self {
+        use mutation::create_access_token::AccessTokenScope;
+        Self {
+            id: v.id,
+            name: v.name,
+            scopes: v
+                .scopes
+                .into_iter()
+                .map(|scope| match scope {
+                    AccessTokenScope::READ => "read".into(),
+                    AccessTokenScope::WRITE => "write".into(),
+                    AccessTokenScope::Other(other) => other,
+                })
+                .collect(),
+            created_at: parse_time(v.created_at),
+            expires_at: v.expires_at.map(parse_time),
+        }
+    }
+}
+
 fn parse_time(t: impl AsRef<str>) -> Time {
     DateTime::parse_from_rfc3339(t.as_ref())
         .expect("invalid rfc3339 time")
@@ -96,6 +96,7 @@ impl Authentication {
                 .map(|provider| match provider {
                     AuthenticationProvider::Github => Text::from("󰊤 GitHub"),
                     AuthenticationProvider::Google => Text::from("󰊭 Google"),
+                    AuthenticationProvider::Oidc => Text::from("󰌆 OpenID Connect"),
                 })
                 .map(ListItem::new);
 
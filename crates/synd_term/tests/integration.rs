@@ -54,6 +54,7 @@ mod test {
                     ),
             ),
             google: DeviceFlow::new(provider::Google::new("dummy", "dummy")),
+            oidc: None,
         };
         let authenticator = Authenticator::new().with_device_flows(device_flows);
         let config = Config {
@@ -6,11 +6,18 @@ use ratatui::backend::TestBackend;
 use synd_api::{
     client::github::GithubClient,
     dependency::Dependency,
+    gql::PersistedQueries,
     monitor::Monitors,
-    repository::kvsd::KvsdClient,
-    serve::{auth::Authenticator, ServeOptions},
+    realtime::EntryBroadcaster,
+    repository::kvsd::{KvsdClient, PoolConfig},
+    search::SearchIndex,
+    serve::{
+        auth::Authenticator,
+        layer::{client_ip::TrustedProxies, rate_limit::RateLimit},
+        ServeOptions,
+    },
     shutdown::Shutdown,
-    usecase::{authorize::Authorizer, MakeUsecase, Runtime},
+    usecase::{authorize::Authorizer, DeletionTokens, MakeUsecase, Runtime},
 };
 use synd_feed::feed::{cache::CacheLayer, parser::FeedService};
 use synd_term::terminal::Terminal;
@@ -26,14 +33,29 @@ pub async fn serve_api(mock_port: u16, api_port: u16) -> anyhow::Result<()> {
     let github_endpoint: &'static str =
         format!("http://localhost:{mock_port}/github/graphql").leak();
     let github_client = GithubClient::new()?.with_endpoint(github_endpoint);
-    let authenticator = Authenticator::new()?.with_client(github_client);
 
-    let kvsd_client = run_kvsd().await.map(KvsdClient::new)?;
+    run_kvsd().await?;
+    let kvsd_client = KvsdClient::connect(
+        "localhost",
+        47379,
+        "test".into(),
+        "test".into(),
+        PoolConfig::default(),
+    )
+    .await
+    .map(Arc::new)?;
+    let authenticator = Authenticator::new(kvsd_client.clone())?.with_client(github_client);
     let feed_service = FeedService::new("synd_term_test", 1024 * 1024);
     let feed_service = CacheLayer::new(feed_service);
     let make_usecase = MakeUsecase {
-        subscription_repo: Arc::new(kvsd_client),
+        subscription_repo: kvsd_client.clone(),
+        user_repo: kvsd_client.clone(),
         fetch_feed: Arc::new(feed_service),
+        search_index: Arc::new(SearchIndex::in_memory()?),
+        archive_repo: kvsd_client,
+        entry_retention: Duration::from_secs(60 * 60 * 24 * 180),
+        broadcaster: EntryBroadcaster::new(),
+        deletion_tokens: DeletionTokens::new(),
     };
     let authorizer = Authorizer::new();
     let runtime = Runtime::new(make_usecase, authorizer);
@@ -56,13 +78,23 @@ pub async fn serve_api(mock_port: u16, api_port: u16) -> anyhow::Result<()> {
         timeout: Duration::from_secs(10),
         body_limit_bytes: 1024 * 2,
         concurrency_limit: 100,
+        client_ip_rate_limit: RateLimit {
+            requests_per_minute: 0,
+            burst: 0,
+        },
+        principal_rate_limit: RateLimit {
+            requests_per_minute: 0,
+            burst: 0,
+        },
+        trusted_proxies: TrustedProxies::default(),
     };
     let dep = Dependency {
         authenticator,
         runtime,
-        tls_config,
+        tls_config: Some(tls_config),
         serve_options,
         monitors: Monitors::new(),
+        persisted_queries: PersistedQueries::default(),
     };
     let listener = TcpListener::bind(("localhost", api_port)).await?;
 
@@ -70,6 +70,7 @@ async fn github_graphql_viewer(
     let response = serde_json::json!({
         "data": {
             "viewer": {
+                "databaseId": 1,
                 "email": dummy_email
             }
         }